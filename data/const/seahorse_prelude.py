@@ -800,6 +800,19 @@ class Array(Generic[T, N]):
         Like Python's native list type, performs wrapping indexing - if you pass in -1, you'll get the last element of the array.
         """
 
+class Str(str, Generic[N]):
+    """
+    A string with a max length of N bytes, used when storing strings in accounts. Otherwise exactly the same as `str`.
+
+    Like `List[T, N]`, giving a string field a max length lets Seahorse calculate exactly how much space its account needs. Example:
+
+    ```
+    class Profile(Account):
+        name: Str[32]
+        scores: List[u64, 10]
+    ```
+    """
+
 def array(*elements: T) -> Array[T, N]:
     """
     Create an array from a variadic list of elements. Example:
@@ -817,7 +830,7 @@ class Enum:
     """
     A type that can have one of multiple named values.
 
-    Note that unlike Rust enums, these cannot contain any data (other than the variant itself). Example:
+    Variants can be plain values (`NAME = 1`), carry data (`NAME: T` or `NAME: Tuple[T, U]`), or carry named fields (a nested `@dataclass` class). Example:
 
     ```
     class MyEnum(Enum):
@@ -825,11 +838,45 @@ class Enum:
         TWO = 2
         THREE = 3
 
+    class Order(Enum):
+        Empty = 0
+        Pending: Tuple[u64, Pubkey]
+
+        @dataclass
+        class Filled:
+            amount: u64
+            price: u64
+
     @instruction
-    def use_enum(code: MyEnum):
+    def use_enum(code: MyEnum, order: Order):
         if code == MyEnum.ONE:
             print(1)
         # ...
+
+        # Check which variant an enum holds with isinstance, then read its data
+        if isinstance(order, Order.Pending):
+            amount, owner = order.Pending
+        order = Order.Filled(amount=1, price=2)
+    ```
+    """
+
+class ErrorCode:
+    """
+    A set of custom errors that your program can fail with.
+
+    Each variant is assigned a message, and becomes an Anchor error code (starting at 6000, in the order the variants are declared) that clients can see in your program's IDL. Errors can be used with `assert` or `raise`. Example:
+
+    ```
+    class MyError(ErrorCode):
+        InsufficientFunds = 'Not enough funds for this withdrawal'
+        Unauthorized = 'Only the owner can do this'
+
+    @instruction
+    def withdraw(owner: Signer, vault: Vault, amount: u64):
+        if owner.key() != vault.owner:
+            raise MyError.Unauthorized
+        assert vault.balance >= amount, MyError.InsufficientFunds
+        # ...
     ```
     """
 
@@ -856,6 +903,13 @@ class AccountWithKey:
     def key(self) -> Pubkey:
         """Get this account's key."""
 
+    def signer_seeds(self) -> List[Any]:
+        """
+        Get the signer seeds (including the bump) of this account, for signing CPIs on its behalf. Only available for PDAs whose seeds are known, either from an `init` call in the same instruction or a `seeds` constraint.
+
+        @returns: The list of signer seeds.
+        """
+
 class Account(AccountWithKey):
     """User-defined Solana account."""
 
@@ -867,6 +921,22 @@ class Account(AccountWithKey):
         @param amount: The amount (in lamports, not SOL) to transfer.
         """
 
+    def close(self, to: AccountWithKey):
+        """
+        Close this account at the end of the instruction, sending its lamports (rent) to another account. The close must be unconditional, and the account can't be used afterwards.
+
+        @param to: The account that receives the lamports. Must be another account of the instruction.
+        """
+
+    def realloc(self, space: u64, payer: AccountWithKey, zero: bool = False):
+        """
+        Resize this account before the instruction runs, so that its lists and strings can grow. The payer covers any extra rent, and gets the rent back if the account shrinks. The realloc must be unconditional.
+
+        @param space: The new size of the account in bytes.
+        @param payer: The account that pays for the extra rent. Must be another account of the instruction.
+        @param zero: (Optional) whether to zero out the bytes that the account gains.
+        """
+
 class Event:
     """Anchor event that clients can listen for"""
 
@@ -875,6 +945,11 @@ class Event:
         Emit the event to the blockchain
         """
 
+    def emit_cpi(self):
+        """
+        Emit the event in the data of a CPI to this program, which RPCs don't truncate like they do program logs. Can only be used directly inside an @instruction.
+        """
+
 class Signer(AccountWithKey):
     """Instruction signer."""
 
@@ -886,10 +961,52 @@ class Signer(AccountWithKey):
         @param amount: The amount (in lamports, not SOL) to transfer.
         """
 
+    def create_account(self, account: AccountWithKey, lamports: u64, space: u64, owner: Pubkey, signer: List[Any] = None):
+        """
+        Create a new account with the system program, paid for by this signer.
+
+        @param account: The account to create. Must be an instruction signer or a PDA with known seeds.
+        @param lamports: How many lamports to fund the new account with.
+        @param space: The size of the new account's data, in bytes.
+        @param owner: The program that will own the new account.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def create_account_with_seed(self, account: AccountWithKey, base: AccountWithKey, seed: str, lamports: u64, space: u64, owner: Pubkey, signer: List[Any] = None):
+        """
+        Create a new account at an address derived from a base key and a seed, paid for by this signer.
+
+        @param account: The account to create. Its address must be derived from `base`, `seed` and `owner`.
+        @param base: The base account of the derived address. Must be an instruction signer or a PDA with known seeds.
+        @param seed: The seed of the derived address.
+        @param lamports: How many lamports to fund the new account with.
+        @param space: The size of the new account's data, in bytes.
+        @param owner: The program that will own the new account.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def allocate(self, account: AccountWithKey, space: u64, signer: List[Any] = None):
+        """
+        Allocate space for a system-owned account's data.
+
+        @param account: The account to allocate. Must be an instruction signer or a PDA with known seeds.
+        @param space: The size of the account's data, in bytes.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def assign(self, account: AccountWithKey, owner: Pubkey, signer: List[Any] = None):
+        """
+        Assign a system-owned account to a new owner program.
+
+        @param account: The account to assign. Must be an instruction signer or a PDA with known seeds.
+        @param owner: The program that will own the account.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
 class Empty(Generic[T]):
     """An account that needs to be initialized."""
 
-    def init(self, payer: Signer, seeds: List[Any] = None, mint: 'TokenMint' = None, decimals: u8 = None, authority: AccountWithKey = None, associated: bool = False, space: u64 = None, padding: u64 = None)  -> T:
+    def init(self, payer: Signer, seeds: List[Any] = None, mint: Union['TokenMint', 'Token2022Mint'] = None, decimals: u8 = None, authority: AccountWithKey = None, associated: bool = False, space: u64 = None, padding: u64 = None)  -> T:
         """
         Initialize the account.
         
@@ -993,6 +1110,57 @@ class TokenAccount(AccountWithKey):
         @param signer: (Optional) seeds for the signature of a PDA.
         """
 
+    def close(self, to: AccountWithKey, authority: AccountWithKey, signer: List[Any] = None):
+        """
+        Close this (empty) token account, sending its lamports (rent) to another account. The account can't be used afterwards.
+
+        @param to: The account that receives the lamports.
+        @param authority: The account that owns this TokenAccount. Must be an instruction signer or the account given by the `signer` param.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def transfer_checked(self, authority: AccountWithKey, to: 'TokenAccount', amount: u64, mint: 'TokenMint', signer: List[Any] = None):
+        """
+        Transfer funds from this token account to another, checking the mint and its decimals.
+
+        @param authority: The account that owns this TokenAccount. Must be an instruction signer or the account given by the `signer` param.
+        @param to: The recipient TokenAccount.
+        @param amount: How much (in *native* token units) to transfer.
+        @param mint: The mint of both token accounts.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def approve(self, authority: AccountWithKey, delegate: AccountWithKey, amount: u64, signer: List[Any] = None):
+        """
+        Let a delegate transfer or burn some of the tokens in this account.
+
+        @param authority: The account that owns this TokenAccount. Must be an instruction signer or the account given by the `signer` param.
+        @param delegate: The account that may use the tokens.
+        @param amount: How much (in *native* token units) the delegate may use.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def revoke(self, authority: AccountWithKey, signer: List[Any] = None):
+        """
+        Remove the delegate of this token account.
+
+        @param authority: The account that owns this TokenAccount. Must be an instruction signer or the account given by the `signer` param.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def set_authority(self, authority: AccountWithKey, new_authority: Optional[Pubkey], authority_type: str = 'account_owner', signer: List[Any] = None):
+        """
+        Change one of the authorities of this token account.
+
+        @param authority: The current authority. Must be an instruction signer or the account given by the `signer` param.
+        @param new_authority: The new authority, or None to remove the authority.
+        @param authority_type: Which authority to change, either 'account_owner' or 'close_account'. Must be a string literal.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def sync_native(self):
+        """Sync the amount of this wrapped SOL token account with its lamports, after lamports were transferred to it."""
+
 class TokenMint(AccountWithKey):
     """SPL token mint."""
 
@@ -1028,6 +1196,189 @@ class TokenMint(AccountWithKey):
         @param signer: (Optional) seeds for the signature of a PDA.
         """
 
+    def freeze(self, authority: AccountWithKey, account: TokenAccount, signer: List[Any] = None):
+        """
+        Freeze a token account of this mint, so that its tokens can't be used.
+
+        @param authority: The freeze authority of this TokenMint. Must be an instruction signer or the account given by the `signer` param.
+        @param account: The TokenAccount to freeze.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def thaw(self, authority: AccountWithKey, account: TokenAccount, signer: List[Any] = None):
+        """
+        Thaw a frozen token account of this mint.
+
+        @param authority: The freeze authority of this TokenMint. Must be an instruction signer or the account given by the `signer` param.
+        @param account: The TokenAccount to thaw.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def set_authority(self, authority: AccountWithKey, new_authority: Optional[Pubkey], authority_type: str = 'mint_tokens', signer: List[Any] = None):
+        """
+        Change one of the authorities of this token mint.
+
+        @param authority: The current authority. Must be an instruction signer or the account given by the `signer` param.
+        @param new_authority: The new authority, or None to remove the authority.
+        @param authority_type: Which authority to change, either 'mint_tokens' or 'freeze_account'. Must be a string literal.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+
+class Token2022Account(AccountWithKey):
+    """Token-2022 token account."""
+
+    def authority(self) -> Pubkey:
+        """Get the owner of this token account."""
+
+    def amount(self) -> u64:
+        """Get the amount of token stored in this account."""
+
+    def mint(self) -> Pubkey:
+        """Get the mint that this token account corresponds to."""
+
+    def transfer(self, authority: AccountWithKey, to: 'Token2022Account', amount: u64, mint: 'Token2022Mint', signer: List[Any] = None):
+        """
+        Transfer funds from this token account to another. Token-2022 transfers are always checked against the mint.
+
+        @param authority: The account that owns this Token2022Account. Must be an instruction signer or the account given by the `signer` param.
+        @param to: The recipient Token2022Account.
+        @param amount: How much (in *native* token units) to transfer.
+        @param mint: The mint of both token accounts.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def close(self, to: AccountWithKey, authority: AccountWithKey, signer: List[Any] = None):
+        """
+        Close this (empty) token account, sending its lamports (rent) to another account. The account can't be used afterwards.
+
+        @param to: The account that receives the lamports.
+        @param authority: The account that owns this Token2022Account. Must be an instruction signer or the account given by the `signer` param.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def transfer_checked(self, authority: AccountWithKey, to: 'Token2022Account', amount: u64, mint: 'Token2022Mint', signer: List[Any] = None):
+        """
+        Transfer funds from this token account to another, checking the mint and its decimals.
+
+        @param authority: The account that owns this Token2022Account. Must be an instruction signer or the account given by the `signer` param.
+        @param to: The recipient Token2022Account.
+        @param amount: How much (in *native* token units) to transfer.
+        @param mint: The mint of both token accounts.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def approve(self, authority: AccountWithKey, delegate: AccountWithKey, amount: u64, signer: List[Any] = None):
+        """
+        Let a delegate transfer or burn some of the tokens in this account.
+
+        @param authority: The account that owns this Token2022Account. Must be an instruction signer or the account given by the `signer` param.
+        @param delegate: The account that may use the tokens.
+        @param amount: How much (in *native* token units) the delegate may use.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def revoke(self, authority: AccountWithKey, signer: List[Any] = None):
+        """
+        Remove the delegate of this token account.
+
+        @param authority: The account that owns this Token2022Account. Must be an instruction signer or the account given by the `signer` param.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def set_authority(self, authority: AccountWithKey, new_authority: Optional[Pubkey], authority_type: str = 'account_owner', signer: List[Any] = None):
+        """
+        Change one of the authorities of this token account.
+
+        @param authority: The current authority. Must be an instruction signer or the account given by the `signer` param.
+        @param new_authority: The new authority, or None to remove the authority.
+        @param authority_type: Which authority to change, either 'account_owner' or 'close_account'. Must be a string literal.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def sync_native(self):
+        """Sync the amount of this wrapped SOL token account with its lamports, after lamports were transferred to it."""
+
+class Token2022Mint(AccountWithKey):
+    """Token-2022 token mint."""
+
+    def authority(self) -> Pubkey:
+        """Get the owner of this token mint."""
+
+    def freeze_authority(self) -> Pubkey:
+        """Get the freeze authority of this token mint."""
+
+    def decimals(self) -> u8:
+        """Get the number of decimals for this token."""
+
+    def supply(self) -> u64:
+        """Get the amount of this token that exists."""
+
+    def transfer_fee_basis_points(self) -> u16:
+        """Get the transfer fee of this mint for the current epoch, in basis points. The mint must have the transfer fee extension."""
+
+    def maximum_transfer_fee(self) -> u64:
+        """Get the maximum transfer fee of this mint for the current epoch. The mint must have the transfer fee extension."""
+
+    def transfer_fee(self, amount: u64) -> u64:
+        """
+        Calculate the fee for transferring some tokens of this mint in the current epoch. The mint must have the transfer fee extension.
+
+        @param amount: How much (in *native* token units) is transferred.
+        @returns: The fee (in *native* token units).
+        """
+
+    def metadata_pointer(self) -> Optional[Pubkey]:
+        """Get the address of this mint's metadata, or None if it isn't set. The mint must have the metadata pointer extension."""
+
+    def mint(self, authority: AccountWithKey, to: Token2022Account, amount: u64, signer: List[Any] = None):
+        """
+        Mint new tokens to a token account.
+
+        @param authority: The account that owns this Token2022Mint. Must be an instruction signer or the account given by the `signer` param.
+        @param to: The recipient Token2022Account.
+        @param amount: How much (in *native* token units) to mint.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def burn(self, authority: AccountWithKey, holder: Token2022Account, amount: u64, signer: List[Any] = None):
+        """
+        Burn tokens from a token account.
+
+        @param authority: The account that owns the `holder` Token2022Account. Must be an instruction signer or the account given by the `signer` param.
+        @param holder: The Token2022Account to burn from.
+        @param amount: How much (in *native* token units) to burn.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def freeze(self, authority: AccountWithKey, account: Token2022Account, signer: List[Any] = None):
+        """
+        Freeze a token account of this mint, so that its tokens can't be used.
+
+        @param authority: The freeze authority of this Token2022Mint. Must be an instruction signer or the account given by the `signer` param.
+        @param account: The Token2022Account to freeze.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def thaw(self, authority: AccountWithKey, account: Token2022Account, signer: List[Any] = None):
+        """
+        Thaw a frozen token account of this mint.
+
+        @param authority: The freeze authority of this Token2022Mint. Must be an instruction signer or the account given by the `signer` param.
+        @param account: The Token2022Account to thaw.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
+    def set_authority(self, authority: AccountWithKey, new_authority: Optional[Pubkey], authority_type: str = 'mint_tokens', signer: List[Any] = None):
+        """
+        Change one of the authorities of this token mint.
+
+        @param authority: The current authority. Must be an instruction signer or the account given by the `signer` param.
+        @param new_authority: The new authority, or None to remove the authority.
+        @param authority_type: Which authority to change, either 'mint_tokens' or 'freeze_account'. Must be a string literal.
+        @param signer: (Optional) seeds for the signature of a PDA.
+        """
+
 
 # ================
 # Helper functions
@@ -1045,6 +1396,9 @@ def instruction(function: Callable[..., None]) -> Callable[..., None]:
 def dataclass(function: Callable[..., None]) -> Callable[..., None]:
     """Decorator to create an automatic default class constructor."""
 
+def zero_copy(cls: T) -> T:
+    """Decorator to make an account zero-copy, so that it's used in place instead of being (de)serialized. Fields can only be numbers, Pubkeys and Arrays of them."""
+
 def int_bytes(n: Any, be: bool = False) -> List[u8]:
     """
     Convenience method to turn an integer type into a little-endian (by default) list of bytes.
@@ -1053,10 +1407,77 @@ def int_bytes(n: Any, be: bool = False) -> List[u8]:
     @param be: Whether you want the conversion to be big-endian - defaults to false.
     """
 
-def size(ob: str) -> u64:
+def has_one(field: str, error: ErrorCode = None) -> Any:
+    """
+    Account constraint that checks a Pubkey field of an account against the key of another account. Use it as the default value of an instruction's account param:
+
+    ```
+    @instruction
+    def withdraw(owner: Signer, vault: Vault = has_one('owner')):
+    ```
+
+    @param field: The name of the Pubkey field. The instruction must have an account with the same name.
+    @param error: (Optional) the error to fail with if the check fails.
+    """
+
+def constraint(cond: bool, error: ErrorCode = None) -> Any:
+    """
+    Account constraint that checks an arbitrary condition before the instruction runs. The condition can use any of the instruction's params. Use it as the default value of an instruction's account param:
+
+    ```
+    @instruction
+    def withdraw(amount: u64, vault: Vault = constraint(vault.balance >= amount)):
+    ```
+
+    @param cond: The condition that must hold.
+    @param error: (Optional) the error to fail with if the check fails.
+    """
+
+def address(address: Pubkey, error: ErrorCode = None) -> Any:
+    """
+    Account constraint that checks the key of an account. Use it as the default value of an instruction's account param:
+
+    ```
+    @instruction
+    def configure(config: Config, admin: Signer = address(config.admin)):
+    ```
+
+    @param address: The key that the account must have.
+    @param error: (Optional) the error to fail with if the check fails.
+    """
+
+def realloc(space: u64, payer: AccountWithKey, zero: bool = False) -> Any:
+    """
+    Account constraint that resizes an account before the instruction runs, so that its lists and strings can grow. Use it as the default value of an instruction's account param:
+
+    ```
+    @instruction
+    def add_member(owner: Signer, member: Pubkey, roster: Roster = realloc(8 + 32 + 4 + 32 * (len(roster.members) + 1), owner)):
+    ```
+
+    @param space: The new size of the account in bytes.
+    @param payer: The account that pays for the extra rent. Must be another account of the instruction.
+    @param zero: (Optional) whether to zero out the bytes that the account gains.
+    """
+
+def seeds(seeds: List[Any], bump: u8 = None) -> Any:
+    """
+    Account constraint that checks that an account is the PDA with the given seeds. Use it as the default value of an instruction's account param:
+
+    ```
+    @instruction
+    def deposit(owner: Signer, vault: Vault = seeds(['vault', owner], bump = vault.bump)):
+        ...
+    ```
+
+    @param seeds: The seeds of the PDA. Must be a list literal.
+    @param bump: (Optional) the bump of the PDA, usually stored in the account when it was initialized. Without it, Anchor has to search for the bump.
+    """
+
+def size(ob: Any) -> u64:
     """
     Get the size of an object in bytes.
-    Currently this is only supported for strings.
+    Currently this is only supported for strings and account types. The size of an account type is the space that it needs, including its 8-byte discriminator.
     
     @param ob: The object to get the size of.
     """
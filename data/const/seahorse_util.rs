@@ -9,7 +9,7 @@ use crate::dot::program::*;
 use std::{cell::RefCell, rc::Rc};
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     ops::{Deref, Index, IndexMut},
 };
@@ -51,6 +51,36 @@ impl<T: Default> Default for Mutable<T> {
     }
 }
 
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
 pub trait IndexWrapped {
     type Output;
 
@@ -113,6 +143,167 @@ impl<T, const N: usize> IndexWrappedMut for [T; N] {
     }
 }
 
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
 #[derive(Clone)]
 pub struct Empty<T: Clone> {
     pub account: T,
@@ -156,6 +347,42 @@ pub struct CpiAccount<'info> {
     pub seeds: Option<Vec<Vec<u8>>>,
 }
 
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
 #[macro_export]
 macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
         macro_rules! $name {() => {
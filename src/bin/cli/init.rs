@@ -13,7 +13,7 @@ use std::{
     path::Path,
     process::Command,
 };
-use toml_edit::{Document, Formatted, InlineTable, Item, Table, Value};
+use toml_edit::{Array, Document, Formatted, InlineTable, Item, Table, Value};
 
 #[derive(Args, Debug)]
 pub struct InitArgs {
@@ -220,6 +220,25 @@ pub fn init(args: InitArgs) -> Result<(), Box<dyn Error>> {
             pyth.insert("optional", Value::Boolean(Formatted::new(true)));
             cargo["dependencies"]["pyth-sdk-solana"] = Item::Value(Value::InlineTable(pyth));
 
+            // Zero-copy accounts derive bytemuck's traits, which need const generics for arrays of
+            // any length
+            let mut bytemuck = InlineTable::new();
+            bytemuck.insert(
+                "version",
+                Value::String(Formatted::new("1.4.0".to_string())),
+            );
+            let mut features = Array::new();
+            features.push("derive");
+            features.push("min_const_generics");
+            bytemuck.insert("features", Value::Array(features));
+            cargo["dependencies"]["bytemuck"] = Item::Value(Value::InlineTable(bytemuck));
+
+            // Emitting events with CPIs needs Anchor's event-cpi feature, which `seahorse build`
+            // turns on for programs that use it
+            let mut event_cpi = Array::new();
+            event_cpi.push("anchor-lang/event-cpi");
+            cargo["features"]["event-cpi"] = Item::Value(Value::Array(event_cpi));
+
             File::create(&cargo_path)?.write_all(cargo.to_string().as_bytes())?;
 
             // Add Anchor seeds feature
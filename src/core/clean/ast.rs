@@ -57,6 +57,11 @@ pub enum ClassDefStatementObj {
         value: Option<Expression>,
     },
     MethodDef(FunctionDef),
+    ClassDef {
+        name: String,
+        body: Vec<ClassDefStatement>,
+        decorator_list: Vec<Expression>,
+    },
 }
 
 /// A function definition.
@@ -84,6 +89,7 @@ pub type Param = Located<ParamObj>;
 pub struct ParamObj {
     pub arg: String,
     pub annotation: TyExpression,
+    pub default: Option<Expression>,
 }
 
 /// A computable value.
@@ -99,6 +105,12 @@ pub enum ExpressionObj {
         value: Box<Expression>,
         index: Box<Expression>,
     },
+    Slice {
+        value: Box<Expression>,
+        start: Option<Box<Expression>>,
+        stop: Option<Box<Expression>>,
+        step: Option<Box<Expression>>,
+    },
     UnOp {
         op: UnaryOperator,
         value: Box<Expression>,
@@ -116,10 +128,15 @@ pub enum ExpressionObj {
         body: Box<Expression>,
         orelse: Box<Expression>,
     },
+    Lambda {
+        params: Vec<String>,
+        body: Box<Expression>,
+    },
     Int(i128),
     Float(f64),
     List(Vec<Expression>),
     Tuple(Vec<Expression>),
+    Dict(Vec<(Expression, Expression)>),
     Comprehension {
         element: Box<Expression>,
         parts: Vec<ComprehensionPart>,
@@ -135,6 +152,12 @@ pub enum ExpressionObj {
     Iter {
         value: Box<Expression>,
     },
+    // A meta expression that evaluates `value` once and makes it available to `body` as `name`
+    Bind {
+        name: String,
+        value: Box<Expression>,
+        body: Box<Expression>,
+    },
 }
 
 /// Arguments to a function call.
@@ -160,7 +183,44 @@ pub enum ComprehensionPart {
 #[derive(Clone, Debug)]
 pub enum FStrPart {
     Str(String),
-    ExpressionObj(Expression),
+    ExpressionObj(Expression, FormatSpec),
+}
+
+/// Formatting options for an f-string component, parsed from a conversion (`!r`) and Python's
+/// format spec mini-language (`:>10.2f`).
+#[derive(Clone, Debug, Default)]
+pub struct FormatSpec {
+    /// The original spec, for error messages.
+    pub src: String,
+    pub repr: bool,
+    pub fill: Option<char>,
+    pub align: Option<FormatAlign>,
+    pub sign: bool,
+    pub alternate: bool,
+    pub zero: bool,
+    pub width: Option<usize>,
+    pub grouping: Option<char>,
+    pub precision: Option<usize>,
+    pub kind: Option<FormatKind>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FormatAlign {
+    Left,
+    Right,
+    Center,
+}
+
+/// The presentation type of a format spec.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FormatKind {
+    Str,
+    Int,
+    Float,
+    Hex,
+    UpperHex,
+    Binary,
+    Octal,
 }
 
 /// An expression that resolves to a type.
@@ -189,6 +249,9 @@ pub enum StatementObj {
         // TODO what to allow here
         msg: Option<Expression>,
     },
+    Raise {
+        error: Expression,
+    },
     Assign {
         target: Expression,
         value: Expression,
@@ -214,12 +277,51 @@ pub enum StatementObj {
     While {
         test: Expression,
         body: Vec<Statement>,
+        orelse: Option<Vec<Statement>>,
     },
     For {
         target: Expression,
         iter: Expression,
         body: Vec<Statement>,
+        orelse: Option<Vec<Statement>>,
+    },
+    Delete {
+        target: Expression,
+    },
+    Match {
+        subject: Expression,
+        cases: Vec<MatchCase>,
+    },
+}
+
+/// A single `case` block in a match statement.
+#[derive(Clone, Debug)]
+pub struct MatchCase {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Vec<Statement>,
+}
+
+/// A pattern that a value is matched against.
+pub type Pattern = Located<PatternObj>;
+#[derive(Clone, Debug)]
+pub enum PatternObj {
+    /// `_`
+    Wildcard,
+    /// A bare name, which binds the matched value to a new variable.
+    Capture(String),
+    /// An int, bool or string literal.
+    Literal(Expression),
+    /// A dotted name, like `MyEnum.Variant`.
+    Value(Expression),
+    /// A class pattern, like `MyEnum.Variant(x, y=0)`.
+    Class {
+        cls: Expression,
+        args: Vec<Pattern>,
+        kwargs: Vec<(String, Pattern)>,
     },
+    Tuple(Vec<Pattern>),
+    Or(Vec<Pattern>),
 }
 
 /// An operator in a binary operation. Note that Python splits these into three categories
@@ -11,6 +11,7 @@ use ast::*;
 enum Error {
     ImportAlias,
     ClassDefWithKeywords,
+    NestedClassWithBases,
     InvalidConstant,
     NonconstantConstant,
     Async,
@@ -19,27 +20,30 @@ enum Error {
     ArbitraryTyExpression,
     ArbitraryParams,
     ParamWithoutType,
-    CompareChain,
     BigInteger,
     ExpressionComplexNumber,
     ComprehensionNotList,
     ExpressionYield,
-    ExpressionDict,
+    DictUnpack,
     ExpressionSet,
     ExpressionStarred,
     ExpressionSlice,
+    SliceAssign,
     ExpressionBytes,
-    ExpressionLambda,
+    LambdaParams,
     ExpressionNamed,
     ExpressionEllipsis,
-    FStrWithSpec,
-    WhileWithOrelse,
-    ForWithOrelse,
+    FStrAsciiConversion,
+    FStrNestedSpec,
+    FStrInvalidSpec(String),
+    FStrUnsupportedSpec(String, &'static str),
     StatementImport,
-    StatementDelete,
+    DeleteMultiple,
     StatementGlobal,
     StatementNonlocal,
     StatementWith,
+    MatchArbitraryStatement,
+    UnsupportedPattern,
     StatementRaise,
     StatementTry,
     StatementClassDef,
@@ -56,6 +60,7 @@ impl Error {
         match self {
             Self::ImportAlias => CoreError::make_raw("imports aliases are currently not supported", ""),
             Self::ClassDefWithKeywords => CoreError::make_raw("class definition with keywords", ""),
+            Self::NestedClassWithBases => CoreError::make_raw("nested classes may not have base classes", ""),
             Self::InvalidConstant => CoreError::make_raw(
                 "invalid constant",
                 concat!(
@@ -91,10 +96,6 @@ impl Error {
                 "param without type",
                 "Help: all function params must have a type annotation."
             ),
-            Self::CompareChain => CoreError::make_raw(
-                "comparison chains are unsupported",
-                "Help: you can convert this chain into several individual comparisons that are `and`'d together."
-            ),
             Self::BigInteger => CoreError::make_raw(
                 "integer too large",
                 "Help: for compilation purposes, integers need to be able to fit into a Rust i128 (roughly the range [-1.7e38, 1.7e38])"
@@ -111,8 +112,8 @@ impl Error {
                 "yield expressions are not supported",
                 ""
             ),
-            Self::ExpressionDict => CoreError::make_raw(
-                "dicts are not supported",
+            Self::DictUnpack => CoreError::make_raw(
+                "unpacking inside of a dict literal is not supported",
                 ""
             ),
             Self::ExpressionSet => CoreError::make_raw(
@@ -124,16 +125,20 @@ impl Error {
                 ""
             ),
             Self::ExpressionSlice => CoreError::make_raw(
-                "slices are not supported",
+                "multi-dimensional slices are not supported",
+                "Help: slices may only be used as the whole index, like `xs[a:b:c]`."
+            ),
+            Self::SliceAssign => CoreError::make_raw(
+                "assigning to a slice is not supported",
                 ""
             ),
             Self::ExpressionBytes => CoreError::make_raw(
                 "byte-strings are not supported",
                 ""
             ),
-            Self::ExpressionLambda => CoreError::make_raw(
-                "lambda functions are not supported",
-                ""
+            Self::LambdaParams => CoreError::make_raw(
+                "unsupported lambda params",
+                "Help: lambda params may only be plain positional arguments, without defaults or type annotations."
             ),
             Self::ExpressionNamed => CoreError::make_raw(
                 "named expressions are not supported",
@@ -143,25 +148,29 @@ impl Error {
                 "ellipsis expressions are not supported",
                 ""
             ),
-            Self::FStrWithSpec => CoreError::make_raw(
-                "f-string formatting options are not supported",
-                ""
+            Self::FStrAsciiConversion => CoreError::make_raw(
+                "the !a f-string conversion is not supported",
+                "Help: try !r instead."
             ),
-            Self::WhileWithOrelse => CoreError::make_raw(
-                "`while` statements with else blocks are not supported",
-                ""
+            Self::FStrNestedSpec => CoreError::make_raw(
+                "f-string format specs may not contain expressions",
+                "Help: format specs must be written out in full, like f\"{x:>10}\"."
             ),
-            Self::ForWithOrelse => CoreError::make_raw(
-                "`for` statements with else blocks are not supported",
+            Self::FStrInvalidSpec(spec) => CoreError::make_raw(
+                format!("invalid f-string format spec \"{}\"", spec),
                 ""
             ),
+            Self::FStrUnsupportedSpec(spec, help) => CoreError::make_raw(
+                format!("unsupported f-string format spec \"{}\"", spec),
+                help
+            ),
             Self::StatementImport => CoreError::make_raw(
                 "import statements must be top-level",
                 "Help: try moving this definition to outside of this function."
             ),
-            Self::StatementDelete => CoreError::make_raw(
-                "`del` statements are unsupported",
-                ""
+            Self::DeleteMultiple => CoreError::make_raw(
+                "`del` statements with multiple targets are unsupported",
+                "Help: try splitting this into one `del` statement per target."
             ),
             Self::StatementGlobal => CoreError::make_raw(
                 "`global` statements are unsupported",
@@ -175,10 +184,21 @@ impl Error {
                 "`with` statements are unsupported",
                 ""
             ),
-            Self::StatementRaise => CoreError::make_raw(
-                "`raise` statements are unsupported",
+            Self::MatchArbitraryStatement => CoreError::make_raw(
+                "match statements may only contain `case` blocks",
                 ""
             ),
+            Self::UnsupportedPattern => CoreError::make_raw(
+                "unsupported pattern",
+                concat!(
+                    "Help: patterns may be enum variants (`MyEnum.A`, `MyEnum.B(x, y)`), int/bool/string literals,\n",
+                    "tuples, names that capture the value, or `_`, optionally combined with `|`."
+                )
+            ),
+            Self::StatementRaise => CoreError::make_raw(
+                "unsupported `raise` statement",
+                "Help: you can only raise an error code, like this:\n\n    raise MyError.SomethingWentWrong"
+            ),
             Self::StatementTry => CoreError::make_raw(
                 "`try` statements are unsupported",
                 ""
@@ -456,6 +476,31 @@ impl TryInto<ClassDefStatement> for WithSrc<py::Statement> {
                     }))
                 }
             }
+            py::StatementType::ClassDef {
+                name,
+                body,
+                bases,
+                keywords,
+                decorator_list,
+            } => {
+                if !keywords.is_empty() {
+                    Err(Error::ClassDefWithKeywords)
+                } else if !bases.is_empty() {
+                    Err(Error::NestedClassWithBases)
+                } else {
+                    Ok(ClassDefStatementObj::ClassDef {
+                        name,
+                        body: body
+                            .into_iter()
+                            .map(|statement| WithSrc::new(&src, statement).try_into())
+                            .collect::<Result<Vec<_>, CoreError>>()?,
+                        decorator_list: decorator_list
+                            .into_iter()
+                            .map(|dec| WithSrc::new(&src, dec).try_into())
+                            .collect::<Result<Vec<_>, CoreError>>()?,
+                    })
+                }
+            }
             _ => Err(Error::ArbitraryClassDefStatement),
         }
         .map(|ok| Located(location.clone(), ok))
@@ -473,7 +518,6 @@ impl TryInto<Params> for WithSrc<py::Parameters> {
             || obj.kwonlyargs.len() > 0
             || obj.vararg != py::Varargs::None
             || obj.kwarg != py::Varargs::None
-            || obj.defaults.len() > 0
             || obj.kw_defaults.len() > 0
         {
             Err(Error::ArbitraryParams.partial())
@@ -485,14 +529,28 @@ impl TryInto<Params> for WithSrc<py::Parameters> {
                 }
             }
 
+            let mut params: Vec<Param> = obj
+                .args
+                .into_iter()
+                .skip(if is_instance_method { 1 } else { 0 })
+                .map(|arg| WithSrc::new(&src, arg).try_into())
+                .collect::<Result<_, CoreError>>()?;
+
+            // Defaults belong to the last params
+            if obj.defaults.len() > params.len() {
+                return Err(Error::ArbitraryParams.partial());
+            }
+            let first_default = params.len() - obj.defaults.len();
+            for (Located(_, param), default) in params[first_default..]
+                .iter_mut()
+                .zip(obj.defaults)
+            {
+                param.default = Some(WithSrc::new(&src, default).try_into()?);
+            }
+
             Ok(Params {
                 is_instance_method,
-                params: obj
-                    .args
-                    .into_iter()
-                    .skip(if is_instance_method { 1 } else { 0 })
-                    .map(|arg| WithSrc::new(&src, arg).try_into())
-                    .collect::<Result<_, CoreError>>()?,
+                params,
             })
         }
     }
@@ -511,6 +569,7 @@ impl TryInto<Param> for WithSrc<py::Parameter> {
                 ParamObj {
                     arg: obj.arg,
                     annotation: WithSrc::new(&src, *annotation).try_into()?,
+                    default: None,
                 },
             ))
         } else {
@@ -525,9 +584,9 @@ impl TryInto<Expression> for WithSrc<py::Expression> {
     fn try_into(self) -> Result<Expression, Self::Error> {
         let WithSrc {
             src,
-            obj: py::Located { location, node },
+            obj: py::Located { location: py_location, node },
         } = self;
-        let location = Location::new(&src, location);
+        let location = Location::new(&src, py_location);
 
         match node {
             py::ExpressionType::BoolOp { op, values } => {
@@ -566,26 +625,46 @@ impl TryInto<Expression> for WithSrc<py::Expression> {
                     .map_err(|err: CoreError| err.updated(&location))?,
                 right: Box::new(WithSrc::new(&src, *b).try_into()?),
             }),
-            py::ExpressionType::Subscript { a, b } => Ok(ExpressionObj::Index {
-                value: Box::new(WithSrc::new(&src, *a).try_into()?),
-                index: Box::new(WithSrc::new(&src, *b).try_into()?),
-            }),
+            py::ExpressionType::Subscript { a, b } => match b.node {
+                py::ExpressionType::Slice { elements } => {
+                    // Omitted parts of the slice are parsed as `None`
+                    let mut parts = elements.into_iter().map(|part| match part.node {
+                        py::ExpressionType::None => Ok(None),
+                        _ => WithSrc::new(&src, part)
+                            .try_into()
+                            .map(|part: Expression| Some(Box::new(part))),
+                    });
+
+                    Ok(ExpressionObj::Slice {
+                        value: Box::new(WithSrc::new(&src, *a).try_into()?),
+                        start: parts.next().unwrap()?,
+                        stop: parts.next().unwrap()?,
+                        step: parts.next().unwrap()?,
+                    })
+                }
+                _ => Ok(ExpressionObj::Index {
+                    value: Box::new(WithSrc::new(&src, *a).try_into()?),
+                    index: Box::new(WithSrc::new(&src, *b).try_into()?),
+                }),
+            },
             py::ExpressionType::Unop { op, a } => Ok(ExpressionObj::UnOp {
                 op: op.try_into().unwrap(),
                 value: Box::new(WithSrc::new(&src, *a).try_into()?),
             }),
             py::ExpressionType::Compare { vals, ops } => {
-                if ops.len() == 1 {
-                    let mut vals = vals.into_iter();
-                    let mut ops = ops.into_iter();
-                    Ok(ExpressionObj::BinOp {
-                        left: Box::new(WithSrc::new(&src, vals.next().unwrap()).try_into()?),
-                        op: ops.next().unwrap().try_into().unwrap(),
-                        right: Box::new(WithSrc::new(&src, vals.next().unwrap()).try_into()?),
-                    })
-                } else {
-                    Err(Error::CompareChain)
-                }
+                let mut vals = vals.into_iter();
+                let first: Expression = WithSrc::new(&src, vals.next().unwrap()).try_into()?;
+
+                // Stored in reverse, so that the next comparison can be popped off the end
+                let mut rest = ops
+                    .into_iter()
+                    .map(|op| op.try_into().unwrap())
+                    .zip(vals)
+                    .map(|(op, val)| Ok((op, WithSrc::new(&src, val).try_into()?)))
+                    .collect::<Result<Vec<(Operator, Expression)>, CoreError>>()?;
+                rest.reverse();
+
+                Ok(desugar_compare_chain(first, rest, 0).1)
             }
             py::ExpressionType::Attribute { value, name } => Ok(ExpressionObj::Attribute {
                 value: Box::new(WithSrc::new(&src, *value).try_into()?),
@@ -673,10 +752,18 @@ impl TryInto<Expression> for WithSrc<py::Expression> {
                 py::StringGroup::Joined { values } => Ok(ExpressionObj::FStr {
                     parts: values
                         .into_iter()
-                        .map(|part| WithSrc::new(&src, part).try_into())
+                        .map(|node| {
+                            WithSrc::new(&src, py::Located { location: py_location, node })
+                                .try_into()
+                        })
                         .collect::<Result<Vec<_>, CoreError>>()?,
                 }),
-                _ => panic!("Encountered an unexpected syntax element"),
+                // An f-string made of a single formatted value isn't joined
+                node @ py::StringGroup::FormattedValue { .. } => Ok(ExpressionObj::FStr {
+                    parts: vec![
+                        WithSrc::new(&src, py::Located { location: py_location, node }).try_into()?,
+                    ],
+                }),
             },
             py::ExpressionType::Identifier { name } => Ok(ExpressionObj::Id(name)),
             py::ExpressionType::True => Ok(ExpressionObj::Bool(true)),
@@ -685,12 +772,46 @@ impl TryInto<Expression> for WithSrc<py::Expression> {
             py::ExpressionType::Await { .. } => Err(Error::Async),
             py::ExpressionType::Yield { .. } => Err(Error::ExpressionYield),
             py::ExpressionType::YieldFrom { .. } => Err(Error::ExpressionYield),
-            py::ExpressionType::Dict { .. } => Err(Error::ExpressionDict),
+            py::ExpressionType::Dict { elements } => Ok(ExpressionObj::Dict(
+                elements
+                    .into_iter()
+                    .map(|(key, value)| match key {
+                        Some(key) => Ok((
+                            WithSrc::new(&src, key).try_into()?,
+                            WithSrc::new(&src, value).try_into()?,
+                        )),
+                        None => Err(Error::DictUnpack.core(location.clone())),
+                    })
+                    .collect::<Result<Vec<_>, CoreError>>()?,
+            )),
             py::ExpressionType::Set { .. } => Err(Error::ExpressionSet),
             py::ExpressionType::Starred { .. } => Err(Error::ExpressionStarred),
             py::ExpressionType::Slice { .. } => Err(Error::ExpressionSlice),
             py::ExpressionType::Bytes { .. } => Err(Error::ExpressionBytes),
-            py::ExpressionType::Lambda { .. } => Err(Error::ExpressionLambda),
+            py::ExpressionType::Lambda { args, body } => {
+                let py::Parameters {
+                    args,
+                    kwonlyargs,
+                    vararg,
+                    kwarg,
+                    defaults,
+                    ..
+                } = *args;
+
+                if !kwonlyargs.is_empty()
+                    || !matches!(vararg, py::Varargs::None)
+                    || !matches!(kwarg, py::Varargs::None)
+                    || !defaults.is_empty()
+                    || args.iter().any(|arg| arg.annotation.is_some())
+                {
+                    Err(Error::LambdaParams)
+                } else {
+                    Ok(ExpressionObj::Lambda {
+                        params: args.into_iter().map(|arg| arg.arg).collect(),
+                        body: Box::new(WithSrc::new(&src, *body).try_into()?),
+                    })
+                }
+            }
             py::ExpressionType::IfExpression { test, body, orelse } => Ok(ExpressionObj::Ternary {
                 test: Box::new(WithSrc::new(&src, *test).try_into()?),
                 body: Box::new(WithSrc::new(&src, *body).try_into()?),
@@ -731,33 +852,211 @@ impl TryInto<Args> for WithSrc<(Vec<py::Expression>, Vec<py::Keyword>)> {
     }
 }
 
-impl TryInto<FStrPart> for WithSrc<py::StringGroup> {
+/// Converts a component of an f-string, located at the f-string itself (the parser locates
+/// formatted values relative to the start of the string).
+impl TryInto<FStrPart> for WithSrc<py::Located<py::StringGroup>> {
     type Error = CoreError;
 
     fn try_into(self) -> Result<FStrPart, Self::Error> {
-        let WithSrc { src, obj } = self;
+        let WithSrc {
+            src,
+            obj: py::Located { location, node },
+        } = self;
+        let location = Location::new(&src, location);
 
-        match obj {
+        match node {
             py::StringGroup::Constant { value } => Ok(FStrPart::Str(value)),
             py::StringGroup::FormattedValue {
                 value,
                 conversion,
                 spec,
             } => {
-                let location = Location::new(&src, value.location);
-                if conversion.is_some() || spec.is_some() {
-                    Err(Error::FStrWithSpec.core(location))
-                } else {
-                    Ok(FStrPart::ExpressionObj(
-                        WithSrc::new(&src, *value).try_into()?,
-                    ))
+                let mut format_spec = match spec.map(|spec| *spec) {
+                    None => FormatSpec::default(),
+                    Some(py::StringGroup::Constant { value }) => {
+                        parse_format_spec(value).map_err(|err| err.core(location.clone()))?
+                    }
+                    Some(_) => return Err(Error::FStrNestedSpec.core(location)),
+                };
+
+                match conversion {
+                    None | Some(py::ConversionFlag::Str) => {}
+                    Some(py::ConversionFlag::Repr) => {
+                        format_spec.repr = true;
+                    }
+                    Some(py::ConversionFlag::Ascii) => {
+                        return Err(Error::FStrAsciiConversion.core(location));
+                    }
                 }
+
+                Ok(FStrPart::ExpressionObj(
+                    WithSrc::new(&src, *value).try_into()?,
+                    format_spec,
+                ))
             }
             _ => panic!("Encountered an unexpected syntax element"),
         }
     }
 }
 
+/// Parse a format spec from Python's format spec mini-language:
+///
+/// `[[fill]align][sign][#][0][width][grouping][.precision][type]`
+///
+/// Options that can't be expressed with Rust's `format!` are rejected.
+fn parse_format_spec(src: String) -> Result<FormatSpec, Error> {
+    let mut spec = FormatSpec {
+        src: src.clone(),
+        ..Default::default()
+    };
+
+    let unsupported = |help| Error::FStrUnsupportedSpec(src.clone(), help);
+    let chars = src.chars().collect::<Vec<_>>();
+    let mut i = 0;
+
+    let align = |c: Option<&char>| match c {
+        Some('<') => Some(FormatAlign::Left),
+        Some('>') => Some(FormatAlign::Right),
+        Some('^') => Some(FormatAlign::Center),
+        _ => None,
+    };
+
+    if chars.first() == Some(&'=') || chars.get(1) == Some(&'=') {
+        return Err(unsupported(
+            "Help: \"=\" alignment is not supported, but a leading 0 (like \"08\") pads numbers with zeroes after the sign.",
+        ));
+    } else if let Some(align) = align(chars.get(1)) {
+        spec.fill = Some(chars[0]);
+        spec.align = Some(align);
+        i = 2;
+    } else if let Some(align) = align(chars.first()) {
+        spec.align = Some(align);
+        i = 1;
+    }
+
+    match chars.get(i) {
+        Some('+') => {
+            spec.sign = true;
+            i += 1;
+        }
+        Some('-') => {
+            i += 1;
+        }
+        Some(' ') => {
+            return Err(unsupported("Help: the \" \" sign option is not supported."));
+        }
+        _ => {}
+    }
+
+    if chars.get(i) == Some(&'z') {
+        return Err(unsupported("Help: the \"z\" option is not supported."));
+    }
+
+    if chars.get(i) == Some(&'#') {
+        spec.alternate = true;
+        i += 1;
+    }
+
+    if chars.get(i) == Some(&'0') {
+        spec.zero = true;
+        i += 1;
+    }
+
+    let number = |i: &mut usize| {
+        let start = *i;
+        while matches!(chars.get(*i), Some(c) if c.is_ascii_digit()) {
+            *i += 1;
+        }
+
+        chars[start..*i].iter().collect::<String>().parse::<usize>().ok()
+    };
+
+    spec.width = number(&mut i);
+
+    if let Some(c @ (',' | '_')) = chars.get(i) {
+        spec.grouping = Some(*c);
+        i += 1;
+    }
+
+    if chars.get(i) == Some(&'.') {
+        i += 1;
+        spec.precision = number(&mut i);
+
+        if spec.precision.is_none() {
+            return Err(Error::FStrInvalidSpec(src));
+        }
+    }
+
+    spec.kind = match chars.get(i) {
+        None => None,
+        Some('s') => Some(FormatKind::Str),
+        Some('d') => Some(FormatKind::Int),
+        Some('f' | 'F') => Some(FormatKind::Float),
+        Some('x') => Some(FormatKind::Hex),
+        Some('X') => Some(FormatKind::UpperHex),
+        Some('b') => Some(FormatKind::Binary),
+        Some('o') => Some(FormatKind::Octal),
+        Some('e' | 'E' | 'g' | 'G' | '%' | 'n' | 'c') => {
+            return Err(unsupported(
+                "Help: the supported format types are s, d, f, x, X, b and o.",
+            ));
+        }
+        Some(_) => return Err(Error::FStrInvalidSpec(src)),
+    };
+
+    if spec.kind.is_some() {
+        i += 1;
+    }
+
+    if i < chars.len() {
+        return Err(Error::FStrInvalidSpec(src));
+    }
+
+    // Python's `f` defaults to 6 digits of precision, while Rust prints as many as needed
+    if spec.kind == Some(FormatKind::Float) && spec.precision.is_none() {
+        spec.precision = Some(6);
+    }
+
+    match (spec.kind, spec.grouping) {
+        (Some(FormatKind::Str), Some(_)) => {
+            return Err(Error::FStrInvalidSpec(src));
+        }
+        (
+            Some(FormatKind::Hex | FormatKind::UpperHex | FormatKind::Binary | FormatKind::Octal),
+            Some(','),
+        ) => {
+            return Err(Error::FStrInvalidSpec(src));
+        }
+        _ => {}
+    }
+
+    if spec.zero && spec.grouping.is_some() {
+        return Err(unsupported(
+            "Help: zero-padding can not be combined with a thousands separator.",
+        ));
+    }
+
+    match (spec.alternate, spec.kind) {
+        (false, _) | (true, Some(FormatKind::Hex | FormatKind::Binary | FormatKind::Octal)) => {}
+        (true, Some(FormatKind::UpperHex)) => {
+            return Err(unsupported(
+                "Help: Rust always uses a lowercase \"0x\" prefix, try \"#x\" instead.",
+            ));
+        }
+        (true, _) => {
+            return Err(unsupported(
+                "Help: the \"#\" option is only supported with the x, b and o types.",
+            ));
+        }
+    }
+
+    if let Some('{' | '}') = spec.fill {
+        return Err(unsupported("Help: braces can not be used as a fill character."));
+    }
+
+    Ok(spec)
+}
+
 impl TryInto<TyExpression> for WithSrc<py::Expression> {
     type Error = CoreError;
 
@@ -829,6 +1128,7 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
             src,
             obj: py::Located { location, node },
         } = self;
+        let py_location = location;
         let location = Location::new(&src, location);
 
         match node {
@@ -848,7 +1148,13 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
             }),
             py::StatementType::Assign { mut targets, value } => Ok(StatementObj::Assign {
                 target: if targets.len() == 1 {
-                    WithSrc::new(&src, targets.pop().unwrap()).try_into()?
+                    let target: Expression = WithSrc::new(&src, targets.pop().unwrap()).try_into()?;
+
+                    if let ExpressionObj::Slice { .. } = &target.1 {
+                        return Err(Error::SliceAssign.core(location));
+                    }
+
+                    target
                 } else {
                     Located(
                         location.clone(),
@@ -863,7 +1169,15 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
                 value: WithSrc::new(&src, value).try_into()?,
             }),
             py::StatementType::AugAssign { target, op, value } => Ok(StatementObj::OpAssign {
-                target: WithSrc::new(&src, *target).try_into()?,
+                target: {
+                    let target: Expression = WithSrc::new(&src, *target).try_into()?;
+
+                    if let ExpressionObj::Slice { .. } = &target.1 {
+                        return Err(Error::SliceAssign.core(location));
+                    }
+
+                    target
+                },
                 op: op
                     .try_into()
                     .map_err(|err: CoreError| err.updated(&location))?,
@@ -898,16 +1212,21 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
                     })
                     .transpose()?,
             }),
-            py::StatementType::While { test, body, orelse } => match orelse {
-                None => Ok(StatementObj::While {
-                    test: WithSrc::new(&src, test).try_into()?,
-                    body: body
-                        .into_iter()
-                        .map(|statement| WithSrc::new(&src, statement).try_into())
-                        .collect::<Result<_, CoreError>>()?,
-                }),
-                _ => Err(Error::WhileWithOrelse),
-            },
+            py::StatementType::While { test, body, orelse } => Ok(StatementObj::While {
+                test: WithSrc::new(&src, test).try_into()?,
+                body: body
+                    .into_iter()
+                    .map(|statement| WithSrc::new(&src, statement).try_into())
+                    .collect::<Result<_, CoreError>>()?,
+                orelse: orelse
+                    .map(|orelse| {
+                        orelse
+                            .into_iter()
+                            .map(|statement| WithSrc::new(&src, statement).try_into())
+                            .collect::<Result<_, CoreError>>()
+                    })
+                    .transpose()?,
+            }),
             py::StatementType::For {
                 is_async,
                 target,
@@ -917,8 +1236,6 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
             } => {
                 if is_async {
                     Err(Error::Async)
-                } else if orelse.is_some() {
-                    Err(Error::ForWithOrelse)
                 } else {
                     Ok(StatementObj::For {
                         target: WithSrc::new(&src, *target).try_into()?,
@@ -930,16 +1247,52 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
                             .into_iter()
                             .map(|statement| WithSrc::new(&src, statement).try_into())
                             .collect::<Result<_, CoreError>>()?,
+                        orelse: orelse
+                            .map(|orelse| {
+                                orelse
+                                    .into_iter()
+                                    .map(|statement| WithSrc::new(&src, statement).try_into())
+                                    .collect::<Result<_, CoreError>>()
+                            })
+                            .transpose()?,
                     })
                 }
             }
             py::StatementType::Import { .. } | py::StatementType::ImportFrom { .. } => {
                 Err(Error::StatementImport)
             }
-            py::StatementType::Delete { .. } => Err(Error::StatementDelete),
+            py::StatementType::Delete { mut targets } => {
+                if targets.len() == 1 {
+                    Ok(StatementObj::Delete {
+                        target: WithSrc::new(&src, targets.pop().unwrap()).try_into()?,
+                    })
+                } else {
+                    Err(Error::DeleteMultiple)
+                }
+            }
             py::StatementType::Global { .. } => Err(Error::StatementGlobal),
             py::StatementType::Nonlocal { .. } => Err(Error::StatementNonlocal),
+            // Match statements are rewritten into `with` statements before parsing, see `parse`
+            py::StatementType::With {
+                is_async: false,
+                mut items,
+                body,
+            } if items.len() == 1 && keyword_at(&src, py_location, "match") => {
+                Ok(StatementObj::Match {
+                    subject: WithSrc::new(&src, items.pop().unwrap().context_expr).try_into()?,
+                    cases: body
+                        .into_iter()
+                        .map(|statement| WithSrc::new(&src, statement).try_into())
+                        .collect::<Result<_, CoreError>>()?,
+                })
+            }
             py::StatementType::With { .. } => Err(Error::StatementWith),
+            py::StatementType::Raise {
+                exception: Some(exception),
+                cause: None,
+            } => Ok(StatementObj::Raise {
+                error: WithSrc::new(&src, exception).try_into()?,
+            }),
             py::StatementType::Raise { .. } => Err(Error::StatementRaise),
             py::StatementType::Try { .. } => Err(Error::StatementTry),
             py::StatementType::ClassDef { .. } => Err(Error::StatementClassDef),
@@ -950,6 +1303,132 @@ impl TryInto<Statement> for WithSrc<py::Statement> {
     }
 }
 
+impl TryInto<MatchCase> for WithSrc<py::Statement> {
+    type Error = CoreError;
+
+    fn try_into(self) -> Result<MatchCase, Self::Error> {
+        let WithSrc {
+            src,
+            obj: py::Located { location, node },
+        } = self;
+        let py_location = location;
+        let location = Location::new(&src, location);
+
+        match node {
+            // `case P if G:` was rewritten to `with P, G:`
+            py::StatementType::With {
+                is_async: false,
+                items,
+                body,
+            } if (items.len() == 1 || items.len() == 2)
+                && keyword_at(&src, py_location, "case") =>
+            {
+                let mut items = items.into_iter().map(|item| item.context_expr);
+
+                let pattern: Expression = WithSrc::new(&src, items.next().unwrap()).try_into()?;
+
+                Ok(MatchCase {
+                    pattern: pattern.try_into()?,
+                    guard: items
+                        .next()
+                        .map(|guard| WithSrc::new(&src, guard).try_into())
+                        .transpose()?,
+                    body: body
+                        .into_iter()
+                        .map(|statement| WithSrc::new(&src, statement).try_into())
+                        .collect::<Result<_, CoreError>>()?,
+                })
+            }
+            _ => Err(Error::MatchArbitraryStatement.core(location)),
+        }
+    }
+}
+
+impl TryInto<Pattern> for Expression {
+    type Error = CoreError;
+
+    fn try_into(self) -> Result<Pattern, Self::Error> {
+        let Located(location, obj) = self;
+
+        let pattern = match obj {
+            ExpressionObj::Id(name) if name == "_" => PatternObj::Wildcard,
+            ExpressionObj::Id(name) => PatternObj::Capture(name),
+            obj @ (ExpressionObj::Int(..) | ExpressionObj::Bool(..) | ExpressionObj::Str(..)) => {
+                PatternObj::Literal(Located(location.clone(), obj))
+            }
+            ExpressionObj::UnOp {
+                op: UnaryOperator::Neg,
+                value,
+            } if matches!(value.1, ExpressionObj::Int(..)) => PatternObj::Literal(Located(
+                location.clone(),
+                ExpressionObj::UnOp {
+                    op: UnaryOperator::Neg,
+                    value,
+                },
+            )),
+            obj @ ExpressionObj::Attribute { .. } => {
+                PatternObj::Value(Located(location.clone(), obj))
+            }
+            ExpressionObj::Call {
+                function,
+                args: Args { pos, kw },
+            } => PatternObj::Class {
+                cls: *function,
+                args: pos
+                    .into_iter()
+                    .map(|arg| arg.try_into())
+                    .collect::<Result<_, CoreError>>()?,
+                kwargs: kw
+                    .into_iter()
+                    .map(|(name, arg)| Ok((name, arg.try_into()?)))
+                    .collect::<Result<_, CoreError>>()?,
+            },
+            ExpressionObj::Tuple(elements) => PatternObj::Tuple(
+                elements
+                    .into_iter()
+                    .map(|element| element.try_into())
+                    .collect::<Result<_, CoreError>>()?,
+            ),
+            ExpressionObj::BinOp {
+                left,
+                op: Operator::BitOr,
+                right,
+            } => {
+                // Flatten `a | b | c` into a single list of options
+                let mut options = vec![];
+                for side in [*left, *right] {
+                    match side.try_into()? {
+                        Located(_, PatternObj::Or(inner)) => options.extend(inner),
+                        pattern => options.push(pattern),
+                    }
+                }
+
+                PatternObj::Or(options)
+            }
+            _ => return Err(Error::UnsupportedPattern.core(location)),
+        };
+
+        Ok(Located(location, pattern))
+    }
+}
+
+/// Whether the source has the given keyword at a location. Rewritten `match` statements put their
+/// `with` exactly where the original `match`/`case` was, see `parse`.
+fn keyword_at(src: &str, loc: py::Location, keyword: &str) -> bool {
+    let mut rest = src
+        .split('\n')
+        .nth(loc.row() - 1)
+        .unwrap_or("")
+        .chars()
+        .skip(loc.column() - 1);
+
+    keyword.chars().all(|c| rest.next() == Some(c))
+        && !rest
+            .next()
+            .map(|c| c.is_alphanumeric() || c == '_')
+            .unwrap_or(false)
+}
+
 impl TryInto<Operator> for py::Operator {
     type Error = CoreError;
 
@@ -1018,3 +1497,107 @@ impl TryInto<UnaryOperator> for py::UnaryOperator {
 pub fn clean(program: py::Program, source: String) -> Result<Module, CoreError> {
     WithSrc::new(&Rc::new(source), program).try_into()
 }
+
+/// Desugar a comparison chain (`a < b <= c`) into `and`-joined comparisons (`a < b and b <= c`).
+/// `rest` holds the remaining comparisons in reverse order. Operands that appear in two
+/// comparisons and might have side effects are bound to a temporary, so that every operand is
+/// still evaluated once and in order.
+fn desugar_compare_chain(
+    left: Expression,
+    mut rest: Vec<(Operator, Expression)>,
+    temps: usize,
+) -> Expression {
+    let (op, right) = rest.pop().unwrap();
+    let loc = left.0.clone();
+
+    if rest.is_empty() {
+        return Located(
+            loc,
+            ExpressionObj::BinOp {
+                left: left.into(),
+                op,
+                right: right.into(),
+            },
+        );
+    }
+
+    if is_pure(&right) {
+        let comparison = Located(
+            loc.clone(),
+            ExpressionObj::BinOp {
+                left: left.into(),
+                op,
+                right: right.clone().into(),
+            },
+        );
+
+        return Located(
+            loc,
+            ExpressionObj::BinOp {
+                left: comparison.into(),
+                op: Operator::And,
+                right: desugar_compare_chain(right, rest, temps).into(),
+            },
+        );
+    }
+
+    let name = format!("__compare_{}__", temps);
+
+    // The right side is about to be bound, so the left side has to be bound before it in order
+    // to be evaluated first
+    if !is_pure(&left) {
+        let temp = Located(loc.clone(), ExpressionObj::Id(name.clone()));
+        rest.push((op, right));
+
+        return Located(
+            loc,
+            ExpressionObj::Bind {
+                name,
+                value: left.into(),
+                body: desugar_compare_chain(temp, rest, temps + 1).into(),
+            },
+        );
+    }
+
+    let temp = Located(right.0.clone(), ExpressionObj::Id(name.clone()));
+    let comparison = Located(
+        loc.clone(),
+        ExpressionObj::BinOp {
+            left: left.into(),
+            op,
+            right: temp.clone().into(),
+        },
+    );
+
+    Located(
+        loc.clone(),
+        ExpressionObj::Bind {
+            name,
+            value: right.into(),
+            body: Located(
+                loc,
+                ExpressionObj::BinOp {
+                    left: comparison.into(),
+                    op: Operator::And,
+                    right: desugar_compare_chain(temp, rest, temps + 1).into(),
+                },
+            )
+            .into(),
+        },
+    )
+}
+
+/// Whether evaluating an expression is free of side effects, meaning it can safely be repeated.
+fn is_pure(expression: &Expression) -> bool {
+    match &expression.1 {
+        ExpressionObj::Id(..)
+        | ExpressionObj::Int(..)
+        | ExpressionObj::Float(..)
+        | ExpressionObj::Str(..)
+        | ExpressionObj::Bool(..)
+        | ExpressionObj::None => true,
+        ExpressionObj::Attribute { value, .. } => is_pure(value),
+        ExpressionObj::UnOp { value, .. } => is_pure(value),
+        _ => false,
+    }
+}
@@ -51,6 +51,7 @@ pub enum TypeDef {
     Account(Account),
     // Context(Context),
     Enum(Enum),
+    ErrorCode(ErrorCode),
 }
 
 /// A `struct` definition.
@@ -71,14 +72,29 @@ pub struct Account {
     // Save type info for generation later
     pub fields: Vec<(String, TyExpr, Ty)>,
     pub methods: Vec<(MethodType, Function)>,
+    pub is_zero_copy: bool,
 }
 
 /// An `enum` definition.
 #[derive(Clone, Debug)]
 pub struct Enum {
     pub name: String,
-    // NOTE: saving some space for the future when enums variants might have data
-    pub variants: Vec<(String, Option<TyExpr>)>,
+    pub variants: Vec<(String, EnumVariant)>,
+}
+
+/// The data carried by a variant of an `enum`.
+#[derive(Clone, Debug)]
+pub enum EnumVariant {
+    Unit,
+    Tuple(Vec<TyExpr>),
+    Struct(Vec<(String, TyExpr)>),
+}
+
+/// An Anchor `#[error_code]` enum definition. Each variant has a message.
+#[derive(Clone, Debug)]
+pub struct ErrorCode {
+    pub name: String,
+    pub variants: Vec<(String, String)>,
 }
 
 /// An expression that resolves to a Rust type. `Generic` types include whether their Seahorse type
@@ -96,6 +112,11 @@ pub enum TyExpr {
         size: Box<TyExpr>,
     },
     Tuple(Vec<TyExpr>),
+    // A growable collection with a declared max capacity, only used to calculate account space
+    Bounded {
+        inner: Box<TyExpr>,
+        capacity: usize,
+    },
     Account(Vec<String>), // Type expression explicitly for defined accounts
     Const(usize),
     InfoLifetime,
@@ -124,11 +145,31 @@ impl TyExpr {
             Self::Generic { params, .. } => params.iter().any(|param| param.has_info_lifetime()),
             Self::Array { element, .. } => element.has_info_lifetime(),
             Self::Tuple(tuple) => tuple.iter().any(|part| part.has_info_lifetime()),
+            Self::Bounded { inner, .. } => inner.has_info_lifetime(),
             Self::InfoLifetime { .. } => true,
             Self::Account(..) => true,
             _ => false,
         }
     }
+
+    /// Whether this type holds a map without a declared max capacity.
+    pub fn has_unbounded_map(&self) -> bool {
+        match self {
+            Self::Generic { name, params, .. } => {
+                name.len() == 1 && name[0] == "BTreeMap"
+                    || params.iter().any(|param| param.has_unbounded_map())
+            }
+            Self::Array { element, .. } => element.has_unbounded_map(),
+            Self::Tuple(tuple) => tuple.iter().any(|part| part.has_unbounded_map()),
+            Self::Bounded { inner, .. } => match &**inner {
+                Self::Generic { params, .. } => {
+                    params.iter().any(|param| param.has_unbounded_map())
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
 }
 
 /// An `fn` definition.
@@ -149,6 +190,12 @@ pub struct InstructionContext {
     pub params: Vec<(String, TyExpr)>,
     pub accounts: Vec<(String, ContextAccount)>,
     pub inferred_accounts: BTreeMap<String, ContextAccount>,
+    // PDA accounts whose seeds are used to sign CPIs, which get passed to the instruction handler
+    pub signer_seeds: BTreeSet<String>,
+    // The param that takes the remaining accounts, along with the type of each account
+    pub remaining_accounts: Option<(String, AccountTyExpr)>,
+    // Whether the instruction emits events with CPIs, which needs the event authority account
+    pub emit_cpi: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -162,13 +209,18 @@ pub struct ContextAccount {
 #[derive(Clone, Debug)]
 pub enum AccountTyExpr {
     Empty(Box<AccountTyExpr>),
+    Optional(Box<AccountTyExpr>),
     Defined(Vec<String>),
+    ZeroCopy(Vec<String>),
     Signer,
     TokenMint,
     TokenAccount,
+    Token2022Mint,
+    Token2022Account,
     UncheckedAccount,
     SystemProgram,
     TokenProgram,
+    Token2022Program,
     AssociatedTokenProgram,
     RentSysvar,
     ClockSysvar,
@@ -177,10 +229,23 @@ pub enum AccountTyExpr {
 impl AccountTyExpr {
     pub fn is_program(&self) -> bool {
         match self {
-            Self::SystemProgram | Self::TokenProgram | Self::AssociatedTokenProgram => true,
+            Self::SystemProgram
+            | Self::TokenProgram
+            | Self::Token2022Program
+            | Self::AssociatedTokenProgram => true,
             _ => false,
         }
     }
+
+    /// Get the token program that owns this type of account, if it's a token mint or account.
+    pub fn token_program(&self) -> Option<AccountTyExpr> {
+        match self {
+            Self::TokenMint | Self::TokenAccount => Some(Self::TokenProgram),
+            Self::Token2022Mint | Self::Token2022Account => Some(Self::Token2022Program),
+            Self::Optional(ty_expr) => ty_expr.token_program(),
+            _ => None,
+        }
+    }
 }
 
 /// Content of an Anchor account annotation (#[account(...)]).
@@ -197,6 +262,8 @@ pub struct AccountAnnotation {
     pub token_authority: Option<TypedExpression>,
     pub space: Option<TypedExpression>,
     pub padding: Option<TypedExpression>,
+    pub close: Option<TypedExpression>,
+    pub constraints: Vec<AccountConstraint>,
 }
 
 impl AccountAnnotation {
@@ -213,10 +280,57 @@ impl AccountAnnotation {
             token_authority: None,
             space: None,
             padding: None,
+            close: None,
+            constraints: vec![],
+        }
+    }
+
+    /// Get the seeds (and stored bump, if there is one) of this account if it's a PDA, either from
+    /// its initialization or from a `seeds` constraint.
+    pub fn pda_seeds(&self) -> Option<(&Vec<TypedExpression>, Option<&TypedExpression>)> {
+        if let Some(seeds) = &self.seeds {
+            return Some((seeds, None));
         }
+
+        self.constraints
+            .iter()
+            .find_map(|constraint| match constraint {
+                AccountConstraint::Seeds { seeds, bump } => Some((seeds, bump.as_ref())),
+                _ => None,
+            })
     }
 }
 
+/// A declarative check on an instruction account, given as the default value of its param (like
+/// `vault: Vault = has_one('owner')`). Each check may fail with a custom error code.
+#[derive(Clone, Debug)]
+pub enum AccountConstraint {
+    HasOne {
+        field: String,
+        error: Option<TypedExpression>,
+    },
+    Constraint {
+        cond: TypedExpression,
+        error: Option<TypedExpression>,
+    },
+    Address {
+        address: TypedExpression,
+        error: Option<TypedExpression>,
+    },
+    // Resizes the account before the instruction runs, with `payer` covering any extra rent
+    Realloc {
+        space: TypedExpression,
+        payer: TypedExpression,
+        zero: Option<TypedExpression>,
+    },
+    // Checks that the account is the PDA with these seeds - with a stored bump, Anchor can skip
+    // searching for it
+    Seeds {
+        seeds: Vec<TypedExpression>,
+        bump: Option<TypedExpression>,
+    },
+}
+
 /// A block of code - multiple statements optionally followed by an implicit return.
 #[derive(Clone, Debug)]
 pub struct Block {
@@ -238,18 +352,36 @@ pub enum Statement {
     },
     Expression(TypedExpression),
     Return(Option<TypedExpression>),
-    Break,
+    Break {
+        label: Option<String>,
+    },
     Continue,
     Noop,
     AnchorRequire {
         cond: TypedExpression,
         msg: TypedExpression,
     },
+    // Like `AnchorRequire`, but fails with a user-defined error code
+    Require {
+        cond: TypedExpression,
+        error: TypedExpression,
+    },
+    Raise(TypedExpression),
     If {
         cond: TypedExpression,
         body: Block,
         orelse: Option<Block>,
     },
+    // `if let Some(name) = value`, used when an optional is narrowed to its inner type
+    IfSome {
+        name: String,
+        value: TypedExpression,
+        body: Block,
+        orelse: Option<Block>,
+        // If the narrowed variable gets assigned to, the name of a reference to the optional that
+        // the new values are written back to
+        write_back: Option<String>,
+    },
     While {
         cond: TypedExpression,
         body: Block,
@@ -263,6 +395,56 @@ pub enum Statement {
         iter: TypedExpression,
         body: Block,
     },
+    Match {
+        subject: TypedExpression,
+        // Strings are matched as `&str`s, so string literals can be used as patterns
+        is_str: bool,
+        arms: Vec<MatchArm>,
+    },
+}
+
+/// A single arm of a match statement.
+#[derive(Clone, Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<TypedExpression>,
+    pub body: Block,
+}
+
+/// A pattern in a match arm.
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    Wildcard,
+    Capture(String),
+    Literal(Literal),
+    Value(TypedExpression),
+    TupleVariant(TypedExpression, Vec<Pattern>),
+    // Fields that aren't matched are left out
+    StructVariant(TypedExpression, Vec<(String, Pattern)>),
+    Tuple(Vec<Pattern>),
+    Or(Vec<Pattern>),
+}
+
+impl Pattern {
+    /// Get the names of the variables captured by this pattern.
+    pub fn captures(&self) -> Vec<String> {
+        match self {
+            Self::Wildcard | Self::Literal(..) | Self::Value(..) => vec![],
+            Self::Capture(name) => vec![name.clone()],
+            Self::TupleVariant(_, patterns) | Self::Tuple(patterns) => {
+                patterns.iter().flat_map(|pattern| pattern.captures()).collect()
+            }
+            Self::StructVariant(_, fields) => fields
+                .iter()
+                .flat_map(|(_, pattern)| pattern.captures())
+                .collect(),
+            // Every option captures the same variables
+            Self::Or(options) => options
+                .first()
+                .map(|option| option.captures())
+                .unwrap_or(vec![]),
+        }
+    }
 }
 
 /// Let-bindable target.
@@ -309,6 +491,15 @@ impl TypedExpression {
         return self;
     }
 
+    /// Get the params and body of this expression if it's a closure.
+    pub fn closure(&self) -> Option<(&Vec<String>, &TypedExpression)> {
+        match &self.obj {
+            ExpressionObj::Closure { params, body } => Some((params, body)),
+            ExpressionObj::Move(value) => value.closure(),
+            _ => None,
+        }
+    }
+
     /// Remove the borrows from this expression.
     pub fn without_borrows(mut self) -> Self {
         self.obj = self.obj.without_borrows();
@@ -366,6 +557,11 @@ pub enum ExpressionObj {
         body: Box<TypedExpression>,
         orelse: Box<TypedExpression>,
     },
+    // A lambda. Calls to a closure are inlined, so that Rust never has to infer its param types
+    Closure {
+        params: Vec<String>,
+        body: Box<TypedExpression>,
+    },
     As {
         value: Box<TypedExpression>,
         ty: TyExpr,
@@ -385,6 +581,9 @@ pub enum ExpressionObj {
     // Indicator that an expression resolves to a mutable type that needs to be immutably
     // borrowed
     BorrowImmut(Box<TypedExpression>),
+    // Indicator that an expression is a program account that's used as its underlying Anchor
+    // account
+    AnchorAccount(Box<TypedExpression>),
     // Indicator that an expression is a "raw" (unshared) mutable type that needs to be wrapped
     // in an Rc<RefCell<_>>
     Mutable(Box<TypedExpression>),
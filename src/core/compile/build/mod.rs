@@ -10,7 +10,7 @@ use crate::{
     match1,
 };
 use heck::ToPascalCase;
-use quote::quote;
+use quote::{format_ident, quote};
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
     rc::Rc,
@@ -24,6 +24,36 @@ enum Error {
     TopLevelNonDirective,
     MisplacedDirective,
     MisplacedCpi,
+    MutableVariantData(Ty),
+    MisplacedClose,
+    ConditionalClose,
+    CloseTarget(String),
+    UseAfterClose(String),
+    MisplacedConstraint,
+    InvalidParamDefault,
+    ConstraintOnNonAccount(String),
+    HasOneWithoutAccount(String),
+    HasOneWithoutField(String, String),
+    MisplacedRealloc,
+    ReallocTarget(String),
+    ReallocPayer(String),
+    ReallocBodyVar(String),
+    DuplicateRealloc(String),
+    ConditionalRealloc,
+    SeedsOnEmpty(String),
+    DuplicateSeeds(String),
+    MisplacedSignerSeeds,
+    NoSignerSeeds(String),
+    UnresolvedAuthority,
+    FormatSpecTy(String, Ty),
+    FormatSpecFloatPrecision(String),
+    DuplicateRemainingAccounts,
+    RemainingAccountsTy(Ty),
+    OptionalAccountTy(Ty),
+    InstructionReturnTy(Ty),
+    InstructionReturnTuple(Ty),
+    MisplacedEmitCpi,
+    UnboundedDict(String),
 }
 
 impl Error {
@@ -41,6 +71,126 @@ impl Error {
             Self::MisplacedDirective => {
                 CoreError::make_raw("misplaced directive", "Hint: directives (like declare_id) are special expressions that can only be at the top level of a module.")
             }
+            Self::MutableVariantData(ty) => CoreError::make_raw(
+                format!("enum variants can not hold mutable data (found {})", ty),
+                "Hint: enum variants may only carry simple values like numbers, strings, pubkeys, tuples and other enums."
+            ),
+            Self::MisplacedClose => CoreError::make_raw(
+                "misplaced account close",
+                "Hint: accounts can only be closed in the @instruction that they were passed into."
+            ),
+            Self::ConditionalClose => CoreError::make_raw(
+                "program accounts can not be closed conditionally",
+                "Hint: Anchor closes the account after your instruction runs, so the close always happens. Try moving it to the top level of your instruction, or splitting the instruction in two."
+            ),
+            Self::CloseTarget(to) => CoreError::make_raw(
+                format!("can not send rent to \"{}\"", to),
+                "Hint: the rent of a closed account must go to another account of the instruction."
+            ),
+            Self::UseAfterClose(name) => CoreError::make_raw(
+                format!("account \"{}\" is used after being closed", name),
+                ""
+            ),
+            Self::MisplacedConstraint => CoreError::make_raw(
+                "misplaced account constraint",
+                "Hint: account constraints (like has_one) can only be used as the default value of an @instruction's account param, like this:\n\n\tdef withdraw(owner: Signer, vault: Vault = has_one('owner')):"
+            ),
+            Self::InvalidParamDefault => CoreError::make_raw(
+                "params can not have default values",
+                "Hint: the only param defaults that Seahorse supports are account constraints (has_one, constraint, address, realloc and seeds), or a tuple of them."
+            ),
+            Self::ConstraintOnNonAccount(name) => CoreError::make_raw(
+                format!("account constraints can only be placed on accounts (\"{}\" is not an account)", name),
+                ""
+            ),
+            Self::HasOneWithoutAccount(field) => CoreError::make_raw(
+                format!("has_one(\"{}\") needs an account named \"{}\"", field, field),
+                "Hint: has_one checks that the field of this account holds the key of the instruction's account with the same name."
+            ),
+            Self::HasOneWithoutField(account, field) => CoreError::make_raw(
+                format!("has_one(\"{}\") needs \"{}\" to have a Pubkey field named \"{}\"", field, account, field),
+                "Hint: has_one checks that the field of this account holds the key of the instruction's account with the same name."
+            ),
+            Self::MisplacedRealloc => CoreError::make_raw(
+                "misplaced account realloc",
+                "Hint: accounts can only be reallocated in the @instruction that they were passed into."
+            ),
+            Self::ReallocTarget(name) => CoreError::make_raw(
+                format!("can not realloc \"{}\"", name),
+                "Hint: only program accounts (classes that inherit from Account) can be reallocated, and zero-copy accounts always have the same size."
+            ),
+            Self::ReallocPayer(name) => CoreError::make_raw(
+                format!("realloc of \"{}\" needs another instruction account as its payer", name),
+                "Hint: the payer covers the extra rent of a growing account, so it's usually a Signer of the instruction."
+            ),
+            Self::ReallocBodyVar(name) => CoreError::make_raw(
+                format!("realloc can not use \"{}\", which is assigned in the instruction", name),
+                "Hint: Anchor reallocs the account before the instruction runs, so its new size and payer can only use the instruction's params and accounts, and constants."
+            ),
+            Self::DuplicateRealloc(name) => CoreError::make_raw(
+                format!("account \"{}\" is reallocated more than once", name),
+                "Hint: an account can only have one realloc per instruction."
+            ),
+            Self::ConditionalRealloc => CoreError::make_raw(
+                "accounts can not be reallocated conditionally",
+                "Hint: Anchor reallocs the account before your instruction runs, so the realloc always happens. Try moving it to the top level of your instruction."
+            ),
+            Self::SeedsOnEmpty(name) => CoreError::make_raw(
+                format!("can not add seeds to \"{}\"", name),
+                "Hint: the seeds of a new account are given when it's initialized, like this:\n\n\tvault.init(payer = owner, seeds = ['vault', owner])"
+            ),
+            Self::DuplicateSeeds(name) => CoreError::make_raw(
+                format!("account \"{}\" has more than one set of seeds", name),
+                ""
+            ),
+            Self::MisplacedSignerSeeds => CoreError::make_raw(
+                "misplaced signer_seeds",
+                "Hint: the seeds of an account can only be used in the @instruction that it was passed into."
+            ),
+            Self::NoSignerSeeds(name) => CoreError::make_raw(
+                format!("account \"{}\" has no known seeds", name),
+                "Hint: only PDAs can sign, and Seahorse needs to know their seeds. Give the account seeds when initializing it, or with the seeds constraint:\n\n\tdef withdraw(owner: Signer, vault: Vault = seeds(['vault', owner], bump = vault.bump)):"
+            ),
+            Self::UnresolvedAuthority => CoreError::make_raw(
+                "can not find the seeds of this authority",
+                "Hint: program accounts can only sign as PDAs, so the authority has to be one of the instruction's accounts, passed by its own name. Otherwise, pass its signer seeds yourself with the signer param."
+            ),
+            Self::DuplicateRemainingAccounts => CoreError::make_raw(
+                "instruction has more than one list of remaining accounts",
+                "Hint: an instruction's remaining accounts can only be taken by one param."
+            ),
+            Self::RemainingAccountsTy(ty) => CoreError::make_raw(
+                format!("remaining accounts can not be {}", ty),
+                "Hint: remaining accounts are already initialized, and zero-copy accounts and sysvars need to be regular instruction accounts."
+            ),
+            Self::OptionalAccountTy(ty) => CoreError::make_raw(
+                format!("optional accounts can not be {}", ty),
+                "Hint: an account that gets initialized or is zero-copy has to be passed in."
+            ),
+            Self::InstructionReturnTy(ty) => CoreError::make_raw(
+                format!("instructions can not return {}", ty),
+                "Hint: instructions return data to the client, so accounts can't be returned. Return the data you need from the account instead."
+            ),
+            Self::InstructionReturnTuple(ty) => CoreError::make_raw(
+                format!("instructions can not return {}", ty),
+                "Hint: Anchor's IDL has no tuple type, so clients couldn't decode the return value. Return a class with a field for each value instead."
+            ),
+            Self::UnboundedDict(name) => CoreError::make_raw(
+                format!("account field \"{}\" holds a dict with no max capacity", name),
+                "Hint: Seahorse needs to know how many entries a stored dict can hold to give the account enough space. Add the capacity as a third type param, like Dict[Pubkey, u64, 16]."
+            ),
+            Self::MisplacedEmitCpi => CoreError::make_raw(
+                "misplaced emit_cpi",
+                "Hint: events can only be emitted with emit_cpi directly inside an @instruction, which gets the event authority account that signs the CPI."
+            ),
+            Self::FormatSpecTy(spec, ty) => CoreError::make_raw(
+                format!("format spec \"{}\" can not be used with {}", spec, ty),
+                ""
+            ),
+            Self::FormatSpecFloatPrecision(spec) => CoreError::make_raw(
+                format!("unsupported f-string format spec \"{}\"", spec),
+                "Help: precision without a type uses Python's general number format, which is not supported. Try adding an \"f\" type."
+            ),
             Self::MisplacedCpi => {
                 CoreError::make_raw(
                     "misplaced CPI",
@@ -75,6 +225,8 @@ pub enum ExprContext {
     Seed,
     Directive,
     Assert,
+    // Inside of a Rust closure, where errors can't be propagated with `?`
+    Closure,
 }
 
 #[derive(Clone, Debug)]
@@ -158,7 +310,12 @@ pub enum Transformed {
     Expression(TypedExpression),
     Cpi {
         expr: TypedExpression,
-        program: AccountTyExpr,
+        // The program to add to the instruction, if the CPI isn't given one
+        program: Option<AccountTyExpr>,
+        // The authorities of the CPI, if it's made without signer seeds
+        authorities: Vec<CpiAuthority>,
+        // Renders the same CPI signed with the seeds of the authorities that turn out to be PDAs
+        signed: Option<CpiSigner>,
     },
     AccountInit {
         expr: TypedExpression,
@@ -166,14 +323,85 @@ pub enum Transformed {
         annotation: AccountAnnotation,
     },
     Directive(Directive),
+    Constraint(AccountConstraint),
+    AccountClose {
+        expr: TypedExpression,
+        name: String,
+        // The account that gets the rent of a program account, which Anchor closes at the end of
+        // the instruction. Token accounts are closed immediately with a CPI instead
+        to: Option<String>,
+    },
+    AccountRealloc {
+        expr: TypedExpression,
+        name: String,
+        realloc: AccountConstraint,
+    },
+    SignerSeeds {
+        expr: TypedExpression,
+        name: String,
+    },
+    EmitCpi(TypedExpression),
+}
+
+/// The authority of a CPI that's made without signer seeds, as it was passed (before being cast).
+#[derive(Clone, Debug)]
+pub enum CpiAuthority {
+    // A variable, which signs if it's one of the instruction's PDAs
+    Named(String),
+    // A program account, which can only sign as a PDA - named if it was passed as a variable
+    ProgramAccount(Option<String>),
+    // Anything else, which never signs
+    Other,
+}
+
+pub type RenderSigned = dyn Fn(&[String]) -> TypedExpression;
+
+/// Renders a CPI signed with the seeds of the given PDAs (by name).
+#[derive(Clone)]
+pub struct CpiSigner(pub Rc<RenderSigned>);
+
+impl CpiSigner {
+    pub fn new(render: impl Fn(&[String]) -> TypedExpression + 'static) -> Self {
+        Self(Rc::new(render))
+    }
+}
+
+impl std::fmt::Debug for CpiSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CpiSigner")
+    }
 }
 
 /// Build context for a single function.
 struct Context {
     ix_context: Option<InstructionContext>,
     directives: Option<Vec<Directive>>,
+    // Constraints of the account param whose default is being built
+    constraints: Option<Vec<AccountConstraint>>,
+    // Accounts that were closed, and can't be used anymore
+    closed_accounts: BTreeSet<String>,
+    // How many blocks deep the statement being built is (the function body is 1)
+    block_depth: usize,
+    // Paths of the accounts that are declared with @zero_copy
+    zero_copy_accounts: BTreeSet<Vec<String>>,
+    // Fields of every account that's defined in the program, by path
+    account_fields: BTreeMap<Vec<String>, HashMap<String, Ty>>,
     expr_order: VecDeque<Ty>,
     assign_order: VecDeque<Assign>,
+    // Labels of the loops that are being built, innermost last. Only loops with an `else` block
+    // get a label
+    loop_labels: Vec<Option<String>>,
+    // Optionals that are narrowed in the blocks being built, innermost last, and whether they get
+    // assigned to
+    narrowed: Vec<(String, bool)>,
+    // Write backs to narrowed optionals that follow the statement being built
+    write_backs: Vec<Statement>,
+    // Variables that are declared or assigned to in the function body so far
+    body_vars: BTreeSet<String>,
+    // Every variable that's been used so far, in order
+    used_vars: Vec<String>,
+    // Variables used by the args of the call that's being transformed
+    call_vars: Vec<String>,
 }
 
 impl From<TypecheckOutput> for Context {
@@ -181,8 +409,19 @@ impl From<TypecheckOutput> for Context {
         Self {
             ix_context: None,
             directives: None,
+            constraints: None,
+            closed_accounts: BTreeSet::new(),
+            block_depth: 0,
+            zero_copy_accounts: BTreeSet::new(),
+            account_fields: BTreeMap::new(),
             expr_order: typecheck.expr_order.into(),
             assign_order: typecheck.assign_order.into(),
+            loop_labels: vec![],
+            narrowed: vec![],
+            write_backs: vec![],
+            body_vars: BTreeSet::new(),
+            used_vars: vec![],
+            call_vars: vec![],
         }
     }
 }
@@ -193,6 +432,15 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
     let is_mut = ty.is_mut();
     match (ty_expr.1, ty) {
         (ast::TyExpressionObj::Generic { base, params }, Ty::Generic(name, ty_params)) => {
+            // Type params that were dropped from the type (like the capacity in `Dict[K, V, N]`)
+            // are kept around as the max capacity of the collection
+            let capacity = match params.last() {
+                Some(Located(_, ast::TyExpressionObj::Const(n))) if params.len() > ty_params.len() => {
+                    Some(*n as usize)
+                }
+                _ => None,
+            };
+
             let mut params = params
                 .into_iter()
                 .zip(ty_params.into_iter())
@@ -205,7 +453,7 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
                 Mutability::Immutable
             };
 
-            match name {
+            let ty_expr = match name {
                 TyName::Builtin(builtin) => match builtin {
                     // Tuple[...] -> (...)
                     Builtin::Python(Python::Tuple) => TyExpr::Tuple(params),
@@ -224,6 +472,20 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
                         params,
                         is_loadable: false
                     },
+                    // Dict[K, V] -> BTreeMap<K, V>
+                    Builtin::Python(Python::Dict) => TyExpr::Generic {
+                        mutability,
+                        name: vec!["BTreeMap".to_string()],
+                        params,
+                        is_loadable: false
+                    },
+                    // Optional[T] -> Option<T>
+                    Builtin::Python(Python::Optional) => TyExpr::Generic {
+                        mutability: Mutability::Immutable,
+                        name: vec!["Option".to_string()],
+                        params,
+                        is_loadable: false
+                    },
                     // str -> String
                     Builtin::Python(Python::Str) => TyExpr::Generic {
                         mutability,
@@ -267,6 +529,43 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
                         ],
                         is_loadable: false
                     },
+                    // Token2022Mint -> WithPrograms<'info, '_, Box<InterfaceAccount<'info, Mint>>>
+                    // Token2022Account -> WithPrograms<'info, '_, Box<InterfaceAccount<'info, TokenAccount>>>
+                    Builtin::Prelude(
+                        prelude @ (Prelude::Token2022Mint | Prelude::Token2022Account),
+                    ) => {
+                        let account = match prelude {
+                            Prelude::Token2022Mint => "Mint",
+                            _ => "TokenAccount",
+                        };
+
+                        TyExpr::Generic {
+                            mutability: Mutability::Immutable,
+                            name: vec!["WithPrograms".to_string()],
+                            params: vec![
+                                TyExpr::InfoLifetime,
+                                TyExpr::AnonLifetime,
+                                TyExpr::Generic {
+                                    mutability: Mutability::Immutable,
+                                    name: vec!["Box".to_string()],
+                                    params: vec![TyExpr::Generic {
+                                        mutability: Mutability::Immutable,
+                                        name: vec!["InterfaceAccount".to_string()],
+                                        params: vec![
+                                            TyExpr::InfoLifetime,
+                                            TyExpr::new_specific(
+                                                vec!["anchor_spl", "token_interface", account],
+                                                Mutability::Immutable,
+                                            ),
+                                        ],
+                                        is_loadable: false,
+                                    }],
+                                    is_loadable: false,
+                                },
+                            ],
+                            is_loadable: false,
+                        }
+                    }
                     // Program, UncheckedAccount, pyth.PriceAccount -> UncheckedAccount<'info>
                     Builtin::Prelude(Prelude::Program | Prelude::UncheckedAccount)
                     | Builtin::Pyth(Pyth::PriceAccount) => TyExpr::Generic {
@@ -295,7 +594,7 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
                 },
                 TyName::Defined(
                     _,
-                    DefinedType::Struct,
+                    DefinedType::Struct | DefinedType::Event,
                 ) => TyExpr::Generic {
                     mutability,
                     name: base,
@@ -304,7 +603,7 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
                 },
                 TyName::Defined(
                     _,
-                    DefinedType::Enum | DefinedType::Event,
+                    DefinedType::Enum | DefinedType::ErrorCode,
                 ) => TyExpr::Generic {
                     mutability,
                     name: base,
@@ -312,6 +611,14 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
                     is_loadable: false
                 },
                 TyName::Defined(_, DefinedType::Account) => TyExpr::Account(base),
+            };
+
+            match capacity {
+                Some(capacity) => TyExpr::Bounded {
+                    inner: ty_expr.into(),
+                    capacity,
+                },
+                None => ty_expr,
             }
         }
         (ast::TyExpressionObj::Const(..), Ty::Const(size)) => TyExpr::Const(size as usize),
@@ -319,29 +626,295 @@ fn make_ty_expr(ty_expr: ast::TyExpression, ty: Ty) -> TyExpr {
     }
 }
 
-fn make_account_ty_expr(ty: Ty) -> AccountTyExpr {
+fn make_account_ty_expr(ty: Ty, zero_copy_accounts: &BTreeSet<Vec<String>>) -> AccountTyExpr {
     match ty {
         Ty::Generic(name, params) => match name {
             TyName::Builtin(Builtin::Prelude(builtin)) => match builtin {
-                Prelude::Empty => {
-                    AccountTyExpr::Empty(make_account_ty_expr(params[0].clone()).into())
-                }
+                Prelude::Empty => AccountTyExpr::Empty(
+                    make_account_ty_expr(params[0].clone(), zero_copy_accounts).into(),
+                ),
                 Prelude::Signer => AccountTyExpr::Signer,
                 Prelude::Program => AccountTyExpr::UncheckedAccount,
                 Prelude::TokenMint => AccountTyExpr::TokenMint,
                 Prelude::TokenAccount => AccountTyExpr::TokenAccount,
+                Prelude::Token2022Mint => AccountTyExpr::Token2022Mint,
+                Prelude::Token2022Account => AccountTyExpr::Token2022Account,
                 Prelude::UncheckedAccount => AccountTyExpr::UncheckedAccount,
                 Prelude::Clock => AccountTyExpr::ClockSysvar,
                 _ => panic!(),
             },
             TyName::Builtin(Builtin::Pyth(Pyth::PriceAccount)) => AccountTyExpr::UncheckedAccount,
-            TyName::Defined(name, DefinedType::Account) => AccountTyExpr::Defined(name),
+            TyName::Defined(name, DefinedType::Account) => {
+                if zero_copy_accounts.contains(&name) {
+                    AccountTyExpr::ZeroCopy(name)
+                } else {
+                    AccountTyExpr::Defined(name)
+                }
+            }
             _ => panic!(),
         },
         _ => panic!(),
     }
 }
 
+/// Translate a formatted f-string component into a `format!` placeholder and its argument.
+fn format_arg(
+    part: TypedExpression,
+    spec: ast::FormatSpec,
+) -> Result<(String, TypedExpression), Error> {
+    use ast::FormatKind;
+
+    // A repr is formatted like a string
+    let (is_int, is_float) = if spec.repr {
+        (false, false)
+    } else {
+        (part.ty.is_int(), part.ty.is_float())
+    };
+    let is_number = is_int || is_float;
+
+    let fits = match spec.kind {
+        None => !spec.sign && spec.grouping.is_none() && !spec.zero || is_number,
+        Some(FormatKind::Str) => !is_number,
+        Some(FormatKind::Float) => is_number,
+        Some(
+            FormatKind::Int
+            | FormatKind::Hex
+            | FormatKind::UpperHex
+            | FormatKind::Binary
+            | FormatKind::Octal,
+        ) => is_int,
+    };
+
+    if !fits || (is_int && spec.kind != Some(FormatKind::Float) && spec.precision.is_some()) {
+        return Err(Error::FormatSpecTy(spec.src, part.ty));
+    }
+
+    if is_float && spec.kind.is_none() && spec.precision.is_some() {
+        return Err(Error::FormatSpecFloatPrecision(spec.src));
+    }
+
+    let part = if is_int && spec.kind == Some(FormatKind::Float) {
+        TypedExpression {
+            ty: Ty::prelude(Prelude::RustFloat, vec![]),
+            obj: ExpressionObj::As {
+                value: part.into(),
+                ty: TyExpr::new_specific(vec!["f64"], Mutability::Immutable),
+            },
+        }
+    } else if is_signed_int(&part.ty)
+        && matches!(
+            spec.kind,
+            Some(FormatKind::Hex | FormatKind::UpperHex | FormatKind::Binary | FormatKind::Octal)
+        )
+    {
+        // Rust would format negative numbers as their two's complement
+        TypedExpression {
+            ty: Ty::prelude(Prelude::RustInt(true, 128), vec![]),
+            obj: ExpressionObj::Rendered(quote! { SignedInt(i128::from(#part)) }),
+        }
+    } else {
+        part
+    };
+
+    let mut padding = String::new();
+    if let Some(fill) = spec.fill {
+        padding.push(fill);
+    }
+    match spec.align {
+        Some(ast::FormatAlign::Left) => padding.push('<'),
+        Some(ast::FormatAlign::Right) => padding.push('>'),
+        Some(ast::FormatAlign::Center) => padding.push('^'),
+        None => {}
+    }
+
+    let mut options = String::new();
+    if spec.sign {
+        options.push('+');
+    }
+    if spec.alternate {
+        options.push('#');
+    }
+    if spec.zero {
+        options.push('0');
+    }
+
+    let width = spec.width.map_or(String::new(), |width| width.to_string());
+    let precision = spec
+        .precision
+        .map_or(String::new(), |precision| format!(".{}", precision));
+
+    let kind = match spec.kind {
+        Some(FormatKind::Hex) => "x",
+        Some(FormatKind::UpperHex) => "X",
+        Some(FormatKind::Binary) => "b",
+        Some(FormatKind::Octal) => "o",
+        _ if spec.repr || !part.ty.is_display() => "?",
+        _ => "",
+    };
+
+    let placeholder = |spec: String| match spec.as_str() {
+        "" => "{}".to_string(),
+        spec => format!("{{:{}}}", spec),
+    };
+
+    let grouping = match spec.grouping {
+        Some(grouping) => grouping,
+        None => {
+            return Ok((
+                placeholder(format!("{}{}{}{}{}", padding, options, width, precision, kind)),
+                part,
+            ));
+        }
+    };
+
+    // Rust has no thousands separator, so the number is formatted and grouped first, then padded
+    let inner = placeholder(format!("{}{}{}", options, precision, kind));
+    let size = proc_macro2::Literal::usize_unsuffixed(match spec.kind {
+        Some(FormatKind::Hex | FormatKind::UpperHex | FormatKind::Binary | FormatKind::Octal) => 4,
+        _ => 3,
+    });
+    // Numbers are right-aligned by default, but the grouped digits are a string
+    if spec.align.is_none() && spec.width.is_some() {
+        padding.push('>');
+    }
+
+    Ok((
+        placeholder(format!("{}{}", padding, width)),
+        TypedExpression {
+            ty: Ty::python(Python::Str, vec![]),
+            obj: ExpressionObj::Rendered(quote! {
+                group_digits(format!(#inner, #part), #grouping, #size)
+            }),
+        },
+    ))
+}
+
+fn is_signed_int(ty: &Ty) -> bool {
+    matches!(
+        ty,
+        Ty::Generic(
+            TyName::Builtin(Builtin::Prelude(Prelude::RustInt(true, _))),
+            _
+        ) | Ty::IntParam(_)
+    )
+}
+
+/// Add a constraint to an account of an instruction. Reallocs also need the system program, and
+/// can only be declared once per account.
+fn add_constraint(
+    ix_context: &mut InstructionContext,
+    name: &String,
+    constraint: AccountConstraint,
+) -> Result<(), Error> {
+    if let AccountConstraint::Realloc { payer, .. } = &constraint {
+        match &payer.obj {
+            ExpressionObj::Id(payer)
+                if payer != name && ix_context.accounts.iter().any(|(name, _)| name == payer) => {}
+            _ => return Err(Error::ReallocPayer(name.clone())),
+        }
+
+        ix_context.inferred_accounts.insert(
+            "system_program".to_string(),
+            ContextAccount {
+                account_ty: AccountTyExpr::SystemProgram,
+                annotation: None,
+                ty: None,
+            },
+        );
+    }
+
+    let account = match ix_context
+        .accounts
+        .iter_mut()
+        .find(|(name_, _)| name == name_)
+    {
+        Some((_, account)) => account,
+        None => return Err(Error::ConstraintOnNonAccount(name.clone())),
+    };
+
+    if let AccountConstraint::Realloc { .. } = &constraint {
+        if !matches!(account.account_ty, AccountTyExpr::Defined(..)) {
+            return Err(Error::ReallocTarget(name.clone()));
+        }
+
+        let constraints = &account.annotation.as_ref().unwrap().constraints;
+        if constraints
+            .iter()
+            .any(|constraint| matches!(constraint, AccountConstraint::Realloc { .. }))
+        {
+            return Err(Error::DuplicateRealloc(name.clone()));
+        }
+    }
+
+    if let AccountConstraint::Seeds { .. } = &constraint {
+        if let AccountTyExpr::Empty(..) = &account.account_ty {
+            return Err(Error::SeedsOnEmpty(name.clone()));
+        }
+
+        let constraints = &account.annotation.as_ref().unwrap().constraints;
+        if constraints
+            .iter()
+            .any(|constraint| matches!(constraint, AccountConstraint::Seeds { .. }))
+        {
+            return Err(Error::DuplicateSeeds(name.clone()));
+        }
+    }
+
+    account
+        .annotation
+        .as_mut()
+        .unwrap()
+        .constraints
+        .push(constraint);
+
+    Ok(())
+}
+
+/// Add a program that an instruction makes CPIs to as an inferred account of the instruction.
+fn infer_program(ix_context: &mut InstructionContext, program: AccountTyExpr) {
+    let name = match &program {
+        AccountTyExpr::SystemProgram => "system_program",
+        AccountTyExpr::TokenProgram => "token_program",
+        AccountTyExpr::Token2022Program => "token_2022_program",
+        _ => panic!(),
+    }
+    .to_string();
+
+    ix_context.inferred_accounts.insert(
+        name,
+        ContextAccount {
+            account_ty: program,
+            annotation: None,
+            ty: None,
+        },
+    );
+}
+
+/// Get the names of the variables that an assignment target assigns to directly.
+fn assigned_vars(target: &ast::Expression) -> Vec<String> {
+    match &target.1 {
+        ast::ExpressionObj::Id(name) => vec![name.clone()],
+        ast::ExpressionObj::Tuple(targets) => targets.iter().flat_map(assigned_vars).collect(),
+        _ => vec![],
+    }
+}
+
+/// Name of the reference to the optional that a narrowed variable is unwrapped from.
+fn narrowed_optional(name: &str) -> String {
+    format!("__{}_optional__", name)
+}
+
+/// Check whether an account of the instruction is a PDA with known seeds.
+fn has_pda_seeds(ix_context: &InstructionContext, name: &String) -> bool {
+    ix_context.accounts.iter().any(|(name_, account)| {
+        name == name_
+            && account
+                .annotation
+                .as_ref()
+                .is_some_and(|annotation| annotation.pda_seeds().is_some())
+    })
+}
+
 impl Context {
     fn build_func(
         &mut self,
@@ -363,12 +936,29 @@ impl Context {
                 Ty::Type(TyName::Builtin(Builtin::Prelude(prelude::Prelude::Instruction)), _) => {
                     let name = format!("{}", name.to_pascal_case());
 
+                    if signature.returns.has_account() {
+                        let loc = def_returns.as_ref().map_or(loc, |Located(loc, _)| loc);
+
+                        return Err(Error::InstructionReturnTy(signature.returns.clone()).core(loc));
+                    }
+
+                    if signature.returns.has_tuple() {
+                        let loc = def_returns.as_ref().map_or(loc, |Located(loc, _)| loc);
+
+                        return Err(
+                            Error::InstructionReturnTuple(signature.returns.clone()).core(loc)
+                        );
+                    }
+
                     let params = signature
                         .params
                         .iter()
                         .zip(def_params.params.iter())
                         .filter_map(|((name, ty, _), Located(_, ParamObj { annotation, .. }))| {
-                            if !ty.is_account() {
+                            if !ty.is_account()
+                                && ty.optional_account().is_none()
+                                && ty.remaining_account().is_none()
+                            {
                                 Some((name.clone(), make_ty_expr(annotation.clone(), ty.clone())))
                             } else {
                                 None
@@ -376,33 +966,86 @@ impl Context {
                         })
                         .collect();
 
-                    let accounts = signature
-                        .params
-                        .iter()
-                        .zip(def_params.params.iter())
-                        .filter_map(|((name, ty, _), Located(_, ParamObj { annotation, .. }))| {
-                            if ty.is_account() {
-                                let mut account_annotation = AccountAnnotation::new();
-                                account_annotation.is_mut = ty.is_mut();
-                                Some((
-                                    name.clone(),
-                                    ContextAccount {
-                                        account_ty: make_account_ty_expr(ty.clone()),
-                                        annotation: Some(account_annotation),
-                                        ty: Some(make_ty_expr(annotation.clone(), ty.clone())),
-                                    },
-                                ))
-                            } else {
-                                None
+                    let mut accounts = vec![];
+                    for ((name, ty, _), Located(loc, ParamObj { annotation, .. })) in
+                        signature.params.iter().zip(def_params.params.iter())
+                    {
+                        // Optional accounts become `Option`s in the context, and can't be
+                        // initialized
+                        let (account, account_ty) = if ty.is_account() {
+                            (
+                                ty,
+                                make_account_ty_expr(ty.clone(), &self.zero_copy_accounts),
+                            )
+                        } else if let Some(account) = ty.optional_account() {
+                            match make_account_ty_expr(account.clone(), &self.zero_copy_accounts) {
+                                AccountTyExpr::Empty(..) | AccountTyExpr::ZeroCopy(..) => {
+                                    return Err(Error::OptionalAccountTy(account.clone()).core(loc));
+                                }
+                                account_ty => {
+                                    (account, AccountTyExpr::Optional(Box::new(account_ty)))
+                                }
                             }
-                        })
-                        .collect();
+                        } else {
+                            continue;
+                        };
+
+                        let mut account_annotation = AccountAnnotation::new();
+                        account_annotation.is_mut = account.is_mut();
+                        accounts.push((
+                            name.clone(),
+                            ContextAccount {
+                                account_ty,
+                                annotation: Some(account_annotation),
+                                ty: Some(make_ty_expr(annotation.clone(), ty.clone())),
+                            },
+                        ));
+                    }
+
+                    // A list of accounts takes the remaining accounts of the instruction
+                    let mut remaining_accounts = None;
+                    for ((name, ty, _), Located(loc, _)) in
+                        signature.params.iter().zip(def_params.params.iter())
+                    {
+                        let account = match ty.remaining_account() {
+                            Some(account) => account,
+                            None => continue,
+                        };
+
+                        if remaining_accounts.is_some() {
+                            return Err(Error::DuplicateRemainingAccounts.core(loc));
+                        }
+
+                        let account_ty = match account {
+                            Ty::Generic(
+                                TyName::Builtin(Builtin::Prelude(
+                                    prelude::Prelude::Account
+                                    | prelude::Prelude::Empty
+                                    | prelude::Prelude::Clock,
+                                )),
+                                _,
+                            ) => None,
+                            account => match make_account_ty_expr(
+                                account.clone(),
+                                &self.zero_copy_accounts,
+                            ) {
+                                AccountTyExpr::ZeroCopy(..) => None,
+                                account_ty => Some(account_ty),
+                            },
+                        }
+                        .ok_or(Error::RemainingAccountsTy(account.clone()).core(loc))?;
+
+                        remaining_accounts = Some((name.clone(), account_ty));
+                    }
 
                     ix_context = Some(InstructionContext {
                         name,
                         params,
                         accounts,
                         inferred_accounts: BTreeMap::new(),
+                        signer_seeds: BTreeSet::new(),
+                        remaining_accounts,
+                        emit_cpi: false,
                     });
                 }
                 dec => {
@@ -412,6 +1055,74 @@ impl Context {
         }
         self.ix_context = ix_context;
 
+        // Account constraints are given as param defaults
+        let mut def_params = def_params;
+        for Located(loc, ParamObj { arg, default, .. }) in def_params.params.iter_mut() {
+            let default = match default.take() {
+                Some(default) => default,
+                None => continue,
+            };
+
+            self.constraints = Some(vec![]);
+            let default = self.build_expression(default, vec![ExprContext::Seed].into())?;
+            let constraints = self.constraints.take().unwrap();
+
+            let is_constraint = |expr: &TypedExpression| match &expr.obj {
+                ExpressionObj::Placeholder => true,
+                ExpressionObj::Tuple(elements) => elements
+                    .iter()
+                    .all(|element| matches!(element.obj, ExpressionObj::Placeholder)),
+                _ => false,
+            };
+            if !is_constraint(&default) {
+                return Err(Error::InvalidParamDefault.core(loc));
+            }
+
+            let ix_context = match &mut self.ix_context {
+                Some(ix_context) => ix_context,
+                None => return Err(Error::MisplacedConstraint.core(loc)),
+            };
+
+            for constraint in constraints.iter() {
+                if let AccountConstraint::HasOne { field, .. } = constraint {
+                    if !ix_context.accounts.iter().any(|(name, _)| name == field) {
+                        return Err(Error::HasOneWithoutAccount(field.clone()).core(loc));
+                    }
+
+                    // The field of the constrained account has to hold a key
+                    let field_ty = signature
+                        .params
+                        .iter()
+                        .find(|(name, ..)| name == arg)
+                        .and_then(|(_, ty, _)| match ty {
+                            Ty::Generic(TyName::Defined(path, DefinedType::Account), _) => {
+                                self.account_fields.get(path)
+                            }
+                            _ => None,
+                        })
+                        .and_then(|fields| fields.get(field));
+
+                    if !matches!(
+                        field_ty,
+                        Some(Ty::Generic(
+                            TyName::Builtin(Builtin::Prelude(Prelude::Pubkey)),
+                            _
+                        ))
+                    ) {
+                        return Err(Error::HasOneWithoutField(arg.clone(), field.clone()).core(loc));
+                    }
+                }
+            }
+
+            if !ix_context.accounts.iter().any(|(name, _)| name == arg) {
+                return Err(Error::ConstraintOnNonAccount(arg.clone()).core(loc));
+            }
+
+            for constraint in constraints.into_iter() {
+                add_constraint(ix_context, arg, constraint).map_err(|err| err.core(loc))?;
+            }
+        }
+
         let params = signature
             .params
             .into_iter()
@@ -439,7 +1150,19 @@ impl Context {
             signature.returns,
         );
 
-        let body = self.build_block(body)?;
+        let mut body = self.build_block(body)?;
+
+        // Functions return a `Result` so that errors can be raised from anywhere, so functions
+        // that return nothing need to end with an `Ok(())`
+        if let TyExpr::Tuple(elements) = &returns {
+            if elements.is_empty() {
+                if let Some(Statement::Return(..)) = body.body.last() {
+                } else {
+                    body.implicit_return =
+                        Some(Box::new(ExpressionObj::Rendered(quote! { Ok(()) }).into()));
+                }
+            }
+        }
 
         return Ok(Function {
             ix_context: self.ix_context.take(),
@@ -452,10 +1175,13 @@ impl Context {
     }
 
     fn build_block(&mut self, block: Vec<ast::Statement>) -> CResult<Block> {
-        let body = block
-            .into_iter()
-            .map(|statement| self.build_statement(statement))
-            .collect::<Result<Vec<_>, CoreError>>()?;
+        self.block_depth += 1;
+        let mut body = vec![];
+        for statement in block.into_iter() {
+            body.push(self.build_statement(statement)?);
+            body.append(&mut self.write_backs);
+        }
+        self.block_depth -= 1;
 
         return Ok(Block {
             body,
@@ -463,9 +1189,56 @@ impl Context {
         });
     }
 
+    /// Build a block in which an optional variable might be narrowed to its inner type. Also
+    /// returns whether the narrowed variable gets assigned to, so the new value has to be written
+    /// back to the optional.
+    fn build_narrowed_block(
+        &mut self,
+        block: Vec<ast::Statement>,
+        narrowed: Option<String>,
+    ) -> CResult<(Block, bool)> {
+        let narrowed = match narrowed {
+            Some(name) => name,
+            None => return Ok((self.build_block(block)?, false)),
+        };
+
+        self.narrowed.push((narrowed, false));
+        let block = self.build_block(block)?;
+        let (_, assigned) = self.narrowed.pop().unwrap();
+
+        Ok((block, assigned))
+    }
+
+    /// Record the variables that an assignment assigns to. New values of narrowed variables are
+    /// written back to the optionals they were unwrapped from after the assignment.
+    fn record_assignment(&mut self, assigned: Vec<String>) {
+        self.body_vars.extend(assigned.iter().cloned());
+
+        for var in assigned {
+            let narrowed = self
+                .narrowed
+                .iter_mut()
+                .rev()
+                .find(|(name, _)| *name == var);
+
+            if let Some((name, assigned)) = narrowed {
+                *assigned = true;
+
+                let optional = format_ident!("{}", narrowed_optional(name));
+                let name = format_ident!("{}", name);
+                self.write_backs.push(Statement::Assign {
+                    receiver: ExpressionObj::Rendered(quote! { *#optional }).into(),
+                    value: ExpressionObj::Rendered(quote! { Some(#name.clone()) }).into(),
+                });
+            }
+        }
+    }
+
     fn build_statement(&mut self, statement: ast::Statement) -> CResult<Statement> {
         let statement = match statement.1 {
-            ast::StatementObj::Break => Statement::Break,
+            ast::StatementObj::Break => Statement::Break {
+                label: self.loop_labels.last().cloned().flatten(),
+            },
             ast::StatementObj::Continue => Statement::Continue,
             ast::StatementObj::Return { value } => {
                 let value = value
@@ -475,28 +1248,46 @@ impl Context {
                 Statement::Return(value)
             }
             ast::StatementObj::Pass => Statement::Noop,
-            ast::StatementObj::Assert { test, msg } => Statement::AnchorRequire {
-                cond: self.build_expression(test, vec![].into())?,
-                msg: self.build_expression(msg.unwrap(), vec![ExprContext::Assert].into())?,
-            },
+            ast::StatementObj::Assert { test, msg } => {
+                let cond = self.build_expression(test, vec![].into())?;
+                let msg = self.build_expression(msg.unwrap(), vec![ExprContext::Assert].into())?;
+
+                if msg.ty.is_error_code() {
+                    Statement::Require { cond, error: msg }
+                } else {
+                    Statement::AnchorRequire { cond, msg }
+                }
+            }
+            ast::StatementObj::Raise { error } => {
+                Statement::Raise(self.build_expression(error, vec![].into())?)
+            }
             ast::StatementObj::Assign { target, value } => {
                 let assign = self.assign_order.pop_front().unwrap();
+                let mut assigned = assigned_vars(&target);
                 match assign {
                     Assign::Mutate => {
                         let receiver =
                             self.build_expression(target, vec![ExprContext::LVal].into())?;
                         let rval = self.build_expression(value, vec![].into())?;
 
+                        self.record_assignment(assigned);
+
                         Statement::Assign {
                             receiver,
                             value: rval,
                         }
                     }
-                    Assign::Declare { undeclared, target } => Statement::Let {
-                        undeclared,
-                        target: self.build_target(target),
-                        value: self.build_expression(value, vec![].into())?,
-                    },
+                    Assign::Declare { undeclared, target } => {
+                        assigned.retain(|var| !undeclared.contains(var));
+
+                        self.record_assignment(assigned);
+
+                        Statement::Let {
+                            undeclared,
+                            target: self.build_target(target),
+                            value: self.build_expression(value, vec![].into())?,
+                        }
+                    }
                 }
             }
             ast::StatementObj::OpAssign { target, op, value } => {
@@ -511,11 +1302,14 @@ impl Context {
                 //
                 // ...this increases the (theoretical) runtime complexity
                 // substantially. Like I said, stupid.
+                let assigned = assigned_vars(&target);
                 let expr_order = self.expr_order.clone();
                 let receiver_lval =
                     self.build_expression(target.clone(), vec![ExprContext::LVal].into())?;
                 self.expr_order = expr_order;
                 let receiver_rval = self.build_expression(target.clone(), vec![].into())?;
+                self.record_assignment(assigned);
+
                 Statement::Assign {
                     receiver: receiver_lval,
                     value: TypedExpression {
@@ -545,37 +1339,263 @@ impl Context {
                 }
             }
             ast::StatementObj::ExpressionObj { expression } => {
-                Statement::Expression(self.build_expression(expression, vec![].into())?)
+                let expression = self.build_expression(expression, vec![].into())?;
+
+                match expression.obj {
+                    // Calls that are fully handled by the compiler (like closing an account) leave
+                    // nothing behind
+                    ExpressionObj::Literal(Literal::Unit) => Statement::Noop,
+                    _ => Statement::Expression(expression),
+                }
+            }
+            ast::StatementObj::If { test, body, orelse } => {
+                let narrowed = narrowed_var(&test).map(|(var, in_body)| (var.clone(), in_body));
+                let cond = self.build_expression(test, vec![].into())?;
+
+                // The narrowed variable is unwrapped by an `if let`
+                let narrowed = match &cond.obj {
+                    ExpressionObj::Call { function, .. } => match &function.obj {
+                        ExpressionObj::Attribute { value, .. } => {
+                            narrowed.map(|(name, in_body)| (name, in_body, *value.clone()))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let narrowed_in = |in_body| match &narrowed {
+                    Some((name, narrowed_in_body, _)) if *narrowed_in_body == in_body => {
+                        Some(name.clone())
+                    }
+                    _ => None,
+                };
+
+                // An account that's closed in one branch can still be used in the other, and might
+                // be closed after the if statement
+                let closed_accounts = self.closed_accounts.clone();
+                let (body, body_write_back) = self.build_narrowed_block(body, narrowed_in(true))?;
+                let closed_in_body = std::mem::replace(&mut self.closed_accounts, closed_accounts);
+                let (orelse, orelse_write_back) = match orelse {
+                    Some(block) => {
+                        let (block, write_back) =
+                            self.build_narrowed_block(block, narrowed_in(false))?;
+                        (Some(block), write_back)
+                    }
+                    None => (None, false),
+                };
+                self.closed_accounts.extend(closed_in_body);
+
+                match (narrowed, orelse) {
+                    (Some((name, true, value)), orelse) => Statement::IfSome {
+                        write_back: body_write_back.then(|| narrowed_optional(&name)),
+                        name,
+                        value,
+                        body,
+                        orelse,
+                    },
+                    (Some((name, false, value)), Some(orelse)) => Statement::IfSome {
+                        write_back: orelse_write_back.then(|| narrowed_optional(&name)),
+                        name,
+                        value,
+                        body: orelse,
+                        orelse: Some(body),
+                    },
+                    (_, orelse) => Statement::If { cond, body, orelse },
+                }
+            }
+            ast::StatementObj::While { test, body, orelse } => {
+                let label = self.push_loop_label(orelse.is_some());
+                let statement = Statement::While {
+                    cond: self.build_expression(test, vec![].into())?,
+                    body: self.build_block(body)?,
+                };
+                self.loop_labels.pop();
+
+                self.build_loop_else(statement, label, orelse)?
             }
-            ast::StatementObj::If { test, body, orelse } => Statement::If {
-                cond: self.build_expression(test, vec![].into())?,
-                body: self.build_block(body)?,
-                orelse: orelse.map(|block| self.build_block(block)).transpose()?,
-            },
-            ast::StatementObj::While { test, body } => Statement::While {
-                cond: self.build_expression(test, vec![].into())?,
-                body: self.build_block(body)?,
-            },
             ast::StatementObj::For {
                 target: _,
                 iter,
                 body,
+                orelse,
             } => {
+                let label = self.push_loop_label(orelse.is_some());
                 let assign = self.assign_order.pop_front().unwrap();
-                match assign {
+                let statement = match assign {
                     Assign::Declare { target, .. } => Statement::For {
                         target: self.build_target(target),
                         iter: self.build_expression(iter, vec![].into())?,
                         body: self.build_block(body)?,
                     },
                     _ => panic!(),
+                };
+                self.loop_labels.pop();
+
+                self.build_loop_else(statement, label, orelse)?
+            }
+            ast::StatementObj::Match { subject, cases } => {
+                let subject = self.build_expression(subject, vec![].into())?;
+                let is_str = matches!(
+                    &subject.ty,
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::Str)), _)
+                );
+
+                // Like with if statements, accounts closed in one case can still be used in the
+                // others
+                let closed_accounts = self.closed_accounts.clone();
+                let mut closed_in_cases = BTreeSet::new();
+
+                let mut arms = vec![];
+                for ast::MatchCase {
+                    pattern,
+                    guard,
+                    body,
+                } in cases.into_iter()
+                {
+                    self.closed_accounts = closed_accounts.clone();
+                    let pattern = self.build_pattern(pattern)?;
+                    self.body_vars.extend(pattern.captures());
+                    let guard = guard
+                        .map(|guard| self.build_expression(guard, vec![].into()))
+                        .transpose()?;
+
+                    // Strings get captured as `&str`s, turn them back into `String`s
+                    let captures = match is_str {
+                        true => pattern.captures(),
+                        false => vec![],
+                    };
+                    let mut body = self.build_block(body)?;
+                    for name in captures.into_iter().rev() {
+                        let value = format_ident!("{}", name);
+
+                        body.body.insert(
+                            0,
+                            Statement::Let {
+                                undeclared: vec![name.clone()],
+                                target: LetTarget::Var {
+                                    name,
+                                    is_mut: true,
+                                },
+                                value: ExpressionObj::Rendered(quote! { #value.to_string() })
+                                    .into(),
+                            },
+                        );
+                    }
+
+                    arms.push(MatchArm {
+                        pattern,
+                        guard,
+                        body,
+                    });
+                    closed_in_cases.append(&mut self.closed_accounts);
+                }
+                self.closed_accounts = closed_accounts;
+                self.closed_accounts.append(&mut closed_in_cases);
+
+                Statement::Match {
+                    subject,
+                    is_str,
+                    arms,
                 }
             }
+            ast::StatementObj::Delete { target } => {
+                let (value, index) = match1!(target.1, ast::ExpressionObj::Index { value, index } => (*value, *index));
+                let value = self.build_expression(value, vec![].into())?;
+                let index = self.build_expression(index, vec![].into())?;
+
+                Statement::Expression(
+                    ExpressionObj::Rendered(quote! {
+                        #value.borrow_mut().remove_key(&#index)?
+                    })
+                    .into(),
+                )
+            }
         };
 
         return Ok(statement);
     }
 
+    /// Enter a loop, giving it a label if it has an `else` block (so that a `break` from inside
+    /// of the loop can skip the block).
+    fn push_loop_label(&mut self, has_orelse: bool) -> Option<String> {
+        let label = if has_orelse {
+            Some(format!("__loop_{}__", self.loop_labels.len()))
+        } else {
+            None
+        };
+        self.loop_labels.push(label.clone());
+
+        label
+    }
+
+    /// Attach the `else` block of a loop, if it has one.
+    fn build_loop_else(
+        &mut self,
+        statement: Statement,
+        label: Option<String>,
+        orelse: Option<Vec<ast::Statement>>,
+    ) -> CResult<Statement> {
+        let (label, orelse) = match (label, orelse) {
+            (Some(label), Some(orelse)) => (label, orelse),
+            _ => {
+                return Ok(statement);
+            }
+        };
+
+        // {
+        //     let mut completed = false;
+        //     'label: loop {
+        //         (the loop, where a `break` becomes `break 'label`)
+        //         completed = true;
+        //         break;
+        //     }
+        //     if completed {
+        //         (the else block)
+        //     }
+        // }
+        // Running the else block outside of the labeled loop means that any `break` or `continue`
+        // inside of it still refers to the loop around it.
+        let completed = label.replace("loop", "completed");
+
+        let block = Block {
+            body: vec![
+                Statement::Let {
+                    undeclared: vec![completed.clone()],
+                    target: LetTarget::Var {
+                        name: completed.clone(),
+                        is_mut: true,
+                    },
+                    value: ExpressionObj::Literal(Literal::Bool(false)).into(),
+                },
+                Statement::Loop {
+                    label: Some(label),
+                    body: Block {
+                        body: vec![
+                            statement,
+                            Statement::Let {
+                                undeclared: vec![],
+                                target: LetTarget::Var {
+                                    name: completed.clone(),
+                                    is_mut: true,
+                                },
+                                value: ExpressionObj::Literal(Literal::Bool(true)).into(),
+                            },
+                            Statement::Break { label: None },
+                        ],
+                        implicit_return: None,
+                    },
+                },
+                Statement::If {
+                    cond: ExpressionObj::Id(completed).into(),
+                    body: self.build_block(orelse)?,
+                    orelse: None,
+                },
+            ],
+            implicit_return: None,
+        };
+
+        Ok(Statement::Expression(ExpressionObj::Block(block).into()))
+    }
+
     fn build_expression(
         &mut self,
         expression: ast::Expression,
@@ -592,16 +1612,44 @@ impl Context {
 
         let obj = match obj {
             ast::ExpressionObj::BinOp { left, op, right } => {
+                // `x is None` only needs to look at the discriminant, since `T` might not
+                // implement `PartialEq`
+                let left_none = matches!(left.1, ast::ExpressionObj::None);
+                let right_none = matches!(right.1, ast::ExpressionObj::None);
+
                 let left = self.build_expression(*left, context_stack.clone())?;
                 let right = self.build_expression(*right, context_stack.clone())?;
 
-                self.build_op(left, op, right)
+                let optional = match (left_none, right_none) {
+                    (false, true) => left.ty.optional_of().is_some(),
+                    (true, false) => right.ty.optional_of().is_some(),
+                    _ => false,
+                };
+                let test = match op {
+                    ast::Operator::Eq if optional => Some("is_none"),
+                    ast::Operator::NotEq if optional => Some("is_some"),
+                    _ => None,
+                };
+
+                match test {
+                    Some(test) => ExpressionObj::Call {
+                        function: ExpressionObj::Attribute {
+                            value: if left_none { right } else { left }.into(),
+                            name: test.into(),
+                        }
+                        .into(),
+                        args: vec![],
+                    },
+                    None => self.build_op(left, op, right),
+                }
             }
             ast::ExpressionObj::Index { value, index } => ExpressionObj::Index {
                 value: {
                     let mut value = self.build_expression(*value, context_stack.clone())?;
 
-                    if value.ty.is_mut() {
+                    // Accounts in seeds and constraints are plain Anchor accounts, so their
+                    // collections are indexed in place
+                    if value.ty.is_mut() && !context_stack.has(&ExprContext::Seed) {
                         value.obj = if context_stack.has(&ExprContext::LVal) {
                             ExpressionObj::BorrowMut(value.obj.into())
                         } else {
@@ -614,6 +1662,44 @@ impl Context {
                 .into(),
                 index: self.build_expression(*index, context_stack.clone())?.into(),
             },
+            ast::ExpressionObj::Slice {
+                value,
+                start,
+                stop,
+                step,
+            } => {
+                let value = self.build_expression(*value, context_stack.clone())?;
+
+                let mut parts = vec![];
+                for part in [start, stop, step] {
+                    parts.push(match part {
+                        Some(part) => {
+                            let part = self.build_expression(*part, context_stack.clone())?;
+                            quote! { Some(#part.into()) }
+                        }
+                        None => quote! { None },
+                    });
+                }
+
+                match &value.ty {
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::Str)), _) => {
+                        ExpressionObj::Rendered(quote! {
+                            #value.slice_wrapped(#(#parts),*)
+                        })
+                    }
+                    _ => {
+                        let sliced = quote! {
+                            #value.borrow().slice_wrapped(#(#parts),*)
+                        };
+
+                        if context_stack.has_any(&[ExprContext::LVal, ExprContext::Seed]) {
+                            ExpressionObj::Rendered(sliced)
+                        } else {
+                            ExpressionObj::Rendered(quote! { Mutable::new(#sliced) })
+                        }
+                    }
+                }
+            }
             ast::ExpressionObj::UnOp { op, value } => ExpressionObj::UnOp {
                 op: match op {
                     ast::UnaryOperator::Pos => UnaryOperator::Pos,
@@ -632,7 +1718,8 @@ impl Context {
                         name,
                     },
                     ty => {
-                        if ty.is_mut() {
+                        // Accounts in seeds and constraints are plain Anchor accounts
+                        if ty.is_mut() && !context_stack.has(&ExprContext::Seed) {
                             // Methods of custom types are impl'd on `Mutable<T>`, not `T` - this
                             // means we should not borrow the value if the attribute leads us to a
                             // function.
@@ -646,6 +1733,19 @@ impl Context {
                             }
                         }
 
+                        // ...but zero-copy ones are `AccountLoader`s, so their fields are only
+                        // reachable through a loaded ref
+                        if let Ty::Generic(TyName::Defined(path, DefinedType::Account), _) = ty {
+                            let is_field = !matches!(&expr_ty, Ty::Function(..));
+
+                            if is_field
+                                && context_stack.has(&ExprContext::Seed)
+                                && self.zero_copy_accounts.contains(path)
+                            {
+                                value.obj = ExpressionObj::Rendered(quote! { #value.load()? });
+                            }
+                        }
+
                         ExpressionObj::Attribute {
                             value: value.into(),
                             name,
@@ -683,38 +1783,35 @@ impl Context {
                 };
 
                 let order = order_args(&args, params, &loc)?;
-                let mut args = vec![];
-                for arg in order.into_iter() {
-                    match arg {
-                        OrderedArg::Pos(pos) => {
-                            args.push(
-                                self.build_expression(pos.clone(), context_stack.clone())?
-                                    .moved(&context_stack),
-                            );
-                        }
+                let first_used = self.used_vars.len();
+                // Args are built in the same order that they were checked in
+                let mut args = vec![None; order.len()];
+                for i in arg_check_order(&order) {
+                    args[i] = Some(match &order[i] {
+                        OrderedArg::Pos(pos) => self
+                            .build_expression((*pos).clone(), context_stack.clone())?
+                            .moved(&context_stack),
                         OrderedArg::Var(var) => {
                             let variadic = var
-                                .into_iter()
+                                .iter()
                                 .map(|arg| {
-                                    self.build_expression(arg.clone(), context_stack.clone())
+                                    self.build_expression((*arg).clone(), context_stack.clone())
                                 })
                                 .collect::<Result<Vec<_>, CoreError>>()?;
 
-                            args.push(ExpressionObj::Vec(variadic).into());
+                            ExpressionObj::Vec(variadic).into()
                         }
-                        OrderedArg::Kw(Some(kw)) => {
-                            args.push(
-                                self.build_expression(kw.clone(), context_stack.clone())?
-                                    .moved(&context_stack),
-                            );
-                        }
-                        OrderedArg::Kw(None) => args.push(ExpressionObj::Placeholder.into()),
-                    }
+                        OrderedArg::Kw(Some(kw)) => self
+                            .build_expression((*kw).clone(), context_stack.clone())?
+                            .moved(&context_stack),
+                        OrderedArg::Kw(None) => ExpressionObj::Placeholder.into(),
+                    });
                 }
+                self.call_vars = self.used_vars[first_used..].to_vec();
 
                 ExpressionObj::Call {
                     function: function.into(),
-                    args,
+                    args: args.into_iter().map(Option::unwrap).collect(),
                 }
             }
             ast::ExpressionObj::Ternary { test, body, orelse } => ExpressionObj::Ternary {
@@ -722,6 +1819,33 @@ impl Context {
                 body: Box::new(self.build_expression(*body, context_stack.clone())?),
                 orelse: Box::new(self.build_expression(*orelse, context_stack.clone())?),
             },
+            ast::ExpressionObj::Lambda { params, body } => ExpressionObj::Closure {
+                params,
+                body: self
+                    .build_expression(*body, context_stack.with(ExprContext::Closure))?
+                    .into(),
+            },
+            ast::ExpressionObj::Bind { name, value, body } => {
+                // {
+                //     let name = value;
+                //     body
+                // }
+                let value = self.build_expression(*value, context_stack.clone())?;
+                let body = self.build_expression(*body, context_stack.clone())?;
+                self.body_vars.insert(name.clone());
+
+                ExpressionObj::Block(Block {
+                    body: vec![Statement::Let {
+                        undeclared: vec![name.clone()],
+                        target: LetTarget::Var {
+                            name,
+                            is_mut: false,
+                        },
+                        value,
+                    }],
+                    implicit_return: Some(body.into()),
+                })
+            }
             ast::ExpressionObj::Int(n) => ExpressionObj::Literal(Literal::Int(n)),
             ast::ExpressionObj::Float(n) => ExpressionObj::Literal(Literal::Float(n)),
             ast::ExpressionObj::List(list) => {
@@ -741,6 +1865,30 @@ impl Context {
                     vec
                 }
             }
+            ast::ExpressionObj::Dict(dict) => {
+                let mut keys = vec![];
+                let mut values = vec![];
+                for (key, value) in dict.into_iter() {
+                    keys.push(
+                        self.build_expression(key, context_stack.clone())?
+                            .moved(&context_stack),
+                    );
+                    values.push(
+                        self.build_expression(value, context_stack.clone())?
+                            .moved(&context_stack),
+                    );
+                }
+
+                let map = ExpressionObj::Rendered(quote! {
+                    BTreeMap::from([#((#keys, #values)),*])
+                });
+
+                if !context_stack.has_any(&[ExprContext::LVal, ExprContext::Seed]) {
+                    ExpressionObj::Mutable(map.into())
+                } else {
+                    map
+                }
+            }
             ast::ExpressionObj::Tuple(tuple) => ExpressionObj::Tuple(
                 tuple
                     .into_iter()
@@ -853,19 +2001,16 @@ impl Context {
 
                 for part in parts.into_iter() {
                     match part {
-                        ast::FStrPart::ExpressionObj(expr) => {
+                        ast::FStrPart::ExpressionObj(expr, spec) => {
                             let part = self.build_expression(expr, context_stack.clone())?;
+                            let (placeholder, part) =
+                                format_arg(part, spec).map_err(|err| err.core(&loc))?;
 
-                            if part.ty.is_display() {
-                                format.push_str("{}");
-                            } else {
-                                format.push_str("{:?}");
-                            }
-
+                            format.push_str(&placeholder);
                             parts_.push(part);
                         }
                         ast::FStrPart::Str(string) => {
-                            format.push_str(&string);
+                            format.push_str(&string.replace('{', "{{").replace('}', "}}"));
                         }
                     }
                 }
@@ -874,7 +2019,14 @@ impl Context {
                     format!(#format, #(#parts_),*)
                 })
             }
-            ast::ExpressionObj::Id(name) => ExpressionObj::Id(name),
+            ast::ExpressionObj::Id(name) => {
+                if self.closed_accounts.contains(&name) {
+                    return Err(Error::UseAfterClose(name).core(&loc));
+                }
+
+                self.used_vars.push(name.clone());
+                ExpressionObj::Id(name)
+            }
             ast::ExpressionObj::Bool(p) => ExpressionObj::Literal(Literal::Bool(p)),
             ast::ExpressionObj::None => ExpressionObj::Literal(Literal::Unit),
             ast::ExpressionObj::Iter { value } => {
@@ -913,26 +2065,83 @@ impl Context {
 
             let expression = match transformed {
                 Transformed::Expression(expression) => Ok(expression),
-                Transformed::Cpi { expr, program } => {
+                Transformed::Cpi {
+                    expr,
+                    program,
+                    authorities,
+                    signed,
+                } => {
                     if let Some(ix_context) = &mut self.ix_context {
-                        let name = match &program {
-                            AccountTyExpr::SystemProgram => "system_program",
-                            AccountTyExpr::TokenProgram => "token_program",
-                            _ => panic!(),
+                        if let Some(program) = program {
+                            infer_program(ix_context, program);
                         }
-                        .to_string();
 
-                        ix_context.inferred_accounts.insert(
-                            name,
-                            ContextAccount {
-                                account_ty: program,
-                                annotation: None,
-                                ty: None,
-                            },
-                        );
+                        // PDA authorities sign with their seeds automatically, and program
+                        // accounts can't sign any other way
+                        let mut pdas = BTreeSet::new();
+                        for authority in authorities.iter() {
+                            // A name that's assigned in the instruction doesn't (necessarily)
+                            // refer to the account anymore
+                            let name = match authority {
+                                CpiAuthority::Named(name)
+                                | CpiAuthority::ProgramAccount(Some(name))
+                                    if !self.body_vars.contains(name) =>
+                                {
+                                    Some(name.clone())
+                                }
+                                _ => None,
+                            };
+
+                            match (name, authority) {
+                                (Some(name), _)
+                                    if signed.is_some() && has_pda_seeds(ix_context, &name) =>
+                                {
+                                    pdas.insert(name);
+                                }
+                                (Some(name), CpiAuthority::ProgramAccount(_)) => {
+                                    return Err(Error::NoSignerSeeds(name).core(loc));
+                                }
+                                (None, CpiAuthority::ProgramAccount(_)) => {
+                                    return Err(Error::UnresolvedAuthority.core(loc));
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        match signed {
+                            Some(CpiSigner(signed)) if !pdas.is_empty() => {
+                                let expr = signed(&pdas.iter().cloned().collect::<Vec<_>>());
+                                ix_context.signer_seeds.extend(pdas);
+
+                                Ok(expr)
+                            }
+                            _ => Ok(expr),
+                        }
                     } else {
-                        return Err(Error::MisplacedCpi.core(loc));
+                        Err(Error::MisplacedCpi.core(loc))
                     }
+                }
+                Transformed::SignerSeeds { expr, name } => {
+                    let ix_context = match &mut self.ix_context {
+                        Some(ix_context) => ix_context,
+                        None => return Err(Error::MisplacedSignerSeeds.core(loc)),
+                    };
+
+                    if !has_pda_seeds(ix_context, &name) {
+                        return Err(Error::NoSignerSeeds(name).core(loc));
+                    }
+
+                    ix_context.signer_seeds.insert(name);
+
+                    Ok(expr)
+                }
+                Transformed::EmitCpi(expr) => {
+                    let ix_context = match &mut self.ix_context {
+                        Some(ix_context) => ix_context,
+                        None => return Err(Error::MisplacedEmitCpi.core(loc)),
+                    };
+
+                    ix_context.emit_cpi = true;
 
                     Ok(expr)
                 }
@@ -951,6 +2160,11 @@ impl Context {
                         let is_associated = annotation.is_associated;
 
                         let account = &mut ix_context.accounts.get_mut(index).unwrap().1;
+                        // Keep the constraints that were declared on the param
+                        let mut annotation = annotation;
+                        if let Some(declared) = account.annotation.take() {
+                            annotation.constraints = declared.constraints;
+                        }
                         account.annotation = Some(annotation);
 
                         ix_context.inferred_accounts.insert(
@@ -972,17 +2186,8 @@ impl Context {
 
                         // Token program is needed to init its own accounts
                         if let AccountTyExpr::Empty(account_ty) = &account.account_ty {
-                            if let AccountTyExpr::TokenMint | AccountTyExpr::TokenAccount =
-                                &**account_ty
-                            {
-                                ix_context.inferred_accounts.insert(
-                                    "token_program".to_string(),
-                                    ContextAccount {
-                                        account_ty: AccountTyExpr::TokenProgram,
-                                        annotation: None,
-                                        ty: None,
-                                    },
-                                );
+                            if let Some(program) = account_ty.token_program() {
+                                infer_program(ix_context, program);
                             }
                         }
 
@@ -1011,6 +2216,89 @@ impl Context {
                         Err(Error::MisplacedDirective.core(loc))
                     }
                 }
+                Transformed::AccountClose { expr, name, to } => {
+                    let ix_context = match &mut self.ix_context {
+                        Some(ix_context) => ix_context,
+                        None => return Err(Error::MisplacedClose.core(loc)),
+                    };
+
+                    match to {
+                        Some(to) => {
+                            // The close is declared on the account, so it always happens
+                            if self.block_depth > 1 {
+                                return Err(Error::ConditionalClose.core(loc));
+                            }
+
+                            if to == name
+                                || !ix_context.accounts.iter().any(|(name, _)| name == &to)
+                            {
+                                return Err(Error::CloseTarget(to).core(loc));
+                            }
+
+                            let account = match ix_context
+                                .accounts
+                                .iter_mut()
+                                .find(|(name_, _)| &name == name_)
+                            {
+                                Some((_, account)) => account,
+                                None => return Err(Error::MisplacedClose.core(loc)),
+                            };
+
+                            account.annotation.as_mut().unwrap().close =
+                                Some(ExpressionObj::Id(to).into());
+                        }
+                        None => {
+                            let program = ix_context
+                                .accounts
+                                .iter()
+                                .find(|(name_, _)| &name == name_)
+                                .and_then(|(_, account)| account.account_ty.token_program())
+                                .unwrap_or(AccountTyExpr::TokenProgram);
+
+                            infer_program(ix_context, program);
+                        }
+                    }
+
+                    self.closed_accounts.insert(name);
+
+                    Ok(expr)
+                }
+                Transformed::AccountRealloc {
+                    expr,
+                    name,
+                    realloc,
+                } => {
+                    let ix_context = match &mut self.ix_context {
+                        Some(ix_context) => ix_context,
+                        None => return Err(Error::MisplacedRealloc.core(loc)),
+                    };
+
+                    // Like a constraint, the realloc is declared on the account
+                    if self.block_depth > 1 {
+                        return Err(Error::ConditionalRealloc.core(loc));
+                    }
+
+                    // ...so it can't see anything that the instruction computes
+                    if let Some(var) = self
+                        .call_vars
+                        .iter()
+                        .find(|var| self.body_vars.contains(*var))
+                    {
+                        return Err(Error::ReallocBodyVar(var.clone()).core(loc));
+                    }
+
+                    add_constraint(ix_context, &name, realloc).map_err(|err| err.core(loc))?;
+
+                    Ok(expr)
+                }
+                Transformed::Constraint(constraint) => {
+                    if let Some(constraints) = &mut self.constraints {
+                        constraints.push(constraint);
+                        Ok(ExpressionObj::Placeholder.into())
+                    } else {
+                        Err(Error::MisplacedConstraint.core(loc))
+                    }
+                }
             }?;
 
             // Might be multiple transformations
@@ -1053,8 +2341,22 @@ impl Context {
                     ExpressionObj::Rendered(quote! { #left.pow(#right) })
                 };
             }
-            ast::Operator::In => todo!(),
-            ast::Operator::NotIn => todo!(),
+            ast::Operator::In | ast::Operator::NotIn => {
+                let contains = match &right.ty {
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::Dict)), _) => {
+                        quote! { #right.borrow().contains_key(&#left) }
+                    }
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::Str)), _) => {
+                        quote! { #right.contains(&#left) }
+                    }
+                    _ => quote! { #right.borrow().contains(&#left) },
+                };
+
+                return match op {
+                    ast::Operator::In => ExpressionObj::Rendered(contains),
+                    _ => ExpressionObj::Rendered(quote! { (!#contains) }),
+                };
+            }
         };
 
         ExpressionObj::BinOp {
@@ -1064,12 +2366,73 @@ impl Context {
         }
     }
 
-    fn build_target(&self, target: Target) -> LetTarget {
+    fn build_pattern(&mut self, pattern: ast::Pattern) -> CResult<Pattern> {
+        let pattern = match pattern.1 {
+            ast::PatternObj::Wildcard => Pattern::Wildcard,
+            ast::PatternObj::Capture(name) => Pattern::Capture(name),
+            ast::PatternObj::Literal(Located(_, literal)) => Pattern::Literal(match literal {
+                ast::ExpressionObj::Int(n) => Literal::Int(n),
+                ast::ExpressionObj::UnOp { value, .. } => {
+                    Literal::Int(-match1!(value.1, ast::ExpressionObj::Int(n) => n))
+                }
+                ast::ExpressionObj::Bool(p) => Literal::Bool(p),
+                ast::ExpressionObj::Str(s) => Literal::Str(s),
+                _ => panic!(),
+            }),
+            ast::PatternObj::Value(value) => {
+                Pattern::Value(self.build_expression(value, vec![].into())?)
+            }
+            ast::PatternObj::Class { cls, args, kwargs } => {
+                let cls = self.build_expression(cls, vec![].into())?;
+                let fields = match1!(&cls.ty, Ty::Function(params, _) => params)
+                    .iter()
+                    .map(|(name, ..)| name.clone())
+                    .collect::<Vec<_>>();
+
+                let args = args
+                    .into_iter()
+                    .map(|arg| self.build_pattern(arg))
+                    .collect::<CResult<Vec<_>>>()?;
+
+                // Struct variants have named fields, tuple variants don't
+                if fields.iter().any(|field| !field.is_empty()) {
+                    let mut named = fields.into_iter().zip(args).collect::<Vec<_>>();
+                    for (name, arg) in kwargs.into_iter() {
+                        named.push((name, self.build_pattern(arg)?));
+                    }
+
+                    Pattern::StructVariant(cls, named)
+                } else {
+                    Pattern::TupleVariant(cls, args)
+                }
+            }
+            ast::PatternObj::Tuple(elements) => Pattern::Tuple(
+                elements
+                    .into_iter()
+                    .map(|element| self.build_pattern(element))
+                    .collect::<CResult<Vec<_>>>()?,
+            ),
+            ast::PatternObj::Or(options) => Pattern::Or(
+                options
+                    .into_iter()
+                    .map(|option| self.build_pattern(option))
+                    .collect::<CResult<Vec<_>>>()?,
+            ),
+        };
+
+        Ok(pattern)
+    }
+
+    fn build_target(&mut self, target: Target) -> LetTarget {
         match target {
-            Target::Var(var) => LetTarget::Var {
-                name: var,
-                is_mut: true,
-            },
+            Target::Var(var) => {
+                self.body_vars.insert(var.clone());
+
+                LetTarget::Var {
+                    name: var,
+                    is_mut: true,
+                }
+            }
             Target::Tuple(tuple) => LetTarget::Tuple(
                 tuple
                     .into_iter()
@@ -1080,6 +2443,37 @@ impl Context {
     }
 }
 
+/// Find the signatures of all accounts, by path.
+fn find_accounts(
+    tree: &Tree<Signed>,
+    path: &mut Vec<String>,
+    found: &mut BTreeMap<Vec<String>, StructSignature>,
+) {
+    match tree {
+        Tree::Node(node) => {
+            for (name, tree) in node.iter() {
+                path.push(name.clone());
+                find_accounts(tree, path, found);
+                path.pop();
+            }
+        }
+        Tree::Leaf(signatures) => {
+            for (name, signature) in signatures.iter() {
+                if let Signature::Class(ClassSignature::Struct(
+                    signature @ StructSignature {
+                        is_account: true, ..
+                    },
+                )) = signature
+                {
+                    let mut path = path.clone();
+                    path.push(name.clone());
+                    found.insert(path, signature.clone());
+                }
+            }
+        }
+    }
+}
+
 pub struct BuildOutput {
     pub tree: Tree<Artifact>,
     pub origin: Vec<String>,
@@ -1098,6 +2492,19 @@ impl TryFrom<CheckOutput> for BuildOutput {
                 .zip(check_output.sign_output.namespace_output.tree.clone()),
         );
 
+        let mut accounts = BTreeMap::new();
+        find_accounts(&check_output.sign_output.tree, &mut vec![], &mut accounts);
+
+        let zero_copy_accounts = accounts
+            .iter()
+            .filter(|(_, signature)| signature.is_zero_copy)
+            .map(|(path, _)| path.clone())
+            .collect::<BTreeSet<_>>();
+        let account_fields = accounts
+            .into_iter()
+            .map(|(path, signature)| (path, signature.fields))
+            .collect::<BTreeMap<_, _>>();
+
         let mut tree = tree
             .map_with_path(|(mut contexts, (mut signatures, namespace)), abs| {
                 let mut artifact = Artifact {
@@ -1181,22 +2588,24 @@ impl TryFrom<CheckOutput> for BuildOutput {
                                         ) => match signature {
                                             Signature::Class(ClassSignature::Struct(
                                                 StructSignature {
-                                                    is_account, is_event, is_dataclass, fields: mut fields_map, methods: mut methods_map, ..
+                                                    is_account, is_event, is_dataclass, is_zero_copy, fields: mut fields_map, methods: mut methods_map, ..
                                                 },
                                             )) => {
                                                 let mut fields = vec![];
                                                 let mut methods = vec![];
                                                 let mut constructor = None;
 
-                                                for Located(_, statement) in body.into_iter() {
+                                                for Located(loc, statement) in body.into_iter() {
                                                     match statement {
                                                         ast::ClassDefStatementObj::FieldDef { name, ty: Some(ty_expr), .. } => {
                                                             let ty = fields_map.remove(&name).unwrap();
-                                                            fields.push((
-                                                                name,
-                                                                make_ty_expr(ty_expr, ty.clone()),
-                                                                ty
-                                                            ));
+                                                            let ty_expr = make_ty_expr(ty_expr, ty.clone());
+
+                                                            if is_account && ty_expr.has_unbounded_map() {
+                                                                return Err(Error::UnboundedDict(name).core(&loc));
+                                                            }
+
+                                                            fields.push((name, ty_expr, ty));
                                                         },
                                                         ast::ClassDefStatementObj::MethodDef(func) => {
                                                             let typecheck = match1!(context, FinalContext::Class(ref mut typechecks) => typechecks.remove(&func.name).unwrap());
@@ -1219,7 +2628,8 @@ impl TryFrom<CheckOutput> for BuildOutput {
                                                     TypeDef::Account(Account {
                                                         name: name.clone(),
                                                         fields,
-                                                        methods
+                                                        methods,
+                                                        is_zero_copy,
                                                     })
                                                 } else {
                                                     TypeDef::Struct(Struct {
@@ -1235,16 +2645,71 @@ impl TryFrom<CheckOutput> for BuildOutput {
                                                 artifact.type_defs.push(type_def);
                                             }
                                             Signature::Class(ClassSignature::Enum(
-                                                EnumSignature { variants },
+                                                EnumSignature { variants: signatures },
                                             )) => {
+                                                let mut variants = vec![];
+
+                                                for (Located(loc, statement), (name, signature)) in body.into_iter().zip(signatures) {
+                                                    let variant = match (statement, signature) {
+                                                        (_, VariantSignature::Unit) => EnumVariant::Unit,
+                                                        (
+                                                            ast::ClassDefStatementObj::FieldDef { ty: Some(ty_expr), .. },
+                                                            VariantSignature::Tuple(fields),
+                                                        ) => {
+                                                            for ty in fields.iter() {
+                                                                if ty.is_mut() {
+                                                                    return Err(Error::MutableVariantData(ty.clone()).core(&loc));
+                                                                }
+                                                            }
+
+                                                            // A `Tuple[...]` annotation gives one field per element
+                                                            let field_ty_exprs = match &ty_expr.1 {
+                                                                ast::TyExpressionObj::Generic { base, params } if base.last().map(|base| base == "Tuple").unwrap_or(false) => params.clone(),
+                                                                _ => vec![ty_expr],
+                                                            };
+
+                                                            EnumVariant::Tuple(
+                                                                field_ty_exprs
+                                                                    .into_iter()
+                                                                    .zip(fields)
+                                                                    .map(|(ty_expr, ty)| make_ty_expr(ty_expr, ty))
+                                                                    .collect(),
+                                                            )
+                                                        }
+                                                        (
+                                                            ast::ClassDefStatementObj::ClassDef { body, .. },
+                                                            VariantSignature::Struct(fields),
+                                                        ) => {
+                                                            let mut fields_ = vec![];
+                                                            for (Located(loc, field), (name, ty)) in body.into_iter().zip(fields) {
+                                                                if ty.is_mut() {
+                                                                    return Err(Error::MutableVariantData(ty).core(&loc));
+                                                                }
+
+                                                                let ty_expr = match1!(field, ast::ClassDefStatementObj::FieldDef { ty: Some(ty_expr), .. } => ty_expr);
+                                                                fields_.push((name, make_ty_expr(ty_expr, ty)));
+                                                            }
+
+                                                            EnumVariant::Struct(fields_)
+                                                        }
+                                                        _ => panic!(),
+                                                    };
+
+                                                    variants.push((name, variant));
+                                                }
+
                                                 artifact.type_defs.push(TypeDef::Enum(Enum {
                                                     name,
-                                                    variants: variants
-                                                        .into_iter()
-                                                        .map(|(name, _)| (name, None))
-                                                        .collect(),
+                                                    variants,
                                                 }));
                                             }
+                                            Signature::Class(ClassSignature::ErrorCode(
+                                                ErrorCodeSignature { variants },
+                                            )) => {
+                                                artifact.type_defs.push(TypeDef::ErrorCode(
+                                                    ErrorCode { name, variants },
+                                                ));
+                                            }
                                             _ => panic!(),
                                         },
                                         Located(
@@ -1254,9 +2719,17 @@ impl TryFrom<CheckOutput> for BuildOutput {
                                             let typecheck = match1!(context, FinalContext::Function(typecheck) => typecheck);
 
                                             let mut context: Context = typecheck.into();
+                                            context.zero_copy_accounts = zero_copy_accounts.clone();
+                                            context.account_fields = account_fields.clone();
                                             let signature = match1!(signature, Signature::Function(signature) => signature);
                                             let func = context.build_func(func, signature)?;
 
+                                            // Emitting events with CPIs needs Anchor's event-cpi
+                                            // feature
+                                            if func.ix_context.as_ref().is_some_and(|ix_context| ix_context.emit_cpi) {
+                                                artifact.features.insert(Feature::EventCpi);
+                                            }
+
                                             artifact.functions.push(func);
                                         }
                                         _ => {}
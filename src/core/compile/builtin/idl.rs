@@ -0,0 +1,871 @@
+//! IDL modules turn the instructions of other Anchor programs into typed CPI functions.
+//!
+//! An IDL file at `programs_py/my_dex.json` gets imported like any other module, with
+//! `from idl.my_dex import swap`. Each instruction in it becomes a function that takes the program,
+//! the instruction's accounts and its args, and serializes the instruction data the same way the
+//! other program's Anchor client would.
+//!
+//! The structs and enums in the IDL's `types` (and the account types in a legacy IDL's `accounts`)
+//! become Seahorse classes in the same module, written out as Seahorse source and compiled along
+//! with the program. Types that can't be written in Seahorse (generic types, tuple structs and
+//! zero-copy types, or types with fields like those) are left out. An instruction with an arg of one
+//! of those types can still be imported, but calling it is an error.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::core::compile::builtin::{
+    prelude::{cpi_authority, signer_seeds},
+    *,
+};
+use crate::core::generate::{stored_field, StoredTyExpr};
+pub use crate::core::{
+    compile::{ast::*, build::*, check::*, namespace::*, sign::*},
+    util::*,
+};
+use crate::match1;
+use base58::FromBase58;
+use heck::ToSnakeCase;
+use proc_macro2::{Literal as PM2Literal, TokenStream};
+use quote::quote;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+enum Error {
+    InvalidIdl(String),
+    InvalidAddress(String),
+    UnsupportedType(String, String, String),
+    DuplicateParam(String, String),
+}
+
+impl Error {
+    fn core(self) -> CoreError {
+        match self {
+            Self::InvalidIdl(what) => CoreError::make_raw(
+                "invalid IDL",
+                format!("Hint: {} is missing or has the wrong type.", what),
+            ),
+            Self::InvalidAddress(address) => {
+                CoreError::make_raw(format!("invalid IDL program address \"{}\"", address), "")
+            }
+            Self::UnsupportedType(instruction, arg, ty) => CoreError::make_raw(
+                format!(
+                    "arg \"{}\" of IDL instruction \"{}\" has an unsupported type",
+                    arg, instruction
+                ),
+                format!(
+                    "Hint: IDL instructions can only be called with args that are bools, integers, f64s, strings, public keys or the IDL's own structs and enums, or vecs, options and arrays of them. Generic types, tuple structs and zero-copy types aren't supported. This arg has type {}.",
+                    ty
+                ),
+            ),
+            Self::DuplicateParam(instruction, param) => CoreError::make_raw(
+                format!(
+                    "IDL instruction \"{}\" has more than one account or arg named \"{}\"",
+                    instruction, param
+                ),
+                "",
+            ),
+        }
+    }
+}
+
+/// An instruction from an IDL, callable as a CPI.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdlInstruction {
+    pub name: String,
+    /// Address of the program, if the IDL has one.
+    pub address: Option<Vec<u8>>,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<IdlAccount>,
+    pub args: Vec<(String, IdlType)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdlAccount {
+    pub name: String,
+    pub is_mut: bool,
+    pub is_signer: bool,
+}
+
+/// The types that instruction args and the fields of defined types can have. These are the IDL types
+/// with a Seahorse equivalent that serializes the same way, and the rest as unsupported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdlType {
+    Bool,
+    Int(bool, usize),
+    Float,
+    Str,
+    Pubkey,
+    Vec(Box<IdlType>),
+    Option(Box<IdlType>),
+    Array(Box<IdlType>, u64),
+    // One of the IDL's structs or enums, by its path in the module tree
+    Defined(Vec<String>, DefinedType),
+    // Any other type, as it's written in the IDL
+    Unsupported(String),
+}
+
+impl IdlType {
+    fn parse(ty: &Value, defined: &DefinedTypes) -> Self {
+        Self::parse_supported(ty, defined).unwrap_or_else(|| Self::Unsupported(ty.to_string()))
+    }
+
+    fn parse_supported(ty: &Value, defined: &DefinedTypes) -> Option<Self> {
+        if let Some(inner) = ty.get("vec") {
+            return Some(Self::Vec(Self::parse_supported(inner, defined)?.into()));
+        }
+        if let Some(inner) = ty.get("option") {
+            return Some(Self::Option(Self::parse_supported(inner, defined)?.into()));
+        }
+        if let Some(array) = ty.get("array") {
+            let element = Self::parse_supported(array.get(0)?, defined)?;
+            let len = array.get(1)?.as_u64()?;
+
+            return Some(Self::Array(element.into(), len));
+        }
+        // Legacy IDLs name the type directly, current IDLs give its name and generic args
+        if let Some(name) = ty.get("defined") {
+            let name = match name.as_str() {
+                Some(name) => name,
+                None if name
+                    .get("generics")
+                    .and_then(|generics| generics.as_array())
+                    .is_none_or(|generics| generics.is_empty()) =>
+                {
+                    name.get("name")?.as_str()?
+                }
+                None => return None,
+            };
+
+            return defined.get(name);
+        }
+
+        let ty = match ty.as_str()? {
+            "bool" => Self::Bool,
+            "f64" => Self::Float,
+            "string" => Self::Str,
+            "publicKey" | "pubkey" => Self::Pubkey,
+            // Borsh serializes bytes just like a vec of u8s
+            "bytes" => Self::Vec(Self::Int(false, 8).into()),
+            int => {
+                let signed = match int.get(..1)? {
+                    "u" => false,
+                    "i" => true,
+                    _ => return None,
+                };
+
+                match int.get(1..)?.parse() {
+                    Ok(bits @ (8 | 16 | 32 | 64 | 128)) => Self::Int(signed, bits),
+                    _ => return None,
+                }
+            }
+        };
+
+        Some(ty)
+    }
+
+    fn ty(&self) -> Ty {
+        match self {
+            Self::Bool => Ty::python(Python::Bool, vec![]),
+            Self::Int(signed, bits) => Ty::prelude(Prelude::RustInt(*signed, *bits), vec![]),
+            Self::Float => Ty::prelude(Prelude::RustFloat, vec![]),
+            Self::Str => Ty::python(Python::Str, vec![]),
+            Self::Pubkey => Ty::prelude(Prelude::Pubkey, vec![]),
+            Self::Vec(element) => Ty::python(Python::List, vec![element.ty()]),
+            Self::Option(inner) => Ty::python(Python::Optional, vec![inner.ty()]),
+            Self::Array(element, len) => {
+                Ty::prelude(Prelude::Array, vec![element.ty(), Ty::Const(*len)])
+            }
+            Self::Defined(path, defined_type) => {
+                Ty::Generic(TyName::Defined(path.clone(), defined_type.clone()), vec![])
+            }
+            // Never called, since using the instruction is an error
+            Self::Unsupported(..) => Ty::Any,
+        }
+    }
+
+    /// Get the Rust type that an arg of this type is serialized as.
+    fn ty_expr(&self) -> TyExpr {
+        let generic = |name: &str, params: Vec<TyExpr>| TyExpr::Generic {
+            mutability: Mutability::Immutable,
+            name: vec![name.to_string()],
+            params,
+            is_loadable: false,
+        };
+
+        match self {
+            Self::Bool => generic("bool", vec![]),
+            Self::Int(signed, bits) => generic(
+                &format!("{}{}", if *signed { "i" } else { "u" }, bits),
+                vec![],
+            ),
+            Self::Float => generic("f64", vec![]),
+            Self::Str => generic("String", vec![]),
+            Self::Pubkey => generic("Pubkey", vec![]),
+            Self::Vec(element) => generic("Vec", vec![element.ty_expr()]),
+            Self::Option(inner) => generic("Option", vec![inner.ty_expr()]),
+            Self::Array(element, len) => TyExpr::Array {
+                element: element.ty_expr().into(),
+                size: TyExpr::Const(*len as usize).into(),
+            },
+            // Structs are stored from their loaded (mutable) form, enums are used as they are
+            Self::Defined(path, defined_type) => {
+                let is_struct = defined_type == &DefinedType::Struct;
+                let mut name = vec!["crate".to_string()];
+                name.extend(path.iter().cloned());
+
+                TyExpr::Generic {
+                    mutability: if is_struct {
+                        Mutability::Mutable
+                    } else {
+                        Mutability::Immutable
+                    },
+                    name,
+                    params: vec![],
+                    is_loadable: is_struct,
+                }
+            }
+            Self::Unsupported(..) => panic!(), // we protect against this when the instruction is used
+        }
+    }
+
+    /// Get the Seahorse annotation of this type.
+    fn annotation(&self) -> String {
+        match self {
+            Self::Bool => "bool".to_string(),
+            Self::Int(signed, bits) => format!("{}{}", if *signed { "i" } else { "u" }, bits),
+            Self::Float => "f64".to_string(),
+            Self::Str => "str".to_string(),
+            Self::Pubkey => "Pubkey".to_string(),
+            Self::Vec(element) => format!("List[{}]", element.annotation()),
+            Self::Option(inner) => format!("Optional[{}]", inner.annotation()),
+            Self::Array(element, len) => format!("Array[{}, {}]", element.annotation(), len),
+            Self::Defined(path, _) => path.last().unwrap().clone(),
+            Self::Unsupported(..) => panic!(),
+        }
+    }
+}
+
+/// The defined types of an IDL that have a Seahorse equivalent, by name.
+struct DefinedTypes {
+    module: Vec<String>,
+    types: BTreeMap<String, DefinedType>,
+}
+
+impl DefinedTypes {
+    fn get(&self, name: &str) -> Option<IdlType> {
+        self.types.get(name).map(|defined_type| {
+            let mut path = self.module.clone();
+            path.push(name.to_string());
+
+            IdlType::Defined(path, defined_type.clone())
+        })
+    }
+}
+
+/// A struct or enum from the IDL. Its fields keep their IDL types until it's known which of the
+/// other defined types are supported.
+struct IdlTypeDef {
+    name: String,
+    kind: IdlTypeDefKind,
+}
+
+enum IdlTypeDefKind {
+    Struct(Vec<(String, Value)>),
+    Enum(Vec<(String, IdlVariantDef)>),
+}
+
+enum IdlVariantDef {
+    Unit,
+    Tuple(Vec<Value>),
+    Struct(Vec<(String, Value)>),
+}
+
+impl IdlTypeDef {
+    /// Parse a type definition, or get `None` if it's a kind of type that Seahorse can't declare.
+    fn parse(def: &Value) -> CResult<Option<Self>> {
+        let name = def
+            .get("name")
+            .and_then(|name| name.as_str())
+            .ok_or(Error::InvalidIdl("a type name".to_string()).core())?
+            .to_string();
+
+        let ty = def.get("type").unwrap_or(&Value::Null);
+        let is_generic = def
+            .get("generics")
+            .and_then(|generics| generics.as_array())
+            .is_some_and(|generics| !generics.is_empty());
+        let is_borsh = def
+            .get("serialization")
+            .and_then(|serialization| serialization.as_str())
+            .is_none_or(|serialization| serialization == "borsh");
+
+        if is_generic || !is_borsh {
+            return Ok(None);
+        }
+
+        let kind = match ty.get("kind").and_then(|kind| kind.as_str()) {
+            Some("struct") => match named_fields(ty.get("fields")) {
+                Some(fields) if !fields.is_empty() => IdlTypeDefKind::Struct(fields),
+                _ => return Ok(None),
+            },
+            Some("enum") => {
+                let variants = ty
+                    .get("variants")
+                    .and_then(|variants| variants.as_array())
+                    .ok_or(Error::InvalidIdl(format!("the variants of \"{}\"", name)).core())?;
+
+                let mut variants_ = vec![];
+                for variant in variants.iter() {
+                    let variant_name = variant
+                        .get("name")
+                        .and_then(|name| name.as_str())
+                        .ok_or(Error::InvalidIdl(format!("a variant name of \"{}\"", name)).core())?
+                        .to_string();
+
+                    // Variant fields are either all named or all unnamed
+                    let variant = match variant.get("fields") {
+                        None => IdlVariantDef::Unit,
+                        Some(fields) => match (named_fields(Some(fields)), fields.as_array()) {
+                            (Some(fields), _) if fields.is_empty() => IdlVariantDef::Unit,
+                            (Some(fields), _) => IdlVariantDef::Struct(fields),
+                            (None, Some(fields)) => IdlVariantDef::Tuple(fields.clone()),
+                            (None, None) => return Ok(None),
+                        },
+                    };
+
+                    variants_.push((variant_name, variant));
+                }
+
+                if variants_.is_empty() {
+                    return Ok(None);
+                }
+
+                IdlTypeDefKind::Enum(variants_)
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Self { name, kind }))
+    }
+
+    fn defined_type(&self) -> DefinedType {
+        match &self.kind {
+            IdlTypeDefKind::Struct(..) => DefinedType::Struct,
+            IdlTypeDefKind::Enum(..) => DefinedType::Enum,
+        }
+    }
+
+    /// Get the types of every field of this type.
+    fn field_tys(&self) -> Vec<&Value> {
+        match &self.kind {
+            IdlTypeDefKind::Struct(fields) => fields.iter().map(|(_, ty)| ty).collect(),
+            IdlTypeDefKind::Enum(variants) => variants
+                .iter()
+                .flat_map(|(_, variant)| match variant {
+                    IdlVariantDef::Unit => vec![],
+                    IdlVariantDef::Tuple(fields) => fields.iter().collect(),
+                    IdlVariantDef::Struct(fields) => fields.iter().map(|(_, ty)| ty).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Write this type as a Seahorse class.
+    fn source(&self, defined: &DefinedTypes) -> String {
+        let annotation = |ty: &Value| IdlType::parse(ty, defined).annotation();
+        let fields = |fields: &Vec<(String, Value)>, indent: &str| {
+            fields
+                .iter()
+                .map(|(name, ty)| format!("{}{}: {}\n", indent, name, annotation(ty)))
+                .collect::<String>()
+        };
+
+        match &self.kind {
+            IdlTypeDefKind::Struct(fields_) => {
+                format!(
+                    "@dataclass\nclass {}:\n{}",
+                    self.name,
+                    fields(fields_, "    ")
+                )
+            }
+            IdlTypeDefKind::Enum(variants) => {
+                let variants = variants
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (name, variant))| match variant {
+                        IdlVariantDef::Unit => format!("    {} = {}\n", name, i),
+                        IdlVariantDef::Tuple(fields) if fields.len() == 1 => {
+                            format!("    {}: {}\n", name, annotation(&fields[0]))
+                        }
+                        IdlVariantDef::Tuple(fields) => format!(
+                            "    {}: Tuple[{}]\n",
+                            name,
+                            fields.iter().map(annotation).collect::<Vec<_>>().join(", ")
+                        ),
+                        IdlVariantDef::Struct(fields_) => format!(
+                            "    @dataclass\n    class {}:\n{}",
+                            name,
+                            fields(fields_, "        ")
+                        ),
+                    })
+                    .collect::<String>();
+
+                format!("class {}(Enum):\n{}", self.name, variants)
+            }
+        }
+    }
+}
+
+/// Get a list of named fields, or `None` if the fields aren't named.
+fn named_fields(fields: Option<&Value>) -> Option<Vec<(String, Value)>> {
+    fields?
+        .as_array()?
+        .iter()
+        .map(|field| {
+            let name = field.get("name")?.as_str()?;
+            let ty = field.get("type")?;
+
+            Some((param_name(name), ty.clone()))
+        })
+        .collect()
+}
+
+/// Convert a name from the IDL to a name that can be used in both Python and Rust, by converting it
+/// to snake case and escaping keywords with a trailing underscore.
+fn param_name(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "and", "as", "assert", "async", "await", "break", "class", "const", "continue", "crate",
+        "def", "del", "dyn", "elif", "else", "enum", "except", "extern", "false", "finally", "fn",
+        "for", "from", "global", "if", "impl", "import", "in", "is", "lambda", "let", "loop",
+        "match", "mod", "move", "mut", "nonlocal", "not", "or", "pass", "pub", "raise", "ref",
+        "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "unsafe",
+        "use", "where", "while", "with", "yield",
+    ];
+
+    let name = name.to_snake_case();
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// The contents of an IDL module.
+pub struct Idl {
+    /// Seahorse source that declares the IDL's types.
+    pub types: String,
+    pub instructions: Vec<IdlInstruction>,
+}
+
+/// Parse the types and instructions out of an Anchor IDL, for a module at `module`. Both the legacy
+/// (<0.30) and the current IDL formats are supported.
+pub fn parse(json: &str, module: &[String]) -> CResult<Idl> {
+    let idl: Value =
+        serde_json::from_str(json).map_err(|_| Error::InvalidIdl("the JSON".to_string()).core())?;
+
+    let address = idl
+        .get("address")
+        .or(idl.pointer("/metadata/address"))
+        .and_then(|address| address.as_str())
+        .map(|address| {
+            address
+                .from_base58()
+                .ok()
+                .filter(|key| key.len() == 32)
+                .ok_or(Error::InvalidAddress(address.to_string()).core())
+        })
+        .transpose()?;
+
+    // Current IDLs declare account types in "types", legacy IDLs declare them in "accounts"
+    let mut type_defs = vec![];
+    for section in ["types", "accounts"] {
+        let defs = match idl.get(section) {
+            Some(defs) => defs
+                .as_array()
+                .ok_or(Error::InvalidIdl(format!("\"{}\"", section)).core())?,
+            None => continue,
+        };
+
+        for def in defs.iter().filter(|def| def.get("type").is_some()) {
+            if let Some(def) = IdlTypeDef::parse(def)? {
+                type_defs.push(def);
+            }
+        }
+    }
+
+    // A type is supported once all of its fields are, which can depend on other types
+    let mut defined = DefinedTypes {
+        module: module.to_vec(),
+        types: BTreeMap::new(),
+    };
+    loop {
+        let supported = type_defs
+            .iter()
+            .filter(|def| !defined.types.contains_key(&def.name))
+            .filter(|def| {
+                def.field_tys()
+                    .into_iter()
+                    .all(|ty| IdlType::parse_supported(ty, &defined).is_some())
+            })
+            .map(|def| (def.name.clone(), def.defined_type()))
+            .collect::<Vec<_>>();
+
+        if supported.is_empty() {
+            break;
+        }
+
+        defined.types.extend(supported);
+    }
+
+    let mut declared = BTreeSet::new();
+    let types = type_defs
+        .iter()
+        .filter(|def| defined.types.contains_key(&def.name) && declared.insert(&def.name))
+        .map(|def| def.source(&defined))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let instructions = idl
+        .get("instructions")
+        .and_then(|instructions| instructions.as_array())
+        .ok_or(Error::InvalidIdl("\"instructions\"".to_string()).core())?
+        .iter()
+        .map(|instruction| parse_instruction(instruction, &address, &defined))
+        .collect::<CResult<Vec<_>>>()?;
+
+    Ok(Idl {
+        types,
+        instructions,
+    })
+}
+
+fn parse_instruction(
+    instruction: &Value,
+    address: &Option<Vec<u8>>,
+    defined: &DefinedTypes,
+) -> CResult<IdlInstruction> {
+    let name = instruction
+        .get("name")
+        .and_then(|name| name.as_str())
+        .ok_or(Error::InvalidIdl("an instruction name".to_string()).core())?
+        .to_snake_case();
+
+    // Current IDLs list the discriminator, legacy IDLs leave it to be calculated from the name
+    let discriminator = match instruction.get("discriminator") {
+        Some(discriminator) => discriminator
+            .as_array()
+            .and_then(|bytes| {
+                bytes
+                    .iter()
+                    .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .ok_or(Error::InvalidIdl(format!("the discriminator of \"{}\"", name)).core())?,
+        None => Sha256::digest(format!("global:{}", name).as_bytes())[..8].to_vec(),
+    };
+
+    let mut accounts = vec![];
+    add_accounts(
+        &mut accounts,
+        instruction.get("accounts").unwrap_or(&Value::Null),
+        &name,
+    )?;
+
+    let args = instruction
+        .get("args")
+        .and_then(|args| args.as_array())
+        .ok_or(Error::InvalidIdl(format!("the args of \"{}\"", name)).core())?
+        .iter()
+        .map(|arg| {
+            let arg_name = arg
+                .get("name")
+                .and_then(|name| name.as_str())
+                .ok_or(Error::InvalidIdl(format!("an arg name of \"{}\"", name)).core())?;
+            let arg_name = param_name(arg_name);
+
+            let ty = IdlType::parse(arg.get("type").unwrap_or(&Value::Null), defined);
+
+            Ok((arg_name, ty))
+        })
+        .collect::<CResult<Vec<_>>>()?;
+
+    let mut params = vec!["program", "signer"];
+    for param in accounts
+        .iter()
+        .map(|account| &account.name)
+        .chain(args.iter().map(|(name, _)| name))
+    {
+        if params.contains(&param.as_str()) {
+            return Err(Error::DuplicateParam(name, param.clone()).core());
+        }
+
+        params.push(param);
+    }
+
+    Ok(IdlInstruction {
+        name,
+        address: address.clone(),
+        discriminator,
+        accounts,
+        args,
+    })
+}
+
+/// Add the accounts of an instruction, flattening any nested groups of accounts.
+fn add_accounts(accounts: &mut Vec<IdlAccount>, list: &Value, instruction: &String) -> CResult<()> {
+    let list = list
+        .as_array()
+        .ok_or(Error::InvalidIdl(format!("the accounts of \"{}\"", instruction)).core())?;
+
+    for account in list.iter() {
+        if let Some(nested) = account.get("accounts") {
+            add_accounts(accounts, nested, instruction)?;
+            continue;
+        }
+
+        let name = account
+            .get("name")
+            .and_then(|name| name.as_str())
+            .ok_or(Error::InvalidIdl(format!("an account name of \"{}\"", instruction)).core())?;
+        let name = param_name(name);
+
+        let flag = |legacy: &str, current: &str| {
+            account
+                .get(legacy)
+                .or(account.get(current))
+                .and_then(|flag| flag.as_bool())
+                .unwrap_or(false)
+        };
+
+        accounts.push(IdlAccount {
+            name,
+            is_mut: flag("isMut", "writable"),
+            is_signer: flag("isSigner", "signer"),
+        });
+    }
+
+    Ok(())
+}
+
+/// Create the namespace of an IDL module.
+pub fn namespace(instructions: &[IdlInstruction]) -> Namespace {
+    let mut namespace = BTreeMap::new();
+    for instruction in instructions.iter() {
+        namespace.insert(
+            instruction.name.clone(),
+            NamespacedObject::Item(Item::Builtin(Builtin::Idl(instruction.clone().into()))),
+        );
+    }
+
+    namespace
+}
+
+impl BuiltinSource for IdlInstruction {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    // (program: Program, ...accounts: Cast(Account), ...args, signer: List[Cast(Seed)]?) -> None
+    fn ty(&self) -> Ty {
+        let mut params = vec![(
+            "program".to_string(),
+            Ty::prelude(Prelude::Program, vec![]),
+            ParamType::Required,
+        )];
+        for IdlAccount { name, .. } in self.accounts.iter() {
+            params.push((
+                name.clone(),
+                Ty::Cast(Ty::prelude(Prelude::Account, vec![]).into()),
+                ParamType::Required,
+            ));
+        }
+        for (name, ty) in self.args.iter() {
+            params.push((name.clone(), ty.ty(), ParamType::Required));
+        }
+        params.push((
+            "signer".to_string(),
+            Ty::new_list(Ty::Cast(Ty::prelude(Prelude::Seed, vec![]).into())),
+            ParamType::Optional,
+        ));
+
+        let instruction = self.clone();
+
+        Ty::Function(
+            params,
+            Ty::Transformed(
+                Ty::python(Python::Tuple, vec![]).into(),
+                Transformation::new(move |mut expr| {
+                    // Unsupported args only matter once the instruction is used
+                    for (arg, ty) in instruction.args.iter() {
+                        if let IdlType::Unsupported(ty) = ty {
+                            return Err(Error::UnsupportedType(
+                                instruction.name.clone(),
+                                arg.clone(),
+                                ty.clone(),
+                            )
+                            .core());
+                        }
+                    }
+
+                    let mut args =
+                        match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+
+                    let program = args.next().unwrap();
+                    let accounts = args
+                        .by_ref()
+                        .take(instruction.accounts.len())
+                        .collect::<Vec<_>>();
+                    let values = args
+                        .by_ref()
+                        .take(instruction.args.len())
+                        .collect::<Vec<_>>();
+                    let signer = args.next().unwrap();
+
+                    let arg_tys = instruction
+                        .args
+                        .iter()
+                        .map(|(_, ty)| ty.ty_expr())
+                        .collect::<Vec<_>>();
+                    let arg_stored_tys = arg_tys
+                        .iter()
+                        .map(|ty| {
+                            let ty = StoredTyExpr(ty);
+
+                            quote! { #ty }
+                        })
+                        .collect::<Vec<_>>();
+                    let values = values
+                        .into_iter()
+                        .zip(arg_tys.iter())
+                        .map(|(value, ty)| stored_field(quote! { #value }, ty))
+                        .collect::<Vec<_>>();
+                    let arg_indices = (0..values.len())
+                        .map(PM2Literal::usize_unsuffixed)
+                        .collect::<Vec<_>>();
+                    let discriminator = instruction.discriminator.clone();
+                    let metas = instruction
+                        .accounts
+                        .iter()
+                        .enumerate()
+                        .map(|(i, account)| {
+                            let index = PM2Literal::usize_unsuffixed(i + 1);
+                            let is_signer = account.is_signer;
+                            let is_writable = account.is_mut;
+
+                            quote! {
+                                AccountMeta {
+                                    pubkey: account_infos[#index].key(),
+                                    is_signer: #is_signer,
+                                    is_writable: #is_writable
+                                }
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    // A program with the wrong address fails the instruction with Anchor's error
+                    let check_address = instruction.address.as_ref().map(|address| {
+                        quote! {
+                            require_keys_eq!(
+                                account_infos[0].key(),
+                                Pubkey::new_from_array([#(#address),*]),
+                                anchor_lang::error::ErrorCode::InvalidProgramId
+                            );
+                        }
+                    });
+
+                    let account_infos = accounts
+                        .iter()
+                        .map(|account| quote! { #account.to_account_info() })
+                        .collect::<Vec<_>>();
+                    let render = move |signer: TokenStream| {
+                        quote! {
+                            {
+                                let account_infos = [
+                                    #program.to_account_info(),
+                                    #(#account_infos),*
+                                ];
+                                let args: (#(#arg_stored_tys,)*) = (#(#values,)*);
+
+                                #check_address
+
+                                let mut data = vec![#(#discriminator),*];
+                                #(args.#arg_indices.serialize(&mut data).unwrap();)*
+
+                                let instruction = solana_program::instruction::Instruction {
+                                    program_id: account_infos[0].key(),
+                                    accounts: vec![#(#metas),*],
+                                    data
+                                };
+
+                                solana_program::program::invoke_signed(
+                                    &instruction,
+                                    &account_infos,
+                                    #signer
+                                ).unwrap();
+                            }
+                        }
+                    };
+
+                    // Without signer seeds, the signer accounts that turn out to be PDAs sign with
+                    // their own seeds
+                    let (signer, authorities, signed) = match signer.obj {
+                        ExpressionObj::Placeholder => {
+                            let authorities = accounts
+                                .iter()
+                                .zip(instruction.accounts.iter())
+                                .filter(|(_, account)| account.is_signer)
+                                .map(|(authority, _)| cpi_authority(authority))
+                                .collect::<Vec<_>>();
+
+                            let ty = expr.ty.clone();
+                            let render = render.clone();
+                            let signed = CpiSigner::new(move |pdas| {
+                                let seeds = pdas.iter().map(|name| signer_seeds(name));
+
+                                TypedExpression {
+                                    ty: ty.clone(),
+                                    obj: ExpressionObj::Rendered(render(quote! {
+                                        &[#(#seeds.borrow().as_slice()),*]
+                                    })),
+                                }
+                            });
+
+                            (quote! { &[] }, authorities, Some(signed))
+                        }
+                        seeds => (quote! { &[#seeds.borrow().as_slice()] }, vec![], None),
+                    };
+
+                    expr.obj = ExpressionObj::Rendered(render(signer));
+
+                    Ok(Transformed::Cpi {
+                        expr,
+                        program: None,
+                        authorities,
+                        signed,
+                    })
+                }),
+            )
+            .into(),
+        )
+    }
+
+    fn as_instance(&self, _params: &Vec<Ty>) -> CResult<()> {
+        Err(CoreError::make_raw("invalid type", ""))
+    }
+
+    fn attr(&self, _attr: &String) -> Option<(Ty, Ty)> {
+        None
+    }
+
+    fn index(&self) -> Option<(Ty, Ty)> {
+        None
+    }
+
+    fn static_attr(&self, _attr: &String) -> Option<Ty> {
+        None
+    }
+
+    fn casted(&self, _ty: &Ty) -> Option<(Ty, Ty)> {
+        None
+    }
+}
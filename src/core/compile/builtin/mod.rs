@@ -1,8 +1,10 @@
 use crate::core::{compile::check::*, util::*};
 
+pub mod idl;
 pub mod prelude;
 pub mod pyth;
 pub mod python;
+pub use idl::IdlInstruction;
 pub use prelude::Prelude;
 pub use pyth::Pyth;
 pub use python::Python;
@@ -42,6 +44,7 @@ pub enum Builtin {
     Python(Python),
     Prelude(Prelude),
     Pyth(Pyth),
+    Idl(Box<IdlInstruction>),
 }
 
 impl From<python::Python> for Builtin {
@@ -56,6 +59,7 @@ macro_rules! match_builtin {
             Self::Python($builtin) => $func,
             Self::Prelude($builtin) => $func,
             Self::Pyth($builtin) => $func,
+            Self::Idl($builtin) => $func,
         }
     };
 }
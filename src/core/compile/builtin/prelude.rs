@@ -1,12 +1,16 @@
 //! The Seahorse Prelude includes a bunch of builtin types that convert to Rust/Anchor.
 
-use crate::core::{compile::builtin::*, generate::LoadedTyExpr};
+use crate::core::{
+    compile::builtin::*,
+    generate::{signer_seeds_ident, LoadedTyExpr},
+};
 pub use crate::core::{
     compile::{ast::*, build::*, check::*, namespace::*, sign::*},
     util::*,
 };
 use crate::match1;
-use quote::quote;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
 use std::collections::BTreeMap;
 pub use std::collections::HashMap;
 
@@ -17,11 +21,14 @@ pub enum Prelude {
     Enum,
     Account,
     Event,
+    ErrorCode,
     Signer,
     Empty,
     Program,
     TokenMint,
     TokenAccount,
+    Token2022Mint,
+    Token2022Account,
     UncheckedAccount,
     Clock,
     CpiAccount,
@@ -31,30 +38,286 @@ pub enum Prelude {
     // Meta types
     Seed,
     InitAccount, // Type used to get account data for `Empty[T].init` - regular `Account` cast doesn't work because of CPIs
+    AccountConstraint,
     // Functions
     Floor,
     Ceil,
     ArrayConstructor,
     IntBytes,
     Size,
+    // Account constraints
+    HasOne,
+    Constraint,
+    Address,
+    Realloc,
+    Seeds,
     // Directives
     DeclareId,
     // Decorators
     Instruction,
 }
 
+/// Get the custom error of an account constraint, if one was given.
+fn constraint_error(error: TypedExpression) -> CResult<Option<TypedExpression>> {
+    match error.optional() {
+        Some(error) if !error.ty.is_error_code() => Err(CoreError::make_raw(
+            format!("account constraints can only fail with an error code (found {})", error.ty),
+            "Hint: declare your errors in a class that inherits from ErrorCode, then pass one of its variants (like MyError.Unauthorized).",
+        )),
+        error => Ok(error),
+    }
+}
+
+/// Get the variable that an authority was passed as, if it was passed as one.
+fn authority_name(authority: &TypedExpression) -> Option<String> {
+    match &authority.obj {
+        ExpressionObj::Id(name) => Some(name.clone()),
+        ExpressionObj::Move(value) => authority_name(value),
+        _ => None,
+    }
+}
+
+/// Resolve the authority of a CPI from its typed expression. Program accounts are cast to their
+/// Anchor account, so they're found inside of the cast.
+pub fn cpi_authority(authority: &TypedExpression) -> CpiAuthority {
+    match &authority.obj {
+        ExpressionObj::Move(value) => cpi_authority(value),
+        ExpressionObj::AnchorAccount(account) => {
+            CpiAuthority::ProgramAccount(authority_name(account))
+        }
+        _ => match authority_name(authority) {
+            Some(name) => CpiAuthority::Named(name),
+            None => CpiAuthority::Other,
+        },
+    }
+}
+
+/// Render the seeds that a PDA account signs with, as a list of seeds.
+pub fn signer_seeds(name: &str) -> TokenStream {
+    let seeds = signer_seeds_ident(name);
+
+    quote! {
+        Mutable::new(#seeds.iter().map(|seed| seed.as_slice()).collect::<Vec<_>>())
+    }
+}
+
+/// Get the type of `account.signer_seeds()`, the seeds that a PDA account of an instruction signs
+/// CPIs with (including its bump).
+pub fn signer_seeds_method() -> Ty {
+    Ty::new_function(
+        vec![],
+        Ty::Transformed(
+            Ty::new_list(Ty::prelude(Prelude::Seed, vec![])).into(),
+            Transformation::new(|mut expr| {
+                let function = match1!(expr.obj, ExpressionObj::Call { function, .. } => *function);
+                let account =
+                    match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                let name = match account.obj {
+                    ExpressionObj::Id(name) => name,
+                    _ => {
+                        return Err(CoreError::make_raw(
+                            "invalid call to signer_seeds",
+                            "Hint: only the accounts of an instruction can have signer seeds.",
+                        ))
+                    }
+                };
+
+                expr.obj = ExpressionObj::Rendered(signer_seeds(&name));
+
+                Ok(Transformed::SignerSeeds { expr, name })
+            }),
+        ),
+    )
+}
+
+/// Make a CPI through one of Anchor's CPI functions. If no signer seeds were given, the CPI is also
+/// made signed by its authority, which gets used instead if the authority turns out to be a PDA with
+/// known seeds.
+fn cpi(
+    ty: Ty,
+    program: AccountTyExpr,
+    authority: &TypedExpression,
+    signer: TypedExpression,
+    program_and_accounts: TokenStream,
+    render: impl Fn(TokenStream) -> TokenStream,
+) -> Transformed {
+    let with_signer = |seeds: TokenStream| {
+        quote! {
+            CpiContext::new_with_signer(
+                #program_and_accounts,
+                &[#seeds.borrow().as_slice()]
+            )
+        }
+    };
+
+    let (cpi_context, authorities, signed) = match signer.obj {
+        ExpressionObj::Placeholder => {
+            let authority = cpi_authority(authority);
+            // There's only one authority, so this is what gets signed if it's a PDA
+            let signed = match &authority {
+                CpiAuthority::Named(name) | CpiAuthority::ProgramAccount(Some(name)) => {
+                    let signed = TypedExpression {
+                        ty: ty.clone(),
+                        obj: ExpressionObj::Rendered(render(with_signer(signer_seeds(name)))),
+                    };
+
+                    Some(CpiSigner::new(move |_| signed.clone()))
+                }
+                _ => None,
+            };
+
+            (
+                quote! { CpiContext::new(#program_and_accounts) },
+                vec![authority],
+                signed,
+            )
+        }
+        seeds => (with_signer(quote! { #seeds }), vec![], None),
+    };
+
+    let expr = TypedExpression {
+        ty,
+        obj: ExpressionObj::Rendered(render(cpi_context)),
+    };
+
+    Transformed::Cpi {
+        expr,
+        program: Some(program),
+        authorities,
+        signed,
+    }
+}
+
+/// Get the token program that a token mint or account belongs to, along with the program's name in
+/// the programs map and the path of the module with its CPIs.
+fn token_program(account: &TypedExpression) -> (AccountTyExpr, &'static str, TokenStream) {
+    match &account.ty {
+        Ty::Generic(
+            TyName::Builtin(Builtin::Prelude(Prelude::Token2022Mint | Prelude::Token2022Account)),
+            _,
+        ) => (
+            AccountTyExpr::Token2022Program,
+            "token_2022_program",
+            quote! { anchor_spl::token_interface },
+        ),
+        _ => (
+            AccountTyExpr::TokenProgram,
+            "token_program",
+            quote! { token },
+        ),
+    }
+}
+
+/// Render the `AuthorityType` of a `set_authority` call, given as one of the allowed names (the first
+/// is the default).
+fn authority_type(
+    program: &AccountTyExpr,
+    authority_type: TypedExpression,
+    allowed: &[&str],
+) -> CResult<TokenStream> {
+    let name = match authority_type.obj {
+        ExpressionObj::Placeholder => allowed[0].to_string(),
+        ExpressionObj::Literal(Literal::Str(name)) if allowed.contains(&name.as_str()) => name,
+        _ => {
+            return Err(CoreError::make_raw(
+                "invalid authority type",
+                format!(
+                    "Hint: the authority type must be one of these strings: {}.",
+                    allowed.join(", ")
+                ),
+            ))
+        }
+    };
+
+    let variant = match name.as_str() {
+        "mint_tokens" => quote! { MintTokens },
+        "freeze_account" => quote! { FreezeAccount },
+        "account_owner" => quote! { AccountOwner },
+        _ => quote! { CloseAccount },
+    };
+
+    Ok(match program {
+        AccountTyExpr::Token2022Program => quote! {
+            anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType::#variant
+        },
+        _ => quote! { token::spl_token::instruction::AuthorityType::#variant },
+    })
+}
+
+/// Render a read from an extension of a Token-2022 mint, given the extension's module and type.
+/// If the mint doesn't have the extension, the read gives `absent`, or fails the instruction when
+/// there's no `absent` value.
+fn mint_extension(
+    mint: &TypedExpression,
+    module: TokenStream,
+    extension: TokenStream,
+    read: TokenStream,
+    absent: Option<TokenStream>,
+) -> TokenStream {
+    let read = match absent {
+        Some(absent) => quote! {
+            match state.get_extension::<#extension>() {
+                Ok(extension) => #read,
+                Err(_) => #absent,
+            }
+        },
+        None => quote! {
+            let extension = state.get_extension::<#extension>()?;
+
+            #read
+        },
+    };
+
+    quote! {
+        {
+            use anchor_spl::token_2022::spl_token_2022::{
+                extension::{#module::#extension, BaseStateWithExtensions, StateWithExtensions},
+                state::Mint
+            };
+
+            let info = #mint.to_account_info();
+            let data = info.try_borrow_data()?;
+            let state = StateWithExtensions::<Mint>::unpack(&data)?;
+
+            #read
+        }
+    }
+}
+
+impl Prelude {
+    /// Get the token mint type that goes with a token mint or account type.
+    fn token_mint(&self) -> Self {
+        match self {
+            Self::Token2022Mint | Self::Token2022Account => Self::Token2022Mint,
+            _ => Self::TokenMint,
+        }
+    }
+
+    /// Get the token account type that goes with a token mint or account type.
+    fn token_account(&self) -> Self {
+        match self {
+            Self::Token2022Mint | Self::Token2022Account => Self::Token2022Account,
+            _ => Self::TokenAccount,
+        }
+    }
+}
+
 /// Create the seahorse.prelude namespace.
 pub fn namespace() -> Namespace {
     let data = [
         ("Array", Prelude::Array),
         ("Enum", Prelude::Enum),
         ("Event", Prelude::Event),
+        ("ErrorCode", Prelude::ErrorCode),
         ("Account", Prelude::Account),
         ("Signer", Prelude::Signer),
         ("Empty", Prelude::Empty),
         ("Program", Prelude::Program),
         ("TokenMint", Prelude::TokenMint),
         ("TokenAccount", Prelude::TokenAccount),
+        ("Token2022Mint", Prelude::Token2022Mint),
+        ("Token2022Account", Prelude::Token2022Account),
         ("UncheckedAccount", Prelude::UncheckedAccount),
         ("Clock", Prelude::Clock),
         ("CpiAccount", Prelude::CpiAccount),
@@ -75,6 +338,11 @@ pub fn namespace() -> Namespace {
         ("int_bytes", Prelude::IntBytes),
         ("size", Prelude::Size),
         ("array", Prelude::ArrayConstructor),
+        ("has_one", Prelude::HasOne),
+        ("constraint", Prelude::Constraint),
+        ("address", Prelude::Address),
+        ("realloc", Prelude::Realloc),
+        ("seeds", Prelude::Seeds),
         ("declare_id", Prelude::DeclareId),
         ("instruction", Prelude::Instruction),
     ];
@@ -87,6 +355,12 @@ pub fn namespace() -> Namespace {
         );
     }
 
+    // `Str` is just `str`, but can be given a max length (`Str[32]`) without upsetting Python
+    namespace.insert(
+        "Str".to_string(),
+        NamespacedObject::Automatic(Builtin::Python(Python::Str)),
+    );
+
     return namespace;
 }
 
@@ -97,11 +371,14 @@ impl BuiltinSource for Prelude {
             Self::Enum => "Enum",
             Self::Account => "Account",
             Self::Event => "Event",
+            Self::ErrorCode => "ErrorCode",
             Self::Signer => "Signer",
             Self::Empty => "Empty",
             Self::Program => "Program",
             Self::TokenMint => "TokenMint",
             Self::TokenAccount => "TokenAccount",
+            Self::Token2022Mint => "Token2022Mint",
+            Self::Token2022Account => "Token2022Account",
             Self::UncheckedAccount => "UncheckedAccount",
             Self::Clock => "Clock",
             Self::CpiAccount => "CpiAccount",
@@ -112,11 +389,17 @@ impl BuiltinSource for Prelude {
             Self::RustFloat => "f64",
             Self::Seed => "<Seed>",
             Self::InitAccount => "<InitAccount>",
+            Self::AccountConstraint => "<AccountConstraint>",
             Self::Floor => "floor",
             Self::Ceil => "ceil",
             Self::IntBytes => "int_bytes",
             Self::Size => "size",
             Self::ArrayConstructor => "array",
+            Self::HasOne => "has_one",
+            Self::Constraint => "constraint",
+            Self::Address => "address",
+            Self::Realloc => "realloc",
+            Self::Seeds => "seeds",
             Self::DeclareId => "declare_id",
             Self::Instruction => "instruction",
         }
@@ -290,10 +573,10 @@ impl BuiltinSource for Prelude {
                     })
                 )
             ),
-            // size(str) -> u64
+            // size(str | type[Account]) -> u64
             Self::Size => Ty::new_function(
                 vec![
-                    ("ob", Ty::python(Python::Str, vec![]), ParamType::Required)
+                    ("ob", Ty::Any, ParamType::Required)
                 ],
                 Ty::Transformed(
                     Ty::prelude(Self::RustInt(false, 64), vec![]).into(),
@@ -301,8 +584,24 @@ impl BuiltinSource for Prelude {
                         let args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args);
                         let ob = args.into_iter().next().unwrap();
 
+                        let value = match &ob.ty {
+                            Ty::Generic(TyName::Builtin(Builtin::Python(Python::Str)), _) => quote! { #ob.len() },
+                            // The size of an account type is known at compile time
+                            Ty::Type(TyName::Defined(path, DefinedType::Account), _) => {
+                                let name = format_ident!("{}", path.last().unwrap());
+
+                                quote! { #name::__SPACE__ }
+                            }
+                            _ => {
+                                return Err(CoreError::make_raw(
+                                    "invalid call to size",
+                                    "Hint: size can only get the size of a string, or the space that an account type needs."
+                                ));
+                            }
+                        };
+
                         expr.obj = ExpressionObj::As {
-                            value: ExpressionObj::Rendered(quote! { #ob.len() }).into(),
+                            value: ExpressionObj::Rendered(value).into(),
                             ty: TyExpr::new_specific(vec!["u64"], Mutability::Immutable)
                         };
 
@@ -311,6 +610,121 @@ impl BuiltinSource for Prelude {
                 )
             ),
             Self::ArrayConstructor => Ty::ArrayConstructor2,
+            // has_one(str, error?: ErrorCode) -> <AccountConstraint>
+            Self::HasOne => Ty::new_function(
+                vec![
+                    ("field", Ty::python(Python::Str, vec![]), ParamType::Required),
+                    ("error", Ty::Any, ParamType::Optional),
+                ],
+                Ty::Transformed(
+                    Ty::prelude(Self::AccountConstraint, vec![]).into(),
+                    Transformation::new_with_context(
+                        |expr, _| {
+                            let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                            let field = match args.next().unwrap().obj {
+                                ExpressionObj::Literal(Literal::Str(field)) => field,
+                                _ => return Err(CoreError::make_raw(
+                                    "the argument to has_one must be a string literal",
+                                    "Hint: has_one takes the name of a Pubkey field, which must also be the name of another account in this instruction."
+                                ))
+                            };
+                            let error = constraint_error(args.next().unwrap())?;
+
+                            Ok(Transformed::Constraint(AccountConstraint::HasOne { field, error }))
+                        },
+                        // Constraints are rendered in the accounts struct, like seeds
+                        Some(ExprContext::Seed),
+                    ),
+                )
+            ),
+            // constraint(bool, error?: ErrorCode) -> <AccountConstraint>
+            Self::Constraint => Ty::new_function(
+                vec![
+                    ("cond", Ty::python(Python::Bool, vec![]), ParamType::Required),
+                    ("error", Ty::Any, ParamType::Optional),
+                ],
+                Ty::Transformed(
+                    Ty::prelude(Self::AccountConstraint, vec![]).into(),
+                    Transformation::new_with_context(
+                        |expr, _| {
+                            let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                            let cond = args.next().unwrap();
+                            let error = constraint_error(args.next().unwrap())?;
+
+                            Ok(Transformed::Constraint(AccountConstraint::Constraint { cond, error }))
+                        },
+                        Some(ExprContext::Seed),
+                    ),
+                )
+            ),
+            // address(Pubkey, error?: ErrorCode) -> <AccountConstraint>
+            Self::Address => Ty::new_function(
+                vec![
+                    ("address", Ty::prelude(Self::Pubkey, vec![]), ParamType::Required),
+                    ("error", Ty::Any, ParamType::Optional),
+                ],
+                Ty::Transformed(
+                    Ty::prelude(Self::AccountConstraint, vec![]).into(),
+                    Transformation::new_with_context(
+                        |expr, _| {
+                            let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                            let address = args.next().unwrap();
+                            let error = constraint_error(args.next().unwrap())?;
+
+                            Ok(Transformed::Constraint(AccountConstraint::Address { address, error }))
+                        },
+                        Some(ExprContext::Seed),
+                    ),
+                )
+            ),
+            // realloc(u64, Cast(<InitAccount>), zero?: bool) -> <AccountConstraint>
+            Self::Realloc => Ty::new_function(
+                vec![
+                    ("space", Ty::prelude(Self::RustInt(false, 64), vec![]), ParamType::Required),
+                    ("payer", Ty::Cast(Ty::prelude(Self::InitAccount, vec![]).into()), ParamType::Required),
+                    ("zero", Ty::python(Python::Bool, vec![]), ParamType::Optional),
+                ],
+                Ty::Transformed(
+                    Ty::prelude(Self::AccountConstraint, vec![]).into(),
+                    Transformation::new_with_context(
+                        |expr, _| {
+                            let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                            let space = args.next().unwrap();
+                            let payer = args.next().unwrap();
+                            let zero = args.next().unwrap().optional();
+
+                            Ok(Transformed::Constraint(AccountConstraint::Realloc { space, payer, zero }))
+                        },
+                        Some(ExprContext::Seed),
+                    ),
+                )
+            ),
+            // seeds(List[Cast(Seed)], bump?: u8) -> <AccountConstraint>
+            Self::Seeds => Ty::new_function(
+                vec![
+                    ("seeds", Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())), ParamType::Required),
+                    ("bump", Ty::prelude(Self::RustInt(false, 8), vec![]), ParamType::Optional),
+                ],
+                Ty::Transformed(
+                    Ty::prelude(Self::AccountConstraint, vec![]).into(),
+                    Transformation::new_with_context(
+                        |expr, _| {
+                            let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                            let seeds = match args.next().unwrap().obj {
+                                ExpressionObj::Vec(seeds) => seeds,
+                                _ => return Err(CoreError::make_raw(
+                                    "the seeds of a PDA must be a list literal",
+                                    "Hint: Anchor needs to know each seed ahead of time, so list them out like this: seeds(['vault', owner], bump = vault.bump)"
+                                ))
+                            };
+                            let bump = args.next().unwrap().optional();
+
+                            Ok(Transformed::Constraint(AccountConstraint::Seeds { seeds, bump }))
+                        },
+                        Some(ExprContext::Seed),
+                    ),
+                )
+            ),
             // declare_id
             Self::DeclareId => Ty::new_function(
                 vec![
@@ -395,112 +809,379 @@ impl BuiltinSource for Prelude {
 
                             Ok(Transformed::Cpi {
                                 expr,
-                                program: AccountTyExpr::SystemProgram,
+                                program: Some(AccountTyExpr::SystemProgram),
+                                authorities: vec![],
+                                signed: None,
                             })
                         }),
                     ),
                 ),
             )),
-            // Empty[T].init(...) -> T
-            (Self::Empty, "init") => Some((
-                Ty::prelude(Self::Empty, vec![Ty::Anonymous(0)]),
+            // Signer.create_account(account = Cast(Account), lamports = u64, space = u64, owner = Pubkey, signer = List[Cast(Seed)]?) -> None
+            (Self::Signer, "create_account") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![
                         (
-                            "payer",
-                            Ty::Cast(Ty::prelude(Self::InitAccount, vec![]).into()),
+                            "account",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
                             ParamType::Required,
                         ),
                         (
-                            "seeds",
-                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
-                            ParamType::Optional,
+                            "lamports",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Required,
                         ),
                         (
-                            "mint",
-                            Ty::prelude(Self::TokenMint, vec![]),
-                            ParamType::Optional,
+                            "space",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Required,
                         ),
                         (
-                            "authority",
-                            Ty::Cast(Ty::prelude(Self::InitAccount, vec![]).into()),
-                            ParamType::Optional,
+                            "owner",
+                            Ty::prelude(Self::Pubkey, vec![]),
+                            ParamType::Required,
                         ),
                         (
-                            "decimals",
-                            Ty::prelude(Self::RustInt(false, 8), vec![]),
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
                             ParamType::Optional,
                         ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let payer = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let account = args.next().unwrap();
+                            let lamports = args.next().unwrap();
+                            let space = args.next().unwrap();
+                            let owner = args.next().unwrap();
+                            let signer = args.next().unwrap();
+
+                            let program_and_accounts = quote! {
+                                #payer.programs.get("system_program"),
+                                anchor_lang::system_program::CreateAccount {
+                                    from: #payer.to_account_info(),
+                                    to: #account.to_account_info()
+                                }
+                            };
+
+                            Ok(cpi(
+                                expr.ty,
+                                AccountTyExpr::SystemProgram,
+                                &account,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        anchor_lang::system_program::create_account(
+                                            #cpi_context,
+                                            #lamports,
+                                            #space,
+                                            &#owner
+                                        ).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // Signer.create_account_with_seed(account = Cast(Account), base = Cast(Account), seed = str, lamports = u64, space = u64, owner = Pubkey, signer = List[Cast(Seed)]?) -> None
+            (Self::Signer, "create_account_with_seed") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "account",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
                         (
-                            "associated",
-                            Ty::python(Python::Bool, vec![]),
-                            ParamType::Optional,
+                            "base",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
                         ),
+                        ("seed", Ty::python(Python::Str, vec![]), ParamType::Required),
                         (
-                            "space",
+                            "lamports",
                             Ty::prelude(Self::RustInt(false, 64), vec![]),
-                            ParamType::Optional,
+                            ParamType::Required,
                         ),
                         (
-                            "padding",
+                            "space",
                             Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "owner",
+                            Ty::prelude(Self::Pubkey, vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
                             ParamType::Optional,
                         ),
                     ],
                     Ty::Transformed(
-                        Ty::Anonymous(0).into(),
-                        Transformation::new_with_context(
-                            |mut expr, _| {
-                                let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args } => (function, args));
-                                let empty = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
-                                let name =
-                                    match1!(&empty.obj, ExpressionObj::Id(var) => var.clone());
-                                let mut args = args.into_iter();
-
-                                let mut annotation = AccountAnnotation::new();
-                                annotation.is_mut = false;
-                                annotation.init = true;
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let payer = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let account = args.next().unwrap();
+                            let base = args.next().unwrap();
+                            let seed = args.next().unwrap();
+                            let lamports = args.next().unwrap();
+                            let space = args.next().unwrap();
+                            let owner = args.next().unwrap();
+                            let signer = args.next().unwrap();
 
-                                let payer = args.next().unwrap();
-                                let seeds = args.next().unwrap().optional();
+                            let program_and_accounts = quote! {
+                                #payer.programs.get("system_program"),
+                                anchor_lang::system_program::CreateAccountWithSeed {
+                                    from: #payer.to_account_info(),
+                                    to: #account.to_account_info(),
+                                    base: #base.to_account_info()
+                                }
+                            };
 
-                                let mint = args.next().unwrap().optional();
-                                let authority = args.next().unwrap().optional();
-                                let decimals = args.next().unwrap().optional();
-                                let associated = match args.next().unwrap().obj {
-                                    ExpressionObj::Literal(Literal::Bool(associated)) => {
-                                        Some(associated)
-                                    }
-                                    ExpressionObj::Placeholder => None,
-                                    _ => {
-                                        return Err(CoreError::make_raw(
-                                        "invalid argument to Empty.init()",
-                                        "Hint: if you provide a value for \"associated\", it must be a boolean literal."
-                                    ));
+                            Ok(cpi(
+                                expr.ty,
+                                AccountTyExpr::SystemProgram,
+                                &base,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        anchor_lang::system_program::create_account_with_seed(
+                                            #cpi_context,
+                                            &#seed,
+                                            #lamports,
+                                            #space,
+                                            &#owner
+                                        ).unwrap();
                                     }
-                                };
-                                let space = args.next().unwrap().optional();
-                                let padding = args.next().unwrap().optional();
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // Signer.allocate(account = Cast(Account), space = u64, signer = List[Cast(Seed)]?) -> None
+            (Self::Signer, "allocate") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "account",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "space",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let payer = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let account = args.next().unwrap();
+                            let space = args.next().unwrap();
+                            let signer = args.next().unwrap();
 
-                                annotation.payer = Some(payer);
-                                annotation.seeds = seeds.map(|seeds| {
-                                    let seeds =
-                                        match1!(seeds.obj, ExpressionObj::Vec(list) => list);
-                                    seeds
-                                });
+                            let program_and_accounts = quote! {
+                                #payer.programs.get("system_program"),
+                                anchor_lang::system_program::Allocate {
+                                    account_to_allocate: #account.to_account_info()
+                                }
+                            };
 
-                                match &expr.ty {
-                                    Ty::Generic(name, _) => match name {
-                                        TyName::Defined(_, DefinedType::Account) => {
-                                            if mint.is_some()
-                                                || authority.is_some()
-                                                || decimals.is_some()
-                                                || associated.is_some()
-                                            {
-                                                return Err(CoreError::make_raw(
-                                                "invalid argument to Empty.init() for a program account",
-                                                "Hint: you can only pass in a payer and optionally a list of seeds."
-                                            ));
+                            Ok(cpi(
+                                expr.ty,
+                                AccountTyExpr::SystemProgram,
+                                &account,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        anchor_lang::system_program::allocate(
+                                            #cpi_context,
+                                            #space
+                                        ).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // Signer.assign(account = Cast(Account), owner = Pubkey, signer = List[Cast(Seed)]?) -> None
+            (Self::Signer, "assign") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "account",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "owner",
+                            Ty::prelude(Self::Pubkey, vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let payer = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let account = args.next().unwrap();
+                            let owner = args.next().unwrap();
+                            let signer = args.next().unwrap();
+
+                            let program_and_accounts = quote! {
+                                #payer.programs.get("system_program"),
+                                anchor_lang::system_program::Assign {
+                                    account_to_assign: #account.to_account_info()
+                                }
+                            };
+
+                            Ok(cpi(
+                                expr.ty,
+                                AccountTyExpr::SystemProgram,
+                                &account,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        anchor_lang::system_program::assign(
+                                            #cpi_context,
+                                            &#owner
+                                        ).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // Empty[T].init(...) -> T
+            (Self::Empty, "init") => Some((
+                Ty::prelude(Self::Empty, vec![Ty::Anonymous(0)]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "payer",
+                            Ty::Cast(Ty::prelude(Self::InitAccount, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "seeds",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                        // Either kind of mint, checked against the type of the token account
+                        ("mint", Ty::Any, ParamType::Optional),
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::InitAccount, vec![]).into()),
+                            ParamType::Optional,
+                        ),
+                        (
+                            "decimals",
+                            Ty::prelude(Self::RustInt(false, 8), vec![]),
+                            ParamType::Optional,
+                        ),
+                        (
+                            "associated",
+                            Ty::python(Python::Bool, vec![]),
+                            ParamType::Optional,
+                        ),
+                        (
+                            "space",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Optional,
+                        ),
+                        (
+                            "padding",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::Anonymous(0).into(),
+                        Transformation::new_with_context(
+                            |mut expr, _| {
+                                let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args } => (function, args));
+                                let empty = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                                let name =
+                                    match1!(&empty.obj, ExpressionObj::Id(var) => var.clone());
+                                let mut args = args.into_iter();
+
+                                let mut annotation = AccountAnnotation::new();
+                                annotation.is_mut = false;
+                                annotation.init = true;
+
+                                let payer = args.next().unwrap();
+                                let seeds = args.next().unwrap().optional();
+
+                                let mint = args.next().unwrap().optional();
+                                let authority = args.next().unwrap().optional();
+                                let decimals = args.next().unwrap().optional();
+                                let associated = match args.next().unwrap().obj {
+                                    ExpressionObj::Literal(Literal::Bool(associated)) => {
+                                        Some(associated)
+                                    }
+                                    ExpressionObj::Placeholder => None,
+                                    _ => {
+                                        return Err(CoreError::make_raw(
+                                        "invalid argument to Empty.init()",
+                                        "Hint: if you provide a value for \"associated\", it must be a boolean literal."
+                                    ));
+                                    }
+                                };
+                                let space = args.next().unwrap().optional();
+                                let padding = args.next().unwrap().optional();
+
+                                annotation.payer = Some(payer);
+                                annotation.seeds = seeds.map(|seeds| {
+                                    let seeds =
+                                        match1!(seeds.obj, ExpressionObj::Vec(list) => list);
+                                    seeds
+                                });
+
+                                match &expr.ty {
+                                    Ty::Generic(name, _) => match name {
+                                        TyName::Defined(_, DefinedType::Account) => {
+                                            if mint.is_some()
+                                                || authority.is_some()
+                                                || decimals.is_some()
+                                                || associated.is_some()
+                                            {
+                                                return Err(CoreError::make_raw(
+                                                "invalid argument to Empty.init() for a program account",
+                                                "Hint: you can only pass in a payer and optionally a list of seeds."
+                                            ));
                                             }
 
                                             if space.is_some() && padding.is_some() {
@@ -514,7 +1195,9 @@ impl BuiltinSource for Prelude {
                                             annotation.padding = padding;
                                         }
 
-                                        TyName::Builtin(Builtin::Prelude(Self::TokenMint)) => {
+                                        TyName::Builtin(Builtin::Prelude(
+                                            Self::TokenMint | Self::Token2022Mint,
+                                        )) => {
                                             if mint.is_some()
                                                 || authority.is_none()
                                                 || decimals.is_none()
@@ -522,7 +1205,7 @@ impl BuiltinSource for Prelude {
                                                 || space.is_some()
                                             {
                                                 return Err(CoreError::make_raw(
-                                                "invalid argument to Empty[TokenMint].init()",
+                                                format!("invalid argument to Empty[{}].init()", expr.ty),
                                                 "Hint: you can only pass in a payer, an authority, a number of decimals, and optionally a list of seeds."
                                             ));
                                             }
@@ -530,23 +1213,43 @@ impl BuiltinSource for Prelude {
                                             annotation.mint_authority = authority;
                                             annotation.mint_decimals = decimals;
                                         }
-                                        TyName::Builtin(Builtin::Prelude(Self::TokenAccount)) => {
+                                        TyName::Builtin(Builtin::Prelude(
+                                            account @ (Self::TokenAccount | Self::Token2022Account),
+                                        )) => {
                                             if mint.is_none()
                                                 || authority.is_none()
                                                 || decimals.is_some()
                                                 || space.is_some()
                                             {
                                                 return Err(CoreError::make_raw(
-                                                "invalid argument to Empty[TokenAccount].init()",
+                                                format!("invalid argument to Empty[{}].init()", expr.ty),
                                                 "Hint: you can only pass in a payer, an authority, a mint, and optionally a list of seeds."
                                             ));
                                             }
 
+                                            let mint_ty = match account {
+                                                Self::Token2022Account => Self::Token2022Mint,
+                                                _ => Self::TokenMint,
+                                            };
+                                            let mint_ty = Ty::prelude(mint_ty, vec![]);
+                                            if mint.as_ref().unwrap().ty != mint_ty {
+                                                return Err(CoreError::make_raw(
+                                                    format!(
+                                                        "invalid argument to Empty[{}].init()",
+                                                        expr.ty
+                                                    ),
+                                                    format!(
+                                                        "Hint: the mint of a {} must be a {}.",
+                                                        expr.ty, mint_ty
+                                                    ),
+                                                ));
+                                            }
+
                                             if annotation.seeds.is_some()
                                                 && associated == Some(true)
                                             {
                                                 return Err(CoreError::make_raw(
-                                                "invalid argument to Empty[TokenAccount].init()",
+                                                format!("invalid argument to Empty[{}].init()", expr.ty),
                                                 "Hint: you may not initialize an associated token account with seeds."
                                             ));
                                             }
@@ -561,7 +1264,7 @@ impl BuiltinSource for Prelude {
                                         _ => {
                                             return Err(CoreError::make_raw(
                                             format!("could not initialize account type \"{}\"", expr.ty),
-                                            "Help: you can only initialize program accounts (owned by your program), SPL token mints, and SPL token accounts."
+                                            "Help: you can only initialize program accounts (owned by your program), SPL token mints, and SPL token accounts (including Token-2022 mints and accounts)."
                                         ));
                                         }
                                     },
@@ -794,8 +1497,9 @@ impl BuiltinSource for Prelude {
                 ),
             )),
             // TokenMint.mint(authority = Cast(Account), to = TokenAccount, amount = u64, signer = List[Cast(Seed)]?) -> None
-            (Self::TokenMint, "mint") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
+            // (and the same for Token2022Mint, with a Token2022Account)
+            (Self::TokenMint | Self::Token2022Mint, "mint") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![
                         (
@@ -805,7 +1509,7 @@ impl BuiltinSource for Prelude {
                         ),
                         (
                             "to",
-                            Ty::prelude(Self::TokenAccount, vec![]),
+                            Ty::prelude(self.token_account(), vec![]),
                             ParamType::Required,
                         ),
                         (
@@ -821,7 +1525,7 @@ impl BuiltinSource for Prelude {
                     ],
                     Ty::Transformed(
                         Ty::python(Python::Tuple, vec![]).into(),
-                        Transformation::new(|mut expr| {
+                        Transformation::new(|expr| {
                             let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
                             let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
                             let mut args = args.into_iter();
@@ -829,46 +1533,40 @@ impl BuiltinSource for Prelude {
                             let to = args.next().unwrap();
                             let amount = args.next().unwrap();
                             let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&mint);
 
                             let program_and_accounts = quote! {
-                                #mint.programs.get("token_program"),
-                                token::MintTo {
+                                #mint.programs.get(#program_name),
+                                #module::MintTo {
                                     mint: #mint.to_account_info(),
                                     authority: #authority.to_account_info(),
                                     to: #to.to_account_info()
                                 }
                             };
 
-                            let cpi_context = match signer.obj {
-                                ExpressionObj::Placeholder => quote! {
-                                    CpiContext::new(#program_and_accounts)
-                                },
-                                seeds => quote! {
-                                    CpiContext::new_with_signer(
-                                        #program_and_accounts,
-                                        &[#seeds.borrow().as_slice()]
-                                    )
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::mint_to(
+                                            #cpi_context,
+                                            #amount
+                                        ).unwrap();
+                                    }
                                 },
-                            };
-
-                            expr.obj = ExpressionObj::Rendered(quote! {
-                                token::mint_to(
-                                    #cpi_context,
-                                    #amount
-                                ).unwrap();
-                            });
-
-                            Ok(Transformed::Cpi {
-                                expr,
-                                program: AccountTyExpr::TokenProgram,
-                            })
+                            ))
                         }),
                     ),
                 ),
             )),
             // TokenMint.burn(authority = Cast(Account), holder = TokenAccount, amount = u64, signer = List[Cast(Seed)]?) -> None
-            (Self::TokenMint, "burn") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
+            // (and the same for Token2022Mint, with a Token2022Account)
+            (Self::TokenMint | Self::Token2022Mint, "burn") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![
                         (
@@ -878,7 +1576,7 @@ impl BuiltinSource for Prelude {
                         ),
                         (
                             "holder",
-                            Ty::prelude(Self::TokenAccount, vec![]),
+                            Ty::prelude(self.token_account(), vec![]),
                             ParamType::Required,
                         ),
                         (
@@ -894,7 +1592,7 @@ impl BuiltinSource for Prelude {
                     ],
                     Ty::Transformed(
                         Ty::python(Python::Tuple, vec![]).into(),
-                        Transformation::new(|mut expr| {
+                        Transformation::new(|expr| {
                             let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
                             let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
                             let mut args = args.into_iter();
@@ -902,83 +1600,278 @@ impl BuiltinSource for Prelude {
                             let holder = args.next().unwrap();
                             let amount = args.next().unwrap();
                             let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&mint);
 
                             let program_and_accounts = quote! {
-                                #mint.programs.get("token_program"),
-                                token::Burn {
+                                #mint.programs.get(#program_name),
+                                #module::Burn {
                                     mint: #mint.to_account_info(),
                                     authority: #authority.to_account_info(),
                                     from: #holder.to_account_info()
                                 }
                             };
 
-                            let cpi_context = match signer.obj {
-                                ExpressionObj::Placeholder => quote! {
-                                    CpiContext::new(#program_and_accounts)
-                                },
-                                seeds => quote! {
-                                    CpiContext::new_with_signer(
-                                        #program_and_accounts,
-                                        &[#seeds.borrow().as_slice()]
-                                    )
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::burn(
+                                            #cpi_context,
+                                            #amount
+                                        ).unwrap();
+                                    }
                                 },
-                            };
-
-                            expr.obj = ExpressionObj::Rendered(quote! {
-                                token::burn(
-                                    #cpi_context,
-                                    #amount
-                                ).unwrap();
-                            });
-
-                            Ok(Transformed::Cpi {
-                                expr,
-                                program: AccountTyExpr::TokenProgram,
-                            })
+                            ))
                         }),
                     ),
                 ),
             )),
-            // TokenMint.key() -> Pubkey
-            (Self::TokenMint, "key") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
-                Ty::new_function(vec![], Ty::prelude(Self::Pubkey, vec![])),
-            )),
-            // TokenMint.authority() -> Pubkey
-            (Self::TokenMint, "authority") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
+            // TokenMint.freeze(authority = Cast(Account), account = TokenAccount, signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Mint, with a Token2022Account)
+            (Self::TokenMint | Self::Token2022Mint, "freeze") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
-                    vec![],
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "account",
+                            Ty::prelude(self.token_account(), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
                     Ty::Transformed(
-                        Ty::prelude(Self::Pubkey, vec![]).into(),
-                        Transformation::new(|mut expr| {
-                            let function =
-                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
                             let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let authority = args.next().unwrap();
+                            let account = args.next().unwrap();
+                            let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&mint);
 
-                            expr.obj = ExpressionObj::Rendered(quote! {
-                                #mint.mint_authority.unwrap()
-                            });
+                            let program_and_accounts = quote! {
+                                #mint.programs.get(#program_name),
+                                #module::FreezeAccount {
+                                    account: #account.to_account_info(),
+                                    mint: #mint.to_account_info(),
+                                    authority: #authority.to_account_info()
+                                }
+                            };
 
-                            Ok(Transformed::Expression(expr))
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::freeze_account(#cpi_context).unwrap();
+                                    }
+                                },
+                            ))
                         }),
                     ),
                 ),
             )),
-            // TokenMint.freeze_authority() -> Pubkey
-            (Self::TokenMint, "freeze_authority") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
+            // TokenMint.thaw(authority = Cast(Account), account = TokenAccount, signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Mint, with a Token2022Account)
+            (Self::TokenMint | Self::Token2022Mint, "thaw") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
-                    vec![],
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "account",
+                            Ty::prelude(self.token_account(), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
                     Ty::Transformed(
-                        Ty::prelude(Self::Pubkey, vec![]).into(),
-                        Transformation::new(|mut expr| {
-                            let function =
-                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
                             let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let authority = args.next().unwrap();
+                            let account = args.next().unwrap();
+                            let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&mint);
 
-                            expr.obj = ExpressionObj::Rendered(quote! {
-                                #mint.freeze_authority.unwrap()
+                            let program_and_accounts = quote! {
+                                #mint.programs.get(#program_name),
+                                #module::ThawAccount {
+                                    account: #account.to_account_info(),
+                                    mint: #mint.to_account_info(),
+                                    authority: #authority.to_account_info()
+                                }
+                            };
+
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::thaw_account(#cpi_context).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // TokenMint.set_authority(authority = Cast(Account), new_authority = Optional[Pubkey], authority_type = str?, signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Mint)
+            (Self::TokenMint | Self::Token2022Mint, "set_authority") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "new_authority",
+                            Ty::python(Python::Optional, vec![Ty::prelude(Self::Pubkey, vec![])]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "authority_type",
+                            Ty::python(Python::Str, vec![]),
+                            ParamType::Optional,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        // Built like a directive, so that the authority type stays a string literal
+                        Transformation::new_with_context(
+                            |expr, _| {
+                                let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                                let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                                let mut args = args.into_iter();
+                                let authority = args.next().unwrap();
+                                let new_authority = args.next().unwrap();
+                                let kind = args.next().unwrap();
+                                let signer = args.next().unwrap();
+                                let (program, program_name, module) = token_program(&account);
+                                let kind = authority_type(
+                                    &program,
+                                    kind,
+                                    &["mint_tokens", "freeze_account"],
+                                )?;
+
+                                let program_and_accounts = quote! {
+                                    #account.programs.get(#program_name),
+                                    #module::SetAuthority {
+                                        current_authority: #authority.to_account_info(),
+                                        account_or_mint: #account.to_account_info()
+                                    }
+                                };
+
+                                Ok(cpi(
+                                    expr.ty,
+                                    program,
+                                    &authority,
+                                    signer,
+                                    program_and_accounts,
+                                    |cpi_context| {
+                                        quote! {
+                                            #module::set_authority(
+                                                #cpi_context,
+                                                #kind,
+                                                #new_authority
+                                            ).unwrap();
+                                        }
+                                    },
+                                ))
+                            },
+                            Some(ExprContext::Directive),
+                        ),
+                    ),
+                ),
+            )),
+            // TokenMint.signer_seeds(), TokenAccount.signer_seeds(), UncheckedAccount.signer_seeds() -> List[Seed]
+            // (and the same for Token2022Mint and Token2022Account)
+            (
+                Self::TokenMint
+                | Self::TokenAccount
+                | Self::Token2022Mint
+                | Self::Token2022Account
+                | Self::UncheckedAccount,
+                "signer_seeds",
+            ) => Some((Ty::prelude(self.clone(), vec![]), signer_seeds_method())),
+            // TokenMint.key() -> Pubkey
+            (Self::TokenMint | Self::Token2022Mint, "key") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(vec![], Ty::prelude(Self::Pubkey, vec![])),
+            )),
+            // TokenMint.authority() -> Pubkey
+            (Self::TokenMint | Self::Token2022Mint, "authority") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::prelude(Self::Pubkey, vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let function =
+                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                            let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                #mint.mint_authority.unwrap()
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // TokenMint.freeze_authority() -> Pubkey
+            (Self::TokenMint | Self::Token2022Mint, "freeze_authority") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::prelude(Self::Pubkey, vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let function =
+                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                            let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                #mint.freeze_authority.unwrap()
                             });
 
                             Ok(Transformed::Expression(expr))
@@ -987,8 +1880,8 @@ impl BuiltinSource for Prelude {
                 ),
             )),
             // TokenMint.decimals() -> u8
-            (Self::TokenMint, "decimals") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
+            (Self::TokenMint | Self::Token2022Mint, "decimals") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![],
                     Ty::Transformed(
@@ -1008,8 +1901,8 @@ impl BuiltinSource for Prelude {
                 ),
             )),
             // TokenMint.supply() -> u64
-            (Self::TokenMint, "supply") => Some((
-                Ty::prelude(Self::TokenMint, vec![]),
+            (Self::TokenMint | Self::Token2022Mint, "supply") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![],
                     Ty::Transformed(
@@ -1028,6 +1921,120 @@ impl BuiltinSource for Prelude {
                     ),
                 ),
             )),
+            // Token2022Mint.transfer_fee_basis_points() -> u16
+            (Self::Token2022Mint, "transfer_fee_basis_points") => Some((
+                Ty::prelude(Self::Token2022Mint, vec![]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::prelude(Self::RustInt(false, 16), vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let function =
+                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                            let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(mint_extension(
+                                &mint,
+                                quote! { transfer_fee },
+                                quote! { TransferFeeConfig },
+                                quote! {
+                                    u16::from(extension.get_epoch_fee(Clock::get()?.epoch).transfer_fee_basis_points)
+                                },
+                                None,
+                            ));
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // Token2022Mint.maximum_transfer_fee() -> u64
+            (Self::Token2022Mint, "maximum_transfer_fee") => Some((
+                Ty::prelude(Self::Token2022Mint, vec![]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::prelude(Self::RustInt(false, 64), vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let function =
+                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                            let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(mint_extension(
+                                &mint,
+                                quote! { transfer_fee },
+                                quote! { TransferFeeConfig },
+                                quote! {
+                                    u64::from(extension.get_epoch_fee(Clock::get()?.epoch).maximum_fee)
+                                },
+                                None,
+                            ));
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // Token2022Mint.transfer_fee(amount = u64) -> u64
+            (Self::Token2022Mint, "transfer_fee") => Some((
+                Ty::prelude(Self::Token2022Mint, vec![]),
+                Ty::new_function(
+                    vec![(
+                        "amount",
+                        Ty::prelude(Self::RustInt(false, 64), vec![]),
+                        ParamType::Required,
+                    )],
+                    Ty::Transformed(
+                        Ty::prelude(Self::RustInt(false, 64), vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args } => (function, args));
+                            let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let amount = args.into_iter().next().unwrap();
+
+                            expr.obj = ExpressionObj::Rendered(mint_extension(
+                                &mint,
+                                quote! { transfer_fee },
+                                quote! { TransferFeeConfig },
+                                quote! {
+                                    extension
+                                        .calculate_epoch_fee(Clock::get()?.epoch, #amount)
+                                        .ok_or(ProgramError::ArithmeticOverflow)?
+                                },
+                                None,
+                            ));
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // Token2022Mint.metadata_pointer() -> Optional[Pubkey]
+            (Self::Token2022Mint, "metadata_pointer") => Some((
+                Ty::prelude(Self::Token2022Mint, vec![]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::python(Python::Optional, vec![Ty::prelude(Self::Pubkey, vec![])])
+                            .into(),
+                        Transformation::new(|mut expr| {
+                            let function =
+                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                            let mint = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(mint_extension(
+                                &mint,
+                                quote! { metadata_pointer },
+                                quote! { MetadataPointer },
+                                // The pointer can be unset, or the mint might not have one at all
+                                quote! { Option::<Pubkey>::from(extension.metadata_address) },
+                                Some(quote! { None }),
+                            ));
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
             // TokenAccount.transfer(authority = Cast(Account), to = TokenAccount, amount = u64, signer = List[Cast(Seed)]?) -> None
             (Self::TokenAccount, "transfer") => Some((
                 Ty::prelude(Self::TokenAccount, vec![]),
@@ -1056,7 +2063,7 @@ impl BuiltinSource for Prelude {
                     ],
                     Ty::Transformed(
                         Ty::python(Python::Tuple, vec![]).into(),
-                        Transformation::new(|mut expr| {
+                        Transformation::new(|expr| {
                             let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
                             let from = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
                             let mut args = args.into_iter();
@@ -1074,6 +2081,380 @@ impl BuiltinSource for Prelude {
                                 }
                             };
 
+                            Ok(cpi(
+                                expr.ty,
+                                AccountTyExpr::TokenProgram,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        token::transfer(
+                                            #cpi_context,
+                                            #amount
+                                        ).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // TokenAccount.transfer_checked(authority = Cast(Account), to = TokenAccount, amount = u64, mint = TokenMint, signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Account, whose transfers are always checked)
+            (Self::TokenAccount | Self::Token2022Account, "transfer_checked")
+            | (Self::Token2022Account, "transfer") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        ("to", Ty::prelude(self.clone(), vec![]), ParamType::Required),
+                        (
+                            "amount",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "mint",
+                            Ty::prelude(self.token_mint(), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let from = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let authority = args.next().unwrap();
+                            let to = args.next().unwrap();
+                            let amount = args.next().unwrap();
+                            let mint = args.next().unwrap();
+                            let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&from);
+
+                            // Token-2022 only supports checked transfers, since transfers might
+                            // need to run the mint's extensions
+                            let program_and_accounts = quote! {
+                                #from.programs.get(#program_name),
+                                #module::TransferChecked {
+                                    from: #from.to_account_info(),
+                                    mint: #mint.to_account_info(),
+                                    to: #to.to_account_info(),
+                                    authority: #authority.to_account_info()
+                                }
+                            };
+
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::transfer_checked(
+                                            #cpi_context,
+                                            #amount,
+                                            #mint.decimals
+                                        ).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // TokenAccount.approve(authority = Cast(Account), delegate = Cast(Account), amount = u64, signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Account)
+            (Self::TokenAccount | Self::Token2022Account, "approve") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "delegate",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "amount",
+                            Ty::prelude(Self::RustInt(false, 64), vec![]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let authority = args.next().unwrap();
+                            let delegate = args.next().unwrap();
+                            let amount = args.next().unwrap();
+                            let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&account);
+
+                            let program_and_accounts = quote! {
+                                #account.programs.get(#program_name),
+                                #module::Approve {
+                                    to: #account.to_account_info(),
+                                    delegate: #delegate.to_account_info(),
+                                    authority: #authority.to_account_info()
+                                }
+                            };
+
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::approve(
+                                            #cpi_context,
+                                            #amount
+                                        ).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // TokenAccount.revoke(authority = Cast(Account), signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Account)
+            (Self::TokenAccount | Self::Token2022Account, "revoke") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let authority = args.next().unwrap();
+                            let signer = args.next().unwrap();
+                            let (program, program_name, module) = token_program(&account);
+
+                            let program_and_accounts = quote! {
+                                #account.programs.get(#program_name),
+                                #module::Revoke {
+                                    source: #account.to_account_info(),
+                                    authority: #authority.to_account_info()
+                                }
+                            };
+
+                            Ok(cpi(
+                                expr.ty,
+                                program,
+                                &authority,
+                                signer,
+                                program_and_accounts,
+                                |cpi_context| {
+                                    quote! {
+                                        #module::revoke(#cpi_context).unwrap();
+                                    }
+                                },
+                            ))
+                        }),
+                    ),
+                ),
+            )),
+            // TokenAccount.set_authority(authority = Cast(Account), new_authority = Optional[Pubkey], authority_type = str?, signer = List[Cast(Seed)]?) -> None
+            // (and the same for Token2022Account)
+            (Self::TokenAccount | Self::Token2022Account, "set_authority") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "new_authority",
+                            Ty::python(Python::Optional, vec![Ty::prelude(Self::Pubkey, vec![])]),
+                            ParamType::Required,
+                        ),
+                        (
+                            "authority_type",
+                            Ty::python(Python::Str, vec![]),
+                            ParamType::Optional,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        // Built like a directive, so that the authority type stays a string literal
+                        Transformation::new_with_context(
+                            |expr, _| {
+                                let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                                let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                                let mut args = args.into_iter();
+                                let authority = args.next().unwrap();
+                                let new_authority = args.next().unwrap();
+                                let kind = args.next().unwrap();
+                                let signer = args.next().unwrap();
+                                let (program, program_name, module) = token_program(&account);
+                                let kind = authority_type(
+                                    &program,
+                                    kind,
+                                    &["account_owner", "close_account"],
+                                )?;
+
+                                let program_and_accounts = quote! {
+                                    #account.programs.get(#program_name),
+                                    #module::SetAuthority {
+                                        current_authority: #authority.to_account_info(),
+                                        account_or_mint: #account.to_account_info()
+                                    }
+                                };
+
+                                Ok(cpi(
+                                    expr.ty,
+                                    program,
+                                    &authority,
+                                    signer,
+                                    program_and_accounts,
+                                    |cpi_context| {
+                                        quote! {
+                                            #module::set_authority(
+                                                #cpi_context,
+                                                #kind,
+                                                #new_authority
+                                            ).unwrap();
+                                        }
+                                    },
+                                ))
+                            },
+                            Some(ExprContext::Directive),
+                        ),
+                    ),
+                ),
+            )),
+            // TokenAccount.sync_native() -> None
+            // (and the same for Token2022Account)
+            (Self::TokenAccount | Self::Token2022Account, "sync_native") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let function =
+                                match1!(expr.obj, ExpressionObj::Call { function, .. } => function);
+                            let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let (program, program_name, module) = token_program(&account);
+
+                            // Anyone can sync a wrapped SOL account, so there's no authority
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                #module::sync_native(CpiContext::new(
+                                    #account.programs.get(#program_name),
+                                    #module::SyncNative {
+                                        account: #account.to_account_info()
+                                    }
+                                )).unwrap();
+                            });
+
+                            Ok(Transformed::Cpi {
+                                expr,
+                                program: Some(program),
+                                authorities: vec![],
+                                signed: None,
+                            })
+                        }),
+                    ),
+                ),
+            )),
+            // TokenAccount.close(Account, Cast(Account), [Seed]?) -> None
+            // (and the same for Token2022Account)
+            (Self::TokenAccount | Self::Token2022Account, "close") => Some((
+                Ty::prelude(self.clone(), vec![]),
+                Ty::new_function(
+                    vec![
+                        (
+                            "to",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "authority",
+                            Ty::Cast(Ty::prelude(Self::Account, vec![]).into()),
+                            ParamType::Required,
+                        ),
+                        (
+                            "signer",
+                            Ty::new_list(Ty::Cast(Ty::prelude(Self::Seed, vec![]).into())),
+                            ParamType::Optional,
+                        ),
+                    ],
+                    Ty::Transformed(
+                        Ty::python(Python::Tuple, vec![]).into(),
+                        Transformation::new(|mut expr| {
+                            let (function, args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args));
+                            let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let mut args = args.into_iter();
+                            let to = args.next().unwrap();
+                            let authority = args.next().unwrap();
+                            let signer = args.next().unwrap();
+
+                            let name = match &account.obj {
+                                ExpressionObj::Id(name) => name.clone(),
+                                _ => {
+                                    return Err(CoreError::make_raw(
+                                        "invalid call to close",
+                                        "Hint: only instruction accounts can be closed.",
+                                    ))
+                                }
+                            };
+
+                            let (_, program_name, module) = token_program(&account);
+
+                            let program_and_accounts = quote! {
+                                #account.programs.get(#program_name),
+                                #module::CloseAccount {
+                                    account: #account.to_account_info(),
+                                    destination: #to.to_account_info(),
+                                    authority: #authority.to_account_info()
+                                }
+                            };
+
                             let cpi_context = match signer.obj {
                                 ExpressionObj::Placeholder => quote! {
                                     CpiContext::new(#program_and_accounts)
@@ -1087,28 +2468,26 @@ impl BuiltinSource for Prelude {
                             };
 
                             expr.obj = ExpressionObj::Rendered(quote! {
-                                token::transfer(
-                                    #cpi_context,
-                                    #amount
-                                ).unwrap();
+                                #module::close_account(#cpi_context).unwrap();
                             });
 
-                            Ok(Transformed::Cpi {
+                            Ok(Transformed::AccountClose {
                                 expr,
-                                program: AccountTyExpr::TokenProgram,
+                                name,
+                                to: None,
                             })
                         }),
                     ),
                 ),
             )),
             // TokenAccount.key() -> Pubkey
-            (Self::TokenAccount, "key") => Some((
-                Ty::prelude(Self::TokenAccount, vec![]),
+            (Self::TokenAccount | Self::Token2022Account, "key") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(vec![], Ty::prelude(Self::Pubkey, vec![])),
             )),
             // TokenAccount.authority() -> Pubkey
-            (Self::TokenAccount, "authority") => Some((
-                Ty::prelude(Self::TokenAccount, vec![]),
+            (Self::TokenAccount | Self::Token2022Account, "authority") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![],
                     Ty::Transformed(
@@ -1128,8 +2507,8 @@ impl BuiltinSource for Prelude {
                 ),
             )),
             // TokenAccount.amount() -> u64
-            (Self::TokenAccount, "amount") => Some((
-                Ty::prelude(Self::TokenAccount, vec![]),
+            (Self::TokenAccount | Self::Token2022Account, "amount") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![],
                     Ty::Transformed(
@@ -1149,8 +2528,8 @@ impl BuiltinSource for Prelude {
                 ),
             )),
             // TokenAccount.mint() -> Pubkey
-            (Self::TokenAccount, "mint") => Some((
-                Ty::prelude(Self::TokenAccount, vec![]),
+            (Self::TokenAccount | Self::Token2022Account, "mint") => Some((
+                Ty::prelude(self.clone(), vec![]),
                 Ty::new_function(
                     vec![],
                     Ty::Transformed(
@@ -1416,7 +2795,7 @@ impl BuiltinSource for Prelude {
                 )),
                 _ => None,
             },
-            Self::TokenMint => match builtin {
+            Self::TokenMint | Self::Token2022Mint => match builtin {
                 Builtin::Prelude(Self::Account | Self::InitAccount) => {
                     Some((Ty::prelude(self.clone(), vec![]), ty.clone()))
                 }
@@ -1436,7 +2815,7 @@ impl BuiltinSource for Prelude {
                 )),
                 _ => None,
             },
-            Self::TokenAccount => match builtin {
+            Self::TokenAccount | Self::Token2022Account => match builtin {
                 Builtin::Prelude(Self::Account | Self::InitAccount) => {
                     Some((Ty::prelude(self.clone(), vec![]), ty.clone()))
                 }
@@ -5,7 +5,8 @@ use crate::{
     match1,
 };
 use prelude::{Namespace, NamespacedObject};
-use quote::quote;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -13,6 +14,8 @@ pub enum Python {
     // Types
     None,
     List,
+    Dict,
+    Optional,
     Tuple,
     Int,
     Bool,
@@ -28,6 +31,7 @@ pub enum Python {
     Round,
     Range,
     Len,
+    IsInstance,
     Enumerate,
     Filter,
     Map,
@@ -42,6 +46,8 @@ pub fn namespace() -> Namespace {
     let data = [
         ("None", Python::None),
         ("List", Python::List),
+        ("Dict", Python::Dict),
+        ("Optional", Python::Optional),
         ("Tuple", Python::Tuple),
         ("int", Python::Int),
         ("bool", Python::Bool),
@@ -53,6 +59,7 @@ pub fn namespace() -> Namespace {
         ("round", Python::Round),
         ("range", Python::Range),
         ("len", Python::Len),
+        ("isinstance", Python::IsInstance),
         ("enumerate", Python::Enumerate),
         ("filter", Python::Filter),
         ("map", Python::Map),
@@ -78,6 +85,8 @@ impl BuiltinSource for Python {
         match self {
             Self::None => "None",
             Self::List => "List",
+            Self::Dict => "Dict",
+            Self::Optional => "Optional",
             Self::Tuple => "Tuple",
             Self::Int => "int",
             Self::Bool => "bool",
@@ -91,6 +100,7 @@ impl BuiltinSource for Python {
             Self::Round => "round",
             Self::Range => "range",
             Self::Len => "len",
+            Self::IsInstance => "isinstance",
             Self::Enumerate => "enumerate",
             Self::Filter => "filter",
             Self::Map => "map",
@@ -181,54 +191,42 @@ impl BuiltinSource for Python {
                     }),
                 ),
             ),
-            // min(...T) -> T
+            // min(...T, key?: (T) -> U) -> T
+            // min(Cast(<Iter>[T]), key?: (T) -> U) -> T
             Self::Min => Ty::new_function(
-                vec![("", Ty::Anonymous(0), ParamType::Variadic)],
+                vec![
+                    ("", Ty::Anonymous(0), ParamType::VariadicOrIterable),
+                    (
+                        "key",
+                        Ty::new_function(
+                            vec![("x", Ty::Anonymous(0), ParamType::Required)],
+                            Ty::Anonymous(1)
+                        ),
+                        ParamType::Optional
+                    ),
+                ],
                 Ty::Transformed(
                     Ty::Anonymous(0).into(),
-                    Transformation::new(|expr| {
-                        let args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter().next().unwrap());
-                        let mut parts =
-                            match1!(args.obj, ExpressionObj::Vec(parts) => parts.into_iter());
-                        if parts.len() < 2 {
-                            return Err(CoreError::make_raw(
-                                "min() requires at least 1 argument",
-                                "",
-                            ));
-                        }
-
-                        let mut accum = parts.next().unwrap();
-                        for part in parts {
-                            accum.obj = accum.obj.with_call("min", vec![part]);
-                        }
-
-                        Ok(Transformed::Expression(accum))
-                    }),
+                    Transformation::new(|expr| extremum(expr, "min")),
                 ),
             ),
-            // max(...T) -> T
+            // max(...T, key?: (T) -> U) -> T
+            // max(Cast(<Iter>[T]), key?: (T) -> U) -> T
             Self::Max => Ty::new_function(
-                vec![("", Ty::Anonymous(0), ParamType::Variadic)],
+                vec![
+                    ("", Ty::Anonymous(0), ParamType::VariadicOrIterable),
+                    (
+                        "key",
+                        Ty::new_function(
+                            vec![("x", Ty::Anonymous(0), ParamType::Required)],
+                            Ty::Anonymous(1)
+                        ),
+                        ParamType::Optional
+                    ),
+                ],
                 Ty::Transformed(
                     Ty::Anonymous(0).into(),
-                    Transformation::new(|expr| {
-                        let args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter().next().unwrap());
-                        let mut parts =
-                            match1!(args.obj, ExpressionObj::Vec(parts) => parts.into_iter());
-                        if parts.len() < 2 {
-                            return Err(CoreError::make_raw(
-                                "max() requires at least 1 argument",
-                                "",
-                            ));
-                        }
-
-                        let mut accum = parts.next().unwrap();
-                        for part in parts {
-                            accum.obj = accum.obj.with_call("max", vec![part]);
-                        }
-
-                        Ok(Transformed::Expression(accum))
-                    }),
+                    Transformation::new(|expr| extremum(expr, "max")),
                 ),
             ),
             // round(f64) -> i128
@@ -307,6 +305,46 @@ impl BuiltinSource for Python {
                     })
                 )
             ),
+            // isinstance(Enum, <Enum variant>) -> bool
+            Self::IsInstance => Ty::new_function(
+                vec![
+                    ("obj", Ty::Anonymous(0), ParamType::Required),
+                    ("variant", Ty::Anonymous(1), ParamType::Required),
+                ],
+                Ty::Transformed(
+                    Ty::python(Self::Bool, vec![]).into(),
+                    Transformation::new(|mut expr| {
+                        let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                        let obj = args.next().unwrap();
+                        let variant = args.next().unwrap();
+
+                        let variant = match variant.obj {
+                            ExpressionObj::Move(variant) => *variant,
+                            obj => obj.into(),
+                        };
+
+                        // Only enum variants (`MyEnum.Variant`) can be checked
+                        match (&obj.ty, &variant.obj) {
+                            (
+                                Ty::Generic(TyName::Defined(_, DefinedType::Enum), _),
+                                ExpressionObj::StaticAttribute { .. },
+                            ) => {}
+                            _ => {
+                                return Err(CoreError::make_raw(
+                                    "isinstance can only be used to check enum variants",
+                                    "Help: try something like `isinstance(status, Status.Pending)`."
+                                ));
+                            }
+                        }
+
+                        expr.obj = ExpressionObj::Rendered(quote! {
+                            matches!(#obj, #variant { .. })
+                        });
+
+                        Ok(Transformed::Expression(expr))
+                    })
+                )
+            ),
             // enumerate(Cast(<Iter>[T])) -> <Iter>[(u64, T)]
             Self::Enumerate => Ty::new_function(
                 vec![(
@@ -363,29 +401,19 @@ impl BuiltinSource for Python {
                         let function = args.next().unwrap();
                         let iterable = args.next().unwrap();
 
-                        let return_ty = match1!(&function.ty, Ty::Function(_, returns) => *returns.clone());
+                        let elem = call_in_closure(
+                            function,
+                            vec![ExpressionObj::Rendered(quote! { elem.clone() }).into()],
+                            "filter",
+                            "a filter",
+                        )?;
 
-                        let elem = ExpressionObj::Rendered(quote! { elem.clone() });
-                        let call = ExpressionObj::Call {
-                            function: function.into(),
-                            args: vec![elem.into()]
-                        }.into();
-
-                        let elem = match return_ty {
-                            Ty::Transformed(_, transformation) => match (transformation.function)(call, &vec![].into())? {
-                                Transformed::Expression(expression) => expression,
-                                _ => {
-                                    return Err(CoreError::make_raw(
-                                        "can not filter using a special function",
-                                        "Hint: this function causes an effect that needs compiler magic to work, and can't be called from a filter."
-                                    ));
-                                }
-                            }
-                            _ => call
-                        };
+                        expr.obj = ExpressionObj::Rendered(if is_fallible(&elem) {
+                            let kept = map_in_closure(quote! { #iterable }, quote! { (#elem, elem) });
 
-                        expr.obj = ExpressionObj::Rendered(quote! {
-                            #iterable.filter(|elem| #elem)
+                            quote! { #kept.filter(|(keep, _)| *keep).map(|(_, elem)| elem) }
+                        } else {
+                            quote! { #iterable.filter(|elem| #elem) }
                         });
 
                         Ok(Transformed::Expression(expr))
@@ -418,30 +446,17 @@ impl BuiltinSource for Python {
                         let function = args.next().unwrap();
                         let iterable = args.next().unwrap();
 
-                        let return_ty = match1!(&function.ty, Ty::Function(_, returns) => *returns.clone());
+                        let elem = call_in_closure(
+                            function,
+                            vec![ExpressionObj::Rendered(quote! { elem.clone() }).into()],
+                            "map",
+                            "a map",
+                        )?;
 
-                        let elem = ExpressionObj::Rendered(quote! { elem.clone() });
-                        let call = ExpressionObj::Call {
-                            function: function.into(),
-                            args: vec![elem.into()]
-                        }.into();
-
-                        let elem = match return_ty {
-                            Ty::Transformed(_, transformation) => match (transformation.function)(call, &vec![].into())? {
-                                Transformed::Expression(expression) => expression,
-                                _ => {
-                                    return Err(CoreError::make_raw(
-                                        "can not map using a special function",
-                                        "Hint: this function causes an effect that needs compiler magic to work, and can't be called from a map."
-                                    ));
-                                }
-                            }
-                            _ => call
-                        };
-
-                        expr.obj = ExpressionObj::Rendered(quote! {
-                            #iterable.map(|elem| #elem)
-                        });
+                        expr.obj = ExpressionObj::Rendered(map_in_closure(
+                            quote! { #iterable },
+                            quote! { #elem },
+                        ));
 
                         Ok(Transformed::Expression(expr))
                     })
@@ -482,27 +497,89 @@ impl BuiltinSource for Python {
                     })
                 )
             ),
-            // sorted(Cast(<Iter>[T])) -> List[T]
-            // TODO support key, reverse
+            // sorted(Cast(<Iter>[T]), key?: (T) -> U, reverse?: bool) -> List[T]
             Self::Sorted => Ty::new_function(
-                vec![(
-                    "iterable",
-                    Ty::Cast(Ty::python(Self::Iter, vec![Ty::Anonymous(0)]).into()),
-                    ParamType::Required,
-                )],
+                vec![
+                    (
+                        "iterable",
+                        Ty::Cast(Ty::python(Self::Iter, vec![Ty::Anonymous(0)]).into()),
+                        ParamType::Required,
+                    ),
+                    (
+                        "key",
+                        Ty::new_function(
+                            vec![("x", Ty::Anonymous(0), ParamType::Required)],
+                            Ty::Anonymous(1)
+                        ),
+                        ParamType::Optional
+                    ),
+                    ("reverse", Ty::python(Self::Bool, vec![]), ParamType::Optional),
+                ],
                 Ty::Transformed(
                     Ty::python(
                         Self::List,
                         vec![Ty::Anonymous(0)],
                     ).into(),
                     Transformation::new(|mut expr| {
-                        let iterable = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter().next().unwrap());
+                        let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+                        let iterable = args.next().unwrap();
+                        let key = args.next().unwrap().optional();
+                        let reverse = args.next().unwrap().optional();
+
+                        if key.is_none() && reverse.is_none() {
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                Mutable::new({
+                                    let mut temp = #iterable.collect::<Vec<_>>();
+                                    temp.sort();
+                                    temp
+                                })
+                            });
+
+                            return Ok(Transformed::Expression(expr));
+                        }
+
+                        let is_keyed = key.is_some();
+                        let (elements, a, b) = match key {
+                            Some(key) => (
+                                with_keys(quote! { #iterable }, key, "sort", "a sort")?,
+                                quote! { &a.0 },
+                                quote! { &b.0 },
+                            ),
+                            None => (quote! { #iterable }, quote! { a }, quote! { b }),
+                        };
+
+                        // `sort_by` is stable, so flipping the comparison keeps equal elements in
+                        // their original order - just like Python's `reverse`
+                        let (reverse, ordering) = match reverse {
+                            None => (quote! {}, quote! { PartialOrd::partial_cmp(#a, #b).unwrap() }),
+                            Some(reverse) => match literal_bool(&reverse) {
+                                Some(true) => (quote! {}, quote! { PartialOrd::partial_cmp(#b, #a).unwrap() }),
+                                Some(false) => (quote! {}, quote! { PartialOrd::partial_cmp(#a, #b).unwrap() }),
+                                None => (
+                                    quote! { let reverse = #reverse; },
+                                    quote! {
+                                        if reverse {
+                                            PartialOrd::partial_cmp(#b, #a).unwrap()
+                                        } else {
+                                            PartialOrd::partial_cmp(#a, #b).unwrap()
+                                        }
+                                    },
+                                ),
+                            },
+                        };
+
+                        let sorted = if is_keyed {
+                            quote! { temp.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>() }
+                        } else {
+                            quote! { temp }
+                        };
 
                         expr.obj = ExpressionObj::Rendered(quote! {
                             Mutable::new({
-                                let mut temp = #iterable.collect::<Vec<_>>();
-                                temp.sort();
-                                temp
+                                #reverse
+                                let mut temp = #elements.collect::<Vec<_>>();
+                                temp.sort_by(|a, b| #ordering);
+                                #sorted
                             })
                         });
 
@@ -574,6 +651,8 @@ impl BuiltinSource for Python {
     fn as_instance(&self, params: &Vec<Ty>) -> CResult<()> {
         match self {
             Self::List if params.len() == 1 => Ok(()),
+            Self::Dict if params.len() == 2 => Ok(()),
+            Self::Optional if params.len() == 1 => Ok(()),
             Self::Tuple => Ok(()),
             Self::None | Self::Int if params.len() == 0 => Ok(()),
             _ => Err(CoreError::make_raw("invalid type", "")),
@@ -622,6 +701,99 @@ impl BuiltinSource for Python {
                     ),
                 ),
             )),
+            // Dict[K, V].get(K, V) -> V
+            (Self::Dict, "get") => Some((
+                Ty::python(self.clone(), vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                Ty::new_function(
+                    vec![
+                        ("key", Ty::Anonymous(0), ParamType::Required),
+                        ("default", Ty::Anonymous(1), ParamType::Required),
+                    ],
+                    Ty::Transformed(
+                        Ty::Anonymous(1).into(),
+                        Transformation::new(|mut expr| {
+                            let (function, mut args) = match1!(expr.obj, ExpressionObj::Call { function, args } => (*function, args.into_iter()));
+                            let value = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                            let key = args.next().unwrap();
+                            let default = args.next().unwrap();
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                #value.borrow().get(&#key).cloned().unwrap_or(#default)
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // Dict[K, V].keys() -> List[K]
+            (Self::Dict, "keys") => Some((
+                Ty::python(self.clone(), vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::python(Self::List, vec![Ty::Anonymous(0)]).into(),
+                        Transformation::new(|mut expr| {
+                            let function = match1!(expr.obj, ExpressionObj::Call { function, .. } => *function);
+                            let value = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                Mutable::new(#value.borrow().keys().cloned().collect::<Vec<_>>())
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // Dict[K, V].values() -> List[V]
+            (Self::Dict, "values") => Some((
+                Ty::python(self.clone(), vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::python(Self::List, vec![Ty::Anonymous(1)]).into(),
+                        Transformation::new(|mut expr| {
+                            let function = match1!(expr.obj, ExpressionObj::Call { function, .. } => *function);
+                            let value = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                Mutable::new(#value.borrow().values().cloned().collect::<Vec<_>>())
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
+            // Dict[K, V].items() -> List[(K, V)]
+            (Self::Dict, "items") => Some((
+                Ty::python(self.clone(), vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                Ty::new_function(
+                    vec![],
+                    Ty::Transformed(
+                        Ty::python(
+                            Self::List,
+                            vec![Ty::python(Self::Tuple, vec![Ty::Anonymous(0), Ty::Anonymous(1)])],
+                        )
+                        .into(),
+                        Transformation::new(|mut expr| {
+                            let function = match1!(expr.obj, ExpressionObj::Call { function, .. } => *function);
+                            let value = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                Mutable::new(
+                                    #value.borrow().iter()
+                                        .map(|(key, value)| (key.clone(), value.clone()))
+                                        .collect::<Vec<_>>()
+                                )
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ),
+            )),
             _ => None,
         }
     }
@@ -655,6 +827,32 @@ impl BuiltinSource for Python {
                     ).into()
                 )
             )),
+            // Dict[K, V].__index__(K) -> V
+            Self::Dict => Some((
+                Ty::python(self.clone(), vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                Ty::new_function(
+                    vec![("", Ty::Anonymous(0), ParamType::Required)],
+                    Ty::Transformed(
+                        Ty::Anonymous(1).into(),
+                        Transformation::new(|mut expr| {
+                            let (value, index) = match1!(expr.obj, ExpressionObj::Index { value, index } => (*value, *index));
+
+                            // Assigning to a missing key inserts it, like in Python
+                            if let ExpressionObj::BorrowMut(..) = &value.obj {
+                                expr.obj = ExpressionObj::Rendered(quote! {
+                                    (*#value.entry(#index.clone()).or_default())
+                                });
+                            } else {
+                                expr.obj = ExpressionObj::Rendered(quote! {
+                                    #value.get(&#index).unwrap().clone()
+                                });
+                            }
+
+                            Ok(Transformed::Expression(expr))
+                        })
+                    )
+                )
+            )),
             _ => None
         }
     }
@@ -691,15 +889,23 @@ impl BuiltinSource for Python {
                     Ty::python(self.clone(), vec![Ty::Anonymous(0)]),
                     Ty::Transformed(
                         Ty::python(Self::AsLen, vec![]).into(),
-                        Transformation::new(|mut expr| {
-                            let list = expr.obj;
-
-                            expr.obj = ExpressionObj::Rendered(quote! {
-                                (#list.borrow().len() as u64)
-                            });
-
-                            Ok(Transformed::Expression(expr))
-                        }),
+                        Transformation::new_with_context(
+                            |mut expr, context_stack| {
+                                let list = expr.obj;
+
+                                // Fields of accounts in constraints aren't wrapped in `Mutable`
+                                expr.obj = if context_stack.has(&ExprContext::Seed) {
+                                    ExpressionObj::Rendered(quote! { (#list.len() as u64) })
+                                } else {
+                                    ExpressionObj::Rendered(
+                                        quote! { (#list.borrow().len() as u64) },
+                                    )
+                                };
+
+                                Ok(Transformed::Expression(expr))
+                            },
+                            None,
+                        ),
                     ),
                 )),
                 Builtin::Prelude(Prelude::Seed) => Some((
@@ -739,6 +945,48 @@ impl BuiltinSource for Python {
                 )),
                 _ => None,
             },
+            Self::Dict => match builtin {
+                // Iterating over a dict iterates over its keys
+                Builtin::Python(Self::Iter) => Some((
+                    Ty::python(Self::Dict, vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                    Ty::Transformed(
+                        Ty::python(Self::Iter, vec![Ty::Anonymous(0)]).into(),
+                        Transformation::new(|mut expr| {
+                            let dict = expr.obj;
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                #dict.borrow().keys().cloned().collect::<Vec<_>>().into_iter()
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                )),
+                Builtin::Python(Self::AsLen) => Some((
+                    Ty::python(self.clone(), vec![Ty::Anonymous(0), Ty::Anonymous(1)]),
+                    Ty::Transformed(
+                        Ty::python(Self::AsLen, vec![]).into(),
+                        Transformation::new_with_context(
+                            |mut expr, context_stack| {
+                                let dict = expr.obj;
+
+                                // Fields of accounts in constraints aren't wrapped in `Mutable`
+                                expr.obj = if context_stack.has(&ExprContext::Seed) {
+                                    ExpressionObj::Rendered(quote! { (#dict.len() as u64) })
+                                } else {
+                                    ExpressionObj::Rendered(
+                                        quote! { (#dict.borrow().len() as u64) },
+                                    )
+                                };
+
+                                Ok(Transformed::Expression(expr))
+                            },
+                            None,
+                        ),
+                    ),
+                )),
+                _ => None,
+            },
             Self::Str => match builtin {
                 Builtin::Prelude(Prelude::Seed) => Some((
                     Ty::python(Python::Str, vec![]),
@@ -798,3 +1046,146 @@ impl BuiltinSource for Python {
         }
     }
 }
+
+/// Call a function arg from inside of a Rust closure (like the one passed to `Iterator::map`),
+/// where errors can't be propagated. `verb` and `noun` describe what the function is used for.
+fn call_in_closure(
+    function: TypedExpression,
+    args: Vec<TypedExpression>,
+    verb: &str,
+    noun: &str,
+) -> Result<TypedExpression, CoreError> {
+    let return_ty = match1!(&function.ty, Ty::Function(_, returns) => *returns.clone());
+
+    let call = ExpressionObj::Call {
+        function: function.into(),
+        args,
+    }
+    .into();
+
+    match return_ty {
+        Ty::Transformed(_, transformation) => {
+            match (transformation.function)(call, &vec![ExprContext::Closure].into())? {
+                Transformed::Expression(expression) => Ok(expression),
+                _ => Err(CoreError::make_raw(
+                    format!("can not {} using a special function", verb),
+                    format!("Hint: this function causes an effect that needs compiler magic to work, and can't be called from {}.", noun)
+                )),
+            }
+        }
+        _ => Ok(call),
+    }
+}
+
+/// Pair every element of an iterator with its key, as `(key, element)`. Like in Python, the key
+/// function is called exactly once per element.
+fn with_keys(
+    elements: TokenStream,
+    key: TypedExpression,
+    verb: &str,
+    noun: &str,
+) -> Result<TokenStream, CoreError> {
+    let key = call_in_closure(
+        key,
+        vec![ExpressionObj::Rendered(quote! { elem.clone() }).into()],
+        verb,
+        noun,
+    )?;
+
+    Ok(map_in_closure(elements, quote! { (#key, elem) }))
+}
+
+/// Whether some rendered code can fail with `?`, like when it calls a user-defined function.
+fn is_fallible(tokens: &impl ToTokens) -> bool {
+    tokens
+        .to_token_stream()
+        .into_iter()
+        .any(|token| match token {
+            TokenTree::Punct(punct) => punct.as_char() == '?',
+            TokenTree::Group(group) => is_fallible(&group.stream()),
+            _ => false,
+        })
+}
+
+/// Map every element of an iterator. Rust closures can't propagate errors to the function that
+/// they're in, so a map that can fail is collected up front - the first error fails the function,
+/// just like it would in Python.
+fn map_in_closure(elements: TokenStream, elem: TokenStream) -> TokenStream {
+    if is_fallible(&elem) {
+        quote! {
+            #elements
+                .map(|elem| -> Result<_> { Ok(#elem) })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+        }
+    } else {
+        quote! { #elements.map(|elem| #elem) }
+    }
+}
+
+/// Get the value of an expression if it's a bool literal.
+fn literal_bool(expr: &TypedExpression) -> Option<bool> {
+    match &expr.obj {
+        ExpressionObj::Literal(Literal::Bool(p)) => Some(*p),
+        ExpressionObj::Move(value) => literal_bool(value),
+        _ => None,
+    }
+}
+
+/// Transform a call to `min` or `max`.
+fn extremum(expr: TypedExpression, name: &str) -> Result<Transformed, CoreError> {
+    let mut args = match1!(expr.obj, ExpressionObj::Call { args, .. } => args.into_iter());
+    let mut parts = match1!(args.next().unwrap().obj, ExpressionObj::Vec(parts) => parts);
+    let key = args.next().unwrap().optional();
+
+    let elements = match (parts.len(), &key) {
+        (0, _) => {
+            return Err(CoreError::make_raw(
+                format!("{}() requires at least 1 argument", name),
+                "",
+            ));
+        }
+        // A single arg is an iterable
+        (1, _) => {
+            let iterable = parts.remove(0);
+
+            quote! { #iterable }
+        }
+        // Multiple args without a key can be compared directly
+        (_, None) => {
+            let mut parts = parts.into_iter();
+            let mut accum = parts.next().unwrap();
+            for part in parts {
+                accum.obj = accum.obj.with_call(name, vec![part]);
+            }
+
+            return Ok(Transformed::Expression(accum));
+        }
+        (_, Some(_)) => quote! { vec![#(#parts),*].into_iter() },
+    };
+
+    let (elements, a, b, element) = match key {
+        Some(key) => (
+            with_keys(elements, key, "compare", "a key")?,
+            quote! { &a.0 },
+            quote! { &b.0 },
+            quote! { .1 },
+        ),
+        None => (elements, quote! { a }, quote! { b }, quote! {}),
+    };
+
+    // Python returns the first extreme element. Rust's `min_by` does too, but `max_by` returns the
+    // last one, so `max` is found with `min_by` and a flipped comparison instead
+    let ordering = if name == "max" {
+        quote! { PartialOrd::partial_cmp(#b, #a).unwrap() }
+    } else {
+        quote! { PartialOrd::partial_cmp(#a, #b).unwrap() }
+    };
+
+    Ok(Transformed::Expression(TypedExpression {
+        ty: expr.ty,
+        obj: ExpressionObj::Rendered(quote! {
+            #elements.min_by(|a, b| #ordering).unwrap()#element
+        }),
+    }))
+}
@@ -3,10 +3,15 @@
 use crate::core::compile::builtin::BuiltinSource;
 use crate::core::{
     clean::ast,
+    generate::Grouped,
     compile::{
         ast::*,
         build::Transformation,
-        builtin::{prelude::Prelude, python::Python, *},
+        builtin::{
+            prelude::{signer_seeds_method, Prelude},
+            python::Python,
+            *,
+        },
         namespace::*,
         sign::*,
     },
@@ -15,9 +20,11 @@ use crate::core::{
 };
 use crate::match1;
 use prelude::ExprContext;
-use quote::quote;
+use heck::ToSnakeCase;
+use quote::{format_ident, quote};
 use std::collections::HashMap;
 use std::mem::replace;
+use std::rc::Rc;
 
 use super::builtin::prelude::Transformed;
 
@@ -42,7 +49,20 @@ enum Error {
     NamedFunctionParamNotFound(String, String),
     ReusedNamedFunctionParam(String),
     AdditionNotFound(Ty),
+    ContainsNotFound(Ty),
+    SliceNotFound(Ty),
+    NotRaisable(Ty),
+    NotDeletable,
     NonU32Exponent,
+    PatternNotVariant,
+    PatternVariantWithoutData(String),
+    PatternVariantWithData(String),
+    PatternArity(String, usize, usize),
+    PatternFieldNotFound(String, String),
+    PatternNestedStr,
+    NonExhaustiveMatch(String),
+    LambdaWithoutSignature,
+    LambdaArity(usize, usize),
 }
 
 impl Error {
@@ -116,10 +136,62 @@ impl Error {
                 format!("can not add two instances of {}", ty),
                 "Hint: addition can be performed on numbers, lists, and strings."
             ),
+            Self::ContainsNotFound(ty) => CoreError::make_raw(
+                format!("can not check for membership in an instance of {}", ty),
+                "Hint: `in` can be used with dicts, lists, arrays, and strings."
+            ),
+            Self::SliceNotFound(ty) => CoreError::make_raw(
+                format!("can not slice an instance of {}", ty),
+                "Hint: slices can be taken from lists, arrays, and strings."
+            ),
+            Self::NotRaisable(ty) => CoreError::make_raw(
+                format!("can not raise an instance of {}", ty),
+                "Hint: only error codes (members of a class that inherits from `ErrorCode`) can be raised."
+            ),
+            Self::NotDeletable => CoreError::make_raw(
+                "expression can not be deleted",
+                "Hint: `del` can only be used to remove a key from a dict, like `del d[key]`."
+            ),
             Self::NonU32Exponent => CoreError::make_raw(
                 "non-u32 exponent in integer exponentiation",
                 "Hint: in Rust, the pow() operation on integers requires the exponent to be a u32, and Seahorse needs to keep that constraint. If you want a non-integer result, make sure that the left side is an f64:\n\n\tf64(b) ** e"
-            )
+            ),
+            Self::PatternNotVariant => CoreError::make_raw(
+                "expected an enum variant in pattern",
+                "Help: class patterns can only match enum variants, like `case MyEnum.Variant(x, y)`."
+            ),
+            Self::PatternVariantWithoutData(variant) => CoreError::make_raw(
+                format!("{} does not carry any data", variant),
+                format!("Help: match it without parentheses:\n\n\tcase {}:", variant)
+            ),
+            Self::PatternVariantWithData(variant) => CoreError::make_raw(
+                format!("{} carries data", variant),
+                format!("Help: match it with a pattern for its data:\n\n\tcase {}(...):", variant)
+            ),
+            Self::PatternArity(variant, expected, found) => CoreError::make_raw(
+                format!("pattern for {} has the wrong number of fields", variant),
+                format!("Help: expected {} fields, found {}.", expected, found)
+            ),
+            Self::PatternFieldNotFound(variant, field) => CoreError::make_raw(
+                format!("{} has no field \"{}\"", variant, field),
+                ""
+            ),
+            Self::PatternNestedStr => CoreError::make_raw(
+                "string patterns can only be used when matching a str directly",
+                ""
+            ),
+            Self::NonExhaustiveMatch(missing) => CoreError::make_raw(
+                "match statement does not cover every case",
+                format!("Help: nothing matches `{}`. Add a case for it, or a wildcard case (`case _:`) at the end.", missing)
+            ),
+            Self::LambdaWithoutSignature => CoreError::make_raw(
+                "lambda used where a function is not expected",
+                "Help: lambdas can only be passed directly to functions that take a function, like `map`, `filter` or the `key` of `sorted`."
+            ),
+            Self::LambdaArity(expected, found) => CoreError::make_raw(
+                format!("lambda takes {} args, but should take {}", found, expected),
+                ""
+            ),
         }
         .located(loc.clone())
     }
@@ -163,6 +235,8 @@ pub enum ParamType {
     Required,
     Optional,
     Variadic,
+    // Variadic param that can also take all of its args from a single iterable, like `min`
+    VariadicOrIterable,
 }
 
 impl Ty {
@@ -205,18 +279,42 @@ impl Ty {
                 _ => true,
             },
             Ty::Generic(TyName::Builtin(Builtin::Python(builtin)), _) => match builtin {
-                Python::Bool | Python::Int | Python::None | Python::Str | Python::Tuple => false,
+                Python::Bool
+                | Python::Int
+                | Python::None
+                | Python::Optional
+                | Python::Str
+                | Python::Tuple => false,
                 _ => true,
             },
             Ty::Generic(
                 TyName::Defined(_, DefinedType::Struct | DefinedType::Account | DefinedType::Event),
                 _,
             ) => true,
-            Ty::Generic(TyName::Defined(_, DefinedType::Enum), _) => false,
+            Ty::Generic(TyName::Defined(_, DefinedType::Enum | DefinedType::ErrorCode), _) => false,
             _ => false,
         }
     }
 
+    /// If this is an `Optional[T]`, get `T`.
+    pub fn optional_of(&self) -> Option<&Ty> {
+        match self {
+            Ty::Generic(TyName::Builtin(Builtin::Python(Python::Optional)), params) => {
+                params.first()
+            }
+            Ty::Transformed(ty, _) => ty.optional_of(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this type is a user-defined error code.
+    pub fn is_error_code(&self) -> bool {
+        matches!(
+            self,
+            Ty::Generic(TyName::Defined(_, DefinedType::ErrorCode), _)
+        )
+    }
+
     /// Returns whether this type is Rust-`Copy`.
     pub fn is_copy(&self) -> bool {
         match self {
@@ -241,6 +339,23 @@ impl Ty {
         }
     }
 
+    /// Returns whether this type is an integer.
+    pub fn is_int(&self) -> bool {
+        matches!(
+            self,
+            Ty::Generic(TyName::Builtin(Builtin::Prelude(Prelude::RustInt(..))), _)
+                | Ty::IntParam(_)
+        )
+    }
+
+    /// Returns whether this type is a float.
+    pub fn is_float(&self) -> bool {
+        matches!(
+            self,
+            Ty::Generic(TyName::Builtin(Builtin::Prelude(Prelude::RustFloat)), _)
+        )
+    }
+
     /// Returns whether this type represents an account.
     /// TODO this function is becoming a mess, could stand to refactor TyName
     /// to include the "is_account" info
@@ -253,6 +368,8 @@ impl Ty {
                 | Prelude::Signer
                 | Prelude::TokenMint
                 | Prelude::TokenAccount
+                | Prelude::Token2022Mint
+                | Prelude::Token2022Account
                 | Prelude::UncheckedAccount
                 | Prelude::Clock => true,
                 _ => false,
@@ -262,6 +379,46 @@ impl Ty {
             _ => false,
         }
     }
+
+    /// Returns whether this type is an account or contains one.
+    pub fn has_account(&self) -> bool {
+        match self {
+            Ty::Generic(_, params) => {
+                self.is_account() || params.iter().any(|param| param.has_account())
+            }
+            Ty::Transformed(ty, _) => ty.has_account(),
+            _ => false,
+        }
+    }
+
+    /// Returns whether this type is a tuple or contains one.
+    pub fn has_tuple(&self) -> bool {
+        match self {
+            Ty::Generic(TyName::Builtin(Builtin::Python(Python::Tuple)), _) => true,
+            Ty::Generic(_, params) => params.iter().any(|param| param.has_tuple()),
+            Ty::Transformed(ty, _) => ty.has_tuple(),
+            _ => false,
+        }
+    }
+
+    /// If this is an `Optional[T]` where `T` is an account, get `T`. An instruction param with this
+    /// type is an account that may not be passed in.
+    pub fn optional_account(&self) -> Option<&Ty> {
+        self.optional_of().filter(|ty| ty.is_account())
+    }
+
+    /// Get the account type of a list of accounts. An instruction param with this type takes the
+    /// instruction's remaining accounts.
+    pub fn remaining_account(&self) -> Option<&Ty> {
+        match self {
+            Ty::Generic(TyName::Builtin(Builtin::Python(Python::List)), params)
+                if params[0].is_account() =>
+            {
+                Some(&params[0])
+            }
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for Ty {
@@ -306,7 +463,9 @@ impl std::fmt::Display for Ty {
                     match param_type {
                         ParamType::Required => write!(f, "{}: {}", name, t)?,
                         ParamType::Optional => write!(f, "{}?: {}", name, t)?,
-                        ParamType::Variadic => write!(f, "*{}: {}", name, t)?,
+                        ParamType::Variadic | ParamType::VariadicOrIterable => {
+                            write!(f, "*{}: {}", name, t)?
+                        }
                     }
 
                     comma = true;
@@ -354,6 +513,7 @@ pub enum DefinedType {
     Account,
     Enum,
     Event,
+    ErrorCode,
 }
 
 impl std::fmt::Display for TyName {
@@ -444,6 +604,21 @@ pub enum OrderedArg<'a> {
     Kw(Option<&'a ast::Expression>),
 }
 
+/// Get the order that a list of ordered args should be checked (and built) in. Lambdas go last, so
+/// that the types of their params are already known from the other args - e.g. in
+/// `map(lambda x: x + 1, xs)`, `x` gets its type from `xs`.
+pub fn arg_check_order(order: &[OrderedArg]) -> Vec<usize> {
+    let (lambdas, others): (Vec<usize>, Vec<usize>) = (0..order.len()).partition(|i| {
+        matches!(
+            &order[*i],
+            OrderedArg::Pos(Located(_, ast::ExpressionObj::Lambda { .. }))
+                | OrderedArg::Kw(Some(Located(_, ast::ExpressionObj::Lambda { .. })))
+        )
+    });
+
+    others.into_iter().chain(lambdas).collect()
+}
+
 /// Reorder `Args` into the order specified by a list of params.
 pub fn order_args<'a>(
     args: &'a ast::Args,
@@ -499,7 +674,7 @@ pub fn order_args<'a>(
                     }
                 }
             }
-            ParamType::Variadic => {
+            ParamType::Variadic | ParamType::VariadicOrIterable => {
                 // Variadics just consume the remaining positional args
                 let mut variadic = vec![];
                 while pos_i < args.pos.len() {
@@ -689,7 +864,8 @@ impl<'a> Context<'a> {
     /// Find the location, if any, of a variable in scope. Returns the scope level that the variable
     /// is stored in.
     fn find_var(&self, var: &String) -> Option<usize> {
-        for (i, scope) in self.scopes.iter().enumerate() {
+        // Innermost scope first, since variables can be shadowed (e.g. by narrowing)
+        for (i, scope) in self.scopes.iter().enumerate().rev() {
             if scope.contains_key(var) {
                 return Some(i);
             }
@@ -774,7 +950,7 @@ impl<'a> Context<'a> {
             Ty::IntParam(i) => self.attr(self.types[i].clone(), attr),
             Ty::Generic(t, _) => match t {
                 TyName::Builtin(x) => x.attr(attr),
-                TyName::Defined(path, DefinedType::Struct | DefinedType::Enum) => self.defined_attr(&path, attr),
+                TyName::Defined(path, DefinedType::Struct | DefinedType::Enum | DefinedType::ErrorCode) => self.defined_attr(&path, attr),
                 TyName::Defined(path, DefinedType::Event) => self.defined_attr(&path, attr).or_else(|| {
                     match attr.as_str() {
                         "emit" => Some((
@@ -796,6 +972,25 @@ impl<'a> Context<'a> {
                                 )
                             )
                         )),
+                        "emit_cpi" => Some((
+                            Ty::Anonymous(0),
+                            Ty::new_function(
+                                vec![],
+                                Ty::Transformed(
+                                    Box::new(Ty::Never),
+                                    Transformation::new(|mut expr| {
+                                        let event = match1!(expr.obj, ExpressionObj::Call { function, .. } => *function);
+                                        let event = match1!(event.obj, ExpressionObj::Attribute { value, .. } => *value);
+
+                                        expr.obj = ExpressionObj::Rendered(quote! {
+                                            #event.__emit_cpi__(&__event_authority__)?;
+                                        });
+
+                                        Ok(Transformed::EmitCpi(expr))
+                                    })
+                                )
+                            )
+                        )),
                         _ => None
                     }
                 }),
@@ -863,13 +1058,84 @@ impl<'a> Context<'a> {
                                 )
                             )
                         )),
+                        "close" => Some((
+                            Ty::Anonymous(0),
+                            Ty::new_function(
+                                vec![
+                                    ("to", Ty::Cast(Ty::prelude(Prelude::Account, vec![]).into()), ParamType::Required)
+                                ],
+                                Ty::Transformed(
+                                    Ty::python(Python::Tuple, vec![]).into(),
+                                    Transformation::new_with_context(|mut expr, _| {
+                                        let (function, mut args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args.into_iter()));
+                                        let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                                        let to = args.next().unwrap();
+
+                                        let (name, to) = match (account.obj, to.obj) {
+                                            (ExpressionObj::Id(name), ExpressionObj::Id(to)) => (name, to),
+                                            _ => return Err(CoreError::make_raw(
+                                                "invalid call to close",
+                                                "Hint: only instruction accounts can be closed, and their rent must go to another instruction account - try account.close(to=signer)."
+                                            ))
+                                        };
+
+                                        // Anchor closes the account once the instruction is done
+                                        expr.obj = ExpressionObj::Literal(Literal::Unit);
+
+                                        Ok(Transformed::AccountClose {
+                                            expr,
+                                            name,
+                                            to: Some(to),
+                                        })
+                                    }, Some(ExprContext::Seed))
+                                )
+                            )
+                        )),
+                        "realloc" => Some((
+                            Ty::Anonymous(0),
+                            Ty::new_function(
+                                vec![
+                                    ("space", Ty::prelude(Prelude::RustInt(false, 64), vec![]), ParamType::Required),
+                                    ("payer", Ty::Cast(Ty::prelude(Prelude::InitAccount, vec![]).into()), ParamType::Required),
+                                    ("zero", Ty::python(Python::Bool, vec![]), ParamType::Optional)
+                                ],
+                                Ty::Transformed(
+                                    Ty::python(Python::Tuple, vec![]).into(),
+                                    Transformation::new_with_context(|mut expr, _| {
+                                        let (function, mut args) = match1!(expr.obj, ExpressionObj::Call { function, args, } => (function, args.into_iter()));
+                                        let account = match1!(function.obj, ExpressionObj::Attribute { value, .. } => *value);
+                                        let space = args.next().unwrap();
+                                        let payer = args.next().unwrap();
+                                        let zero = args.next().unwrap().optional();
+
+                                        let name = match account.obj {
+                                            ExpressionObj::Id(name) => name,
+                                            _ => return Err(CoreError::make_raw(
+                                                "invalid call to realloc",
+                                                "Hint: only instruction accounts can be reallocated."
+                                            ))
+                                        };
+
+                                        // Anchor reallocs the account before the instruction runs
+                                        expr.obj = ExpressionObj::Literal(Literal::Unit);
+
+                                        Ok(Transformed::AccountRealloc {
+                                            expr,
+                                            name,
+                                            realloc: AccountConstraint::Realloc { space, payer, zero },
+                                        })
+                                    }, Some(ExprContext::Seed))
+                                )
+                            )
+                        )),
+                        "signer_seeds" => Some((Ty::Anonymous(0), signer_seeds_method())),
                         _ => self.defined_attr(&path, attr)
                     }
                 })
             },
             Ty::Type(t, _) => match t {
                 TyName::Builtin(x) => x.static_attr(attr).map(|t| (Ty::Anonymous(0), t)),
-                TyName::Defined(path, DefinedType::Struct | DefinedType::Enum | DefinedType::Account | DefinedType::Event) => self.defined_static_attr(&path, attr),
+                TyName::Defined(path, DefinedType::Struct | DefinedType::Enum | DefinedType::Account | DefinedType::Event | DefinedType::ErrorCode) => self.defined_static_attr(&path, attr),
             },
             Ty::Path(mut abs) => match self.sign_output.namespace_output.tree.get(&abs) {
                 Some(Tree::Leaf(namespace)) => match namespace.get(attr) {
@@ -929,9 +1195,12 @@ impl<'a> Context<'a> {
                             Some(Signature::Class(ClassSignature::Enum(..))) => {
                                 Some((Ty::Anonymous(0), Ty::Type(TyName::Defined(abs, DefinedType::Enum), None)))
                             }
+                            Some(Signature::Class(ClassSignature::ErrorCode(..))) => {
+                                Some((Ty::Anonymous(0), Ty::Type(TyName::Defined(abs, DefinedType::ErrorCode), None)))
+                            }
                             Some(Signature::Function(func)) => Some((
                                 Ty::Anonymous(0),
-                                Ty::Function(func.params.clone(), func.returns.clone().into()),
+                                Ty::Function(func.params.clone(), fallible(func.returns.clone()).into()),
                             )),
                             Some(Signature::Builtin(builtin)) => Some((
                                 Ty::Anonymous(0),
@@ -973,7 +1242,7 @@ impl<'a> Context<'a> {
                 {
                     Some((
                         Ty::Anonymous(0),
-                        Ty::Function(params.clone(), returns.clone().into()),
+                        Ty::Function(params.clone(), fallible(returns.clone()).into()),
                     ))
                 } else {
                     let mut attr_ty = None;
@@ -987,7 +1256,43 @@ impl<'a> Context<'a> {
                     attr_ty
                 }
             }
-            Signature::Class(ClassSignature::Enum(..))
+            // Reading a variant's data, e.g. `status.Pending`
+            Signature::Class(ClassSignature::Enum(sig)) => {
+                let fields = match sig.variant(attr)? {
+                    VariantSignature::Unit => {
+                        return None;
+                    }
+                    VariantSignature::Tuple(fields) => fields.clone(),
+                    VariantSignature::Struct(fields) => {
+                        fields.iter().map(|(_, ty)| ty.clone()).collect()
+                    }
+                };
+
+                let ty = if fields.len() == 1 {
+                    fields.into_iter().next().unwrap()
+                } else {
+                    Ty::python(Python::Tuple, fields)
+                };
+
+                let method = format_ident!("{}", attr.to_snake_case());
+
+                Some((
+                    Ty::Anonymous(0),
+                    Ty::Transformed(
+                        ty.into(),
+                        Transformation::new(move |mut expr| {
+                            let value = match1!(expr.obj, ExpressionObj::Attribute { value, .. } => value);
+
+                            expr.obj = ExpressionObj::Rendered(quote! {
+                                #value.#method()
+                            });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                ))
+            }
+            Signature::Class(ClassSignature::ErrorCode(..))
             | Signature::Function(..)
             | Signature::Constant(..) => None,
             Signature::Builtin(builtin) => builtin.attr(attr),
@@ -1005,17 +1310,69 @@ impl<'a> Context<'a> {
                     {
                         Some((
                             Ty::Anonymous(0),
-                            Ty::Function(params.clone(), returns.clone().into()),
+                            Ty::Function(params.clone(), fallible(returns.clone()).into()),
                         ))
                     } else {
                         None
                     }
                 }
-                Signature::Class(ClassSignature::Enum(EnumSignature { variants })) => {
+                Signature::Class(ClassSignature::Enum(sig)) => {
+                    let ty = Ty::Generic(TyName::Defined(path.clone(), DefinedType::Enum), vec![]);
+
+                    match sig.variant(attr)? {
+                        VariantSignature::Unit => Some((Ty::Anonymous(0), ty)),
+                        // Variants with data are constructed by calling them
+                        VariantSignature::Tuple(fields) => Some((
+                            Ty::Anonymous(0),
+                            Ty::Function(
+                                fields
+                                    .iter()
+                                    .map(|ty| ("".to_string(), ty.clone(), ParamType::Required))
+                                    .collect(),
+                                ty.into(),
+                            ),
+                        )),
+                        VariantSignature::Struct(fields) => {
+                            let names = fields
+                                .iter()
+                                .map(|(name, _)| format_ident!("{}", name))
+                                .collect::<Vec<_>>();
+
+                            Some((
+                                Ty::Anonymous(0),
+                                Ty::Function(
+                                    fields
+                                        .iter()
+                                        .map(|(name, ty)| {
+                                            (name.clone(), ty.clone(), ParamType::Required)
+                                        })
+                                        .collect(),
+                                    Ty::Transformed(
+                                        ty.into(),
+                                        Transformation::new(move |mut expr| {
+                                            let (variant, args) = match1!(expr.obj, ExpressionObj::Call { function, args } => (function, args));
+
+                                            expr.obj = ExpressionObj::Rendered(quote! {
+                                                #variant { #(#names: #args),* }
+                                            });
+
+                                            Ok(Transformed::Expression(expr))
+                                        }),
+                                    )
+                                    .into(),
+                                ),
+                            ))
+                        }
+                    }
+                }
+                Signature::Class(ClassSignature::ErrorCode(ErrorCodeSignature { variants })) => {
                     if variants.iter().any(|(name, _)| name == attr) {
                         Some((
                             Ty::Anonymous(0),
-                            Ty::Generic(TyName::Defined(path.clone(), DefinedType::Enum), vec![]),
+                            Ty::Generic(
+                                TyName::Defined(path.clone(), DefinedType::ErrorCode),
+                                vec![],
+                            ),
                         ))
                     } else {
                         None
@@ -1038,6 +1395,7 @@ impl<'a> Context<'a> {
         let ast::FunctionDef {
             body,
             decorator_list,
+            params: def_params,
             ..
         } = func;
 
@@ -1060,6 +1418,16 @@ impl<'a> Context<'a> {
             scope.insert(name.clone(), i);
         }
 
+        // Param defaults (account constraints) can refer to any of the params
+        self.scopes.push(scope.clone());
+        for Located(_, ast::ParamObj { default, .. }) in def_params.params.iter() {
+            if let Some(default) = default {
+                let i = self.free();
+                self.check_expr(Ty::Param(i), default)?;
+            }
+        }
+        self.scopes.pop();
+
         let i = self.new_ty(returns.clone());
         self.returns = i;
 
@@ -1084,6 +1452,24 @@ impl<'a> Context<'a> {
         return Ok(());
     }
 
+    /// Typecheck a block in which an optional variable might be narrowed to its inner type.
+    fn check_narrowed_block(
+        &mut self,
+        block: &Vec<ast::Statement>,
+        narrowed: Option<HashMap<String, usize>>,
+    ) -> CResult<()> {
+        let narrowed = match narrowed {
+            Some(narrowed) => narrowed,
+            None => return self.check_block(block, None),
+        };
+
+        self.scopes.push(narrowed);
+        self.check_block(block, None)?;
+        self.scopes.pop();
+
+        Ok(())
+    }
+
     /// Typecheck a statement.
     fn check_statement(&mut self, statement: &ast::Statement) -> CResult<()> {
         let Located(loc, obj) = statement;
@@ -1094,7 +1480,16 @@ impl<'a> Context<'a> {
 
                 match value {
                     Some(value) => {
-                        self.check_expr(returns, value)?;
+                        let param_value = self.free();
+                        let value_i = self.check_expr(Ty::Param(param_value), value)?;
+                        let ty = self.unify(returns, Ty::Param(param_value), loc)?;
+
+                        // Wrap non-optional values returned from optional functions
+                        if self.base(self.returns).optional_of().is_some()
+                            && self.base(param_value).optional_of().is_none()
+                        {
+                            self.expr_order[value_i] = ty;
+                        }
                     }
                     None => {
                         self.unify(
@@ -1109,11 +1504,30 @@ impl<'a> Context<'a> {
                 self.check_expr(Ty::python(Python::Bool, vec![]), test)?;
                 match msg {
                     Some(msg) => {
-                        self.check_expr(Ty::python(Python::Str, vec![]), msg)?;
+                        // The message can be a string or an error code
+                        let param_msg = self.free();
+                        self.check_expr(Ty::Param(param_msg), msg)?;
+
+                        if !self.base(param_msg).is_error_code() {
+                            self.unify(
+                                Ty::python(Python::Str, vec![]),
+                                Ty::Param(param_msg),
+                                &msg.0,
+                            )?;
+                        }
                     }
                     None => {}
                 }
             }
+            ast::StatementObj::Raise { error } => {
+                let param_error = self.free();
+                self.check_expr(Ty::Param(param_error), error)?;
+
+                let ty = self.base(param_error);
+                if !ty.is_error_code() {
+                    return Err(Error::NotRaisable(self.finalize(ty)).core(&error.0));
+                }
+            }
             ast::StatementObj::OpAssign { target, op, value } => {
                 let param_op = self.free();
 
@@ -1165,7 +1579,7 @@ impl<'a> Context<'a> {
                     });
 
                     let param_value = self.free();
-                    self.check_expr(Ty::Param(param_value), value)?;
+                    let value_i = self.check_expr(Ty::Param(param_value), value)?;
 
                     let mut declarations = vec![];
                     let param_target = self.declare_target(&target, false, &mut declarations)?;
@@ -1177,7 +1591,14 @@ impl<'a> Context<'a> {
                         *undeclared = declarations;
                     }
 
-                    self.unify(Ty::Param(param_target), Ty::Param(param_value), loc)?;
+                    let ty = self.unify(Ty::Param(param_target), Ty::Param(param_value), loc)?;
+
+                    // Assigning a `T` to an existing `Optional[T]` needs the value to be wrapped
+                    if self.base(param_target).optional_of().is_some()
+                        && self.base(param_value).optional_of().is_none()
+                    {
+                        self.expr_order[value_i] = ty;
+                    }
                 }
                 None => {
                     let i = self.free();
@@ -1192,16 +1613,45 @@ impl<'a> Context<'a> {
             }
             ast::StatementObj::If { test, body, orelse } => {
                 self.check_expr(Ty::python(Python::Bool, vec![]), test)?;
-                self.check_block(body, None)?;
+
+                // `if x is not None` narrows `x` from `Optional[T]` to `T` in the body, and
+                // `if x is None` does the same in the else block
+                let mut scopes = (None, None);
+                if let Some((var, in_body)) = narrowed_var(test) {
+                    let param = self
+                        .find_var(var)
+                        .map(|i| self.base(*self.scopes[i].get(var).unwrap()));
+
+                    if let Some(inner) = param.as_ref().and_then(|ty| ty.optional_of()) {
+                        let scope = HashMap::from([(var.clone(), self.new_ty(inner.clone()))]);
+
+                        scopes = match in_body {
+                            true => (Some(scope), None),
+                            false => (None, Some(scope)),
+                        };
+                    }
+                }
+
+                // The narrowed variable gets its own scope around the block, so assigning to it
+                // mutates it instead of declaring a new variable
+                self.check_narrowed_block(body, scopes.0)?;
                 if let Some(orelse) = orelse {
-                    self.check_block(orelse, None)?;
+                    self.check_narrowed_block(orelse, scopes.1)?;
                 }
             }
-            ast::StatementObj::While { test, body } => {
+            ast::StatementObj::While { test, body, orelse } => {
                 self.check_expr(Ty::python(Python::Bool, vec![]), test)?;
                 self.check_block(body, None)?;
+                if let Some(orelse) = orelse {
+                    self.check_block(orelse, None)?;
+                }
             }
-            ast::StatementObj::For { target, iter, body } => {
+            ast::StatementObj::For {
+                target,
+                iter,
+                body,
+                orelse,
+            } => {
                 let param_iter = self.free();
                 self.check_expr(Ty::Param(param_iter), iter)?;
 
@@ -1222,6 +1672,54 @@ impl<'a> Context<'a> {
                 let scope = self.scopes.pop().unwrap();
 
                 self.check_block(body, Some(scope))?;
+                if let Some(orelse) = orelse {
+                    self.check_block(orelse, None)?;
+                }
+            }
+            ast::StatementObj::Delete { target } => match &target.1 {
+                ast::ExpressionObj::Index { value, index } => {
+                    let param_key = self.free();
+                    let param_value = self.free();
+
+                    self.check_expr(
+                        Ty::python(
+                            Python::Dict,
+                            vec![Ty::Param(param_key), Ty::Param(param_value)],
+                        ),
+                        value,
+                    )?;
+                    self.check_expr(Ty::Param(param_key), index)?;
+                }
+                _ => return Err(Error::NotDeletable.core(loc)),
+            },
+            ast::StatementObj::Match { subject, cases } => {
+                let param_subject = self.free();
+                self.check_expr(Ty::Param(param_subject), subject)?;
+
+                // Guarded cases don't count towards exhaustiveness
+                let mut rows = vec![];
+                for ast::MatchCase {
+                    pattern,
+                    guard,
+                    body,
+                } in cases.iter()
+                {
+                    self.scopes.push(HashMap::new());
+                    let pat = self.check_pattern(Ty::Param(param_subject), pattern, true)?;
+                    match guard {
+                        Some(guard) => {
+                            self.check_expr(Ty::python(Python::Bool, vec![]), guard)?;
+                        }
+                        None => rows.push(vec![pat]),
+                    }
+                    let scope = self.scopes.pop().unwrap();
+
+                    self.check_block(body, Some(scope))?;
+                }
+
+                if let Some(mut missing) = missing_pattern(rows, 1) {
+                    return Err(Error::NonExhaustiveMatch(missing.remove(0)).core(loc));
+                }
             }
             ast::StatementObj::Break | ast::StatementObj::Continue | ast::StatementObj::Pass => {}
         }
@@ -1229,6 +1727,221 @@ impl<'a> Context<'a> {
         return Ok(());
     }
 
+    /// Typecheck a pattern that a value of type `ty` is matched against, declaring the variables
+    /// that it captures in the current scope. Returns a simplified version of the pattern that
+    /// is used to check exhaustiveness.
+    fn check_pattern(&mut self, ty: Ty, pattern: &ast::Pattern, top: bool) -> CResult<Pat> {
+        let Located(loc, obj) = pattern;
+
+        match obj {
+            ast::PatternObj::Wildcard => Ok(Pat::Wild),
+            ast::PatternObj::Capture(name) => {
+                match self.scopes.last().unwrap().get(name) {
+                    // Each option of an or-pattern captures the same variables
+                    Some(param) => {
+                        let param = *param;
+                        self.unify(Ty::Param(param), ty, loc)?;
+                    }
+                    None => {
+                        let param = self.new_ty(ty);
+                        self.scopes
+                            .last_mut()
+                            .unwrap()
+                            .insert(name.clone(), param);
+                    }
+                }
+
+                Ok(Pat::Wild)
+            }
+            ast::PatternObj::Literal(Located(_, literal)) => {
+                let (name, ctors) = match literal {
+                    ast::ExpressionObj::Bool(b) => {
+                        self.unify(ty, Ty::python(Python::Bool, vec![]), loc)?;
+
+                        let name = if *b { "True" } else { "False" };
+                        (name.to_string(), CtorSet::Bool)
+                    }
+                    ast::ExpressionObj::Str(s) => {
+                        if !top {
+                            return Err(Error::PatternNestedStr.core(loc));
+                        }
+                        self.unify(ty, Ty::python(Python::Str, vec![]), loc)?;
+
+                        (format!("'{}'", s), CtorSet::Infinite)
+                    }
+                    literal => {
+                        let param_n = self.free();
+                        self.types[param_n] = Ty::IntParam(param_n);
+                        self.unify(ty, self.types[param_n].clone(), loc)?;
+
+                        let name = match literal {
+                            ast::ExpressionObj::Int(n) => n.to_string(),
+                            ast::ExpressionObj::UnOp { value, .. } => {
+                                format!("-{}", match1!(&value.1, ast::ExpressionObj::Int(n) => n))
+                            }
+                            _ => panic!(),
+                        };
+                        (name, CtorSet::Infinite)
+                    }
+                };
+
+                Ok(Pat::Ctor {
+                    name,
+                    args: vec![],
+                    ctors: ctors.into(),
+                })
+            }
+            ast::PatternObj::Value(value) => {
+                let variant = match1!(&value.1, ast::ExpressionObj::Attribute { name, .. } => name);
+
+                let param_value = self.free();
+                self.check_expr(Ty::Param(param_value), value)?;
+
+                match self.base(param_value) {
+                    Ty::Generic(TyName::Defined(path, DefinedType::Enum), _) => {
+                        self.unify(ty, Ty::Param(param_value), loc)?;
+
+                        Ok(Pat::Ctor {
+                            name: variant.clone(),
+                            args: vec![],
+                            ctors: self.enum_ctors(&path).into(),
+                        })
+                    }
+                    Ty::Function(_, returns) => match self.variant_enum(*returns) {
+                        Some(path) => Err(Error::PatternVariantWithData(format!(
+                            "{}.{}",
+                            path.last().unwrap(),
+                            variant
+                        ))
+                        .core(loc)),
+                        None => Err(Error::PatternNotVariant.core(loc)),
+                    },
+                    _ => Err(Error::PatternNotVariant.core(loc)),
+                }
+            }
+            ast::PatternObj::Class { cls, args, kwargs } => {
+                let variant = match &cls.1 {
+                    ast::ExpressionObj::Attribute { name, .. } => name,
+                    _ => return Err(Error::PatternNotVariant.core(loc)),
+                };
+
+                let param_cls = self.free();
+                self.check_expr(Ty::Param(param_cls), cls)?;
+
+                let (params, path) = match self.base(param_cls) {
+                    Ty::Function(params, returns) => match self.variant_enum(*returns) {
+                        Some(path) => (params, path),
+                        None => return Err(Error::PatternNotVariant.core(loc)),
+                    },
+                    Ty::Generic(TyName::Defined(path, DefinedType::Enum), _) => {
+                        return Err(Error::PatternVariantWithoutData(format!(
+                            "{}.{}",
+                            path.last().unwrap(),
+                            variant
+                        ))
+                        .core(loc));
+                    }
+                    _ => return Err(Error::PatternNotVariant.core(loc)),
+                };
+                let display = format!("{}.{}", path.last().unwrap(), variant);
+
+                self.unify(
+                    ty,
+                    Ty::Generic(TyName::Defined(path.clone(), DefinedType::Enum), vec![]),
+                    loc,
+                )?;
+
+                // Tuple variants have unnamed fields, which can only be matched by position
+                let is_struct = params.iter().any(|(name, ..)| !name.is_empty());
+                if args.len() > params.len() || (!is_struct && args.len() != params.len()) {
+                    return Err(Error::PatternArity(display, params.len(), args.len()).core(loc));
+                }
+
+                let mut fields = vec![Pat::Wild; params.len()];
+                let mut matched = vec![false; params.len()];
+                for (i, arg) in args.iter().enumerate() {
+                    fields[i] = self.check_pattern(params[i].1.clone(), arg, false)?;
+                    matched[i] = true;
+                }
+                for (name, arg) in kwargs.iter() {
+                    let i = match params.iter().position(|(field, ..)| field == name) {
+                        Some(i) if is_struct => i,
+                        _ => {
+                            return Err(Error::PatternFieldNotFound(display, name.clone()).core(loc))
+                        }
+                    };
+                    if matched[i] {
+                        return Err(Error::ReusedNamedFunctionParam(name.clone()).core(loc));
+                    }
+
+                    fields[i] = self.check_pattern(params[i].1.clone(), arg, false)?;
+                    matched[i] = true;
+                }
+
+                Ok(Pat::Ctor {
+                    name: variant.clone(),
+                    args: fields,
+                    ctors: self.enum_ctors(&path).into(),
+                })
+            }
+            ast::PatternObj::Tuple(elements) => {
+                let params = elements
+                    .iter()
+                    .map(|_| Ty::Param(self.free()))
+                    .collect::<Vec<_>>();
+                self.unify(ty, Ty::python(Python::Tuple, params.clone()), loc)?;
+
+                let args = elements
+                    .iter()
+                    .zip(params)
+                    .map(|(element, param)| self.check_pattern(param, element, false))
+                    .collect::<CResult<Vec<_>>>()?;
+
+                Ok(Pat::Ctor {
+                    name: "".to_string(),
+                    ctors: CtorSet::Tuple(args.len()).into(),
+                    args,
+                })
+            }
+            ast::PatternObj::Or(options) => Ok(Pat::Or(
+                options
+                    .iter()
+                    .map(|option| self.check_pattern(ty.clone(), option, top))
+                    .collect::<CResult<Vec<_>>>()?,
+            )),
+        }
+    }
+
+    /// Get the path of the enum that a variant constructor returns, if it is one.
+    fn variant_enum(&self, returns: Ty) -> Option<Vec<String>> {
+        match returns {
+            Ty::Transformed(returns, _) => self.variant_enum(*returns),
+            Ty::Generic(TyName::Defined(path, DefinedType::Enum), _) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Get the variants of an enum (and how many fields each one has).
+    fn enum_ctors(&self, path: &Vec<String>) -> CtorSet {
+        let variants = match self.sign_output.tree.get_leaf_ext(path) {
+            Some(Signature::Class(ClassSignature::Enum(EnumSignature { variants }))) => variants
+                .iter()
+                .map(|(name, variant)| {
+                    let arity = match variant {
+                        VariantSignature::Unit => 0,
+                        VariantSignature::Tuple(fields) => fields.len(),
+                        VariantSignature::Struct(fields) => fields.len(),
+                    };
+
+                    (name.clone(), arity)
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        CtorSet::Enum(path.last().unwrap().clone(), variants)
+    }
+
     /// Typecheck an expression that needs to unify to type `expr_ty`. Based on the expression, might
     /// impose additional constraints on the underlying type of `expr_ty`.
     fn check_expr(&mut self, expr_ty: Ty, expression: &ast::Expression) -> CResult<usize> {
@@ -1312,6 +2025,40 @@ impl<'a> Context<'a> {
                     }
                 }
             }
+            ast::ExpressionObj::Slice {
+                value,
+                start,
+                stop,
+                step,
+            } => {
+                let param_value = self.free();
+                self.check_expr(Ty::Param(param_value), value)?;
+
+                // Slices of lists and arrays are always lists, slices of strings are strings
+                let ty = match self.base(param_value) {
+                    Ty::Generic(
+                        TyName::Builtin(
+                            Builtin::Python(Python::List) | Builtin::Prelude(Prelude::Array),
+                        ),
+                        params,
+                    ) => Ty::python(Python::List, vec![params[0].clone()]),
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::Str)), _) => {
+                        Ty::python(Python::Str, vec![])
+                    }
+                    ty => {
+                        return Err(Error::SliceNotFound(self.finalize(ty)).core(loc));
+                    }
+                };
+
+                for part in [start, stop, step].into_iter().flatten() {
+                    self.check_expr(
+                        Ty::Cast(Ty::prelude(Prelude::RustInt(true, 128), vec![]).into()),
+                        part,
+                    )?;
+                }
+
+                self.unify(expr_ty, ty, loc)?
+            }
             ast::ExpressionObj::UnOp { op, value } => {
                 match op {
                     ast::UnaryOperator::Pos | ast::UnaryOperator::Neg | ast::UnaryOperator::Inv => {
@@ -1361,6 +2108,51 @@ impl<'a> Context<'a> {
 
                 expr_ty
             }
+            ast::ExpressionObj::Lambda { params, body } => {
+                let (expected_params, _) = match self.base_ty(expr_ty.clone()) {
+                    Ty::Function(params, returns) => (params, returns),
+                    _ => {
+                        return Err(Error::LambdaWithoutSignature.core(loc));
+                    }
+                };
+
+                let expected_params = expected_params
+                    .into_iter()
+                    .filter(|(_, _, param_type)| param_type == &ParamType::Required)
+                    .collect::<Vec<_>>();
+                if expected_params.len() != params.len() {
+                    return Err(Error::LambdaArity(expected_params.len(), params.len()).core(loc));
+                }
+
+                let mut scope = HashMap::new();
+                let mut lambda_params = vec![];
+                for (name, (_, ty, _)) in params.iter().zip(expected_params) {
+                    let param = self.new_ty(ty);
+                    scope.insert(name.clone(), param);
+                    lambda_params.push((name.clone(), Ty::Param(param), ParamType::Required));
+                }
+
+                self.scopes.push(scope);
+                let param_body = self.free();
+                self.check_expr(Ty::Param(param_body), body)?;
+                self.scopes.pop();
+
+                self.unify(
+                    expr_ty,
+                    Ty::Function(lambda_params, Ty::Param(param_body).into()),
+                    loc,
+                )?
+            }
+            ast::ExpressionObj::Bind { name, value, body } => {
+                let param_value = self.free();
+                self.check_expr(Ty::Param(param_value), value)?;
+
+                self.scopes.push(HashMap::from([(name.clone(), param_value)]));
+                self.check_expr(expr_ty.clone(), body)?;
+                self.scopes.pop();
+
+                expr_ty
+            }
             ast::ExpressionObj::Int(_) => {
                 let param_n = self.free();
                 self.types[param_n] = Ty::IntParam(param_n);
@@ -1385,6 +2177,25 @@ impl<'a> Context<'a> {
 
                 list_ty
             }
+            ast::ExpressionObj::Dict(dict) => {
+                let param_key = self.free();
+                let param_value = self.free();
+                let dict_ty = self.unify(
+                    expr_ty,
+                    Ty::python(
+                        Python::Dict,
+                        vec![Ty::Param(param_key), Ty::Param(param_value)],
+                    ),
+                    loc,
+                )?;
+
+                for (key, value) in dict.iter() {
+                    self.check_expr(Ty::Param(param_key), key)?;
+                    self.check_expr(Ty::Param(param_value), value)?;
+                }
+
+                dict_ty
+            }
             ast::ExpressionObj::Tuple(tuple) => {
                 let tuple_params = (0..tuple.len())
                     .map(|_| Ty::Param(self.free()))
@@ -1463,7 +2274,7 @@ impl<'a> Context<'a> {
                 // matter but the typecheck output needs those types anyway
                 for part in parts.iter() {
                     match part {
-                        ast::FStrPart::ExpressionObj(expr) => {
+                        ast::FStrPart::ExpressionObj(expr, _) => {
                             self.check_expr(Ty::Any, expr)?;
                         }
                         _ => {}
@@ -1543,12 +2354,20 @@ impl<'a> Context<'a> {
                                     ),
                                     loc,
                                 )?,
+                                Some(Signature::Class(ClassSignature::ErrorCode(..))) => self.unify(
+                                    expr_ty,
+                                    Ty::Type(
+                                        TyName::Defined(path.clone(), DefinedType::ErrorCode),
+                                        None,
+                                    ),
+                                    loc,
+                                )?,
                                 Some(Signature::Function(FunctionSignature {
                                     params,
                                     returns,
                                 })) => self.unify(
                                     expr_ty,
-                                    Ty::Function(params.clone(), returns.clone().into()),
+                                    Ty::Function(params.clone(), fallible(returns.clone()).into()),
                                     loc,
                                 )?,
                                 None => {
@@ -1615,10 +2434,18 @@ impl<'a> Context<'a> {
                                 Ty::Type(TyName::Defined(path.clone(), DefinedType::Enum), None),
                                 loc,
                             )?,
+                            Some(Signature::Class(ClassSignature::ErrorCode(..))) => self.unify(
+                                expr_ty,
+                                Ty::Type(
+                                    TyName::Defined(path.clone(), DefinedType::ErrorCode),
+                                    None,
+                                ),
+                                loc,
+                            )?,
                             Some(Signature::Function(FunctionSignature { params, returns })) => {
                                 self.unify(
                                     expr_ty,
-                                    Ty::Function(params.clone(), returns.clone().into()),
+                                    Ty::Function(params.clone(), fallible(returns.clone()).into()),
                                     loc,
                                 )?
                             }
@@ -1839,7 +2666,34 @@ impl<'a> Context<'a> {
 
                 self.unify(expr_ty, self.base(param), loc)
             }
-            ast::Operator::In | ast::Operator::NotIn => todo!(),
+            ast::Operator::In | ast::Operator::NotIn => {
+                let param_element = self.free();
+                let param_container = self.free();
+                self.check_expr(Ty::Param(param_element), left)?;
+                self.check_expr(Ty::Param(param_container), right)?;
+
+                // Unify the left side with whatever the container holds - keys for a dict,
+                // elements for a list/array, substrings for a string
+                match self.base(param_container) {
+                    Ty::Generic(
+                        TyName::Builtin(
+                            Builtin::Python(Python::Dict | Python::List)
+                            | Builtin::Prelude(Prelude::Array),
+                        ),
+                        params,
+                    ) => {
+                        self.unify(params[0].clone(), Ty::Param(param_element), loc)?;
+                    }
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::Str)), _) => {
+                        self.unify(Ty::python(Python::Str, vec![]), Ty::Param(param_element), loc)?;
+                    }
+                    ty => {
+                        return Err(Error::ContainsNotFound(self.finalize(ty)).core(loc));
+                    }
+                }
+
+                self.unify(expr_ty, Ty::python(Python::Bool, vec![]), loc)
+            }
         }
     }
 
@@ -1894,13 +2748,22 @@ impl<'a> Context<'a> {
             Ty::Function(params, return_ty) => {
                 let order = order_args(args, &params, loc)?;
 
-                for (arg, (_, arg_ty, _)) in order.into_iter().zip(params.into_iter()) {
-                    match arg {
+                for i in arg_check_order(&order) {
+                    let (_, arg_ty, param_type) = params[i].clone();
+                    match &order[i] {
                         OrderedArg::Pos(arg) => {
                             self.check_expr(arg_ty, arg)?;
                         }
+                        OrderedArg::Var(args)
+                            if args.len() == 1 && param_type == ParamType::VariadicOrIterable =>
+                        {
+                            self.check_expr(
+                                Ty::Cast(Ty::python(Python::Iter, vec![arg_ty]).into()),
+                                args[0],
+                            )?;
+                        }
                         OrderedArg::Var(args) => {
-                            for arg in args.into_iter() {
+                            for arg in args.iter() {
                                 self.check_expr(arg_ty.clone(), arg)?;
                             }
                         }
@@ -2113,6 +2976,37 @@ impl<'a> Context<'a> {
                     Err(Error::UnificationConst(n, m).core(loc))
                 }
             }
+            // `T` and `None` can be used wherever an `Optional[T]` is expected
+            (Ty::Generic(TyName::Builtin(Builtin::Python(Python::Optional)), params), t)
+                if matches!(t, Ty::Generic(..) | Ty::IntParam(..)) && t.optional_of().is_none() =>
+            {
+                let inner = params.into_iter().next().unwrap();
+
+                let (inner, transformation) = match t {
+                    Ty::Generic(TyName::Builtin(Builtin::Python(Python::None)), _) => (
+                        inner,
+                        Transformation::new(|mut expr| {
+                            expr.obj = ExpressionObj::Rendered(quote! { None });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                    t => (
+                        self.unify(inner, t, loc)?,
+                        Transformation::new(|mut expr| {
+                            let value = Grouped(&expr);
+                            expr.obj = ExpressionObj::Rendered(quote! { Some(#value) });
+
+                            Ok(Transformed::Expression(expr))
+                        }),
+                    ),
+                };
+
+                Ok(Ty::Transformed(
+                    Ty::python(Python::Optional, vec![inner]).into(),
+                    transformation,
+                ))
+            }
             // Match generics
             (Ty::Generic(x, a), Ty::Generic(y, b)) => {
                 if x != y {
@@ -2201,20 +3095,31 @@ impl<'a> Context<'a> {
                 Ty::Generic(TyName::Defined(_, DefinedType::Account), _),
             ) => Ok(Ty::Transformed(
                 c.into(),
-                Transformation::new(|mut expr| {
-                    let obj = expr.obj;
-
-                    expr.obj = ExpressionObj::Rendered(quote! {
-                        #obj.borrow().__account__
-                    });
+                Transformation::new_with_context(
+                    |mut expr, context_stack| {
+                        // Accounts in seeds and constraints are already plain Anchor accounts
+                        if !context_stack.has(&ExprContext::Seed) {
+                            let account = TypedExpression {
+                                ty: expr.ty.clone(),
+                                obj: expr.obj,
+                            };
+
+                            expr.obj = ExpressionObj::AnchorAccount(account.into());
+                        }
 
-                    Ok(Transformed::Expression(expr))
-                }),
+                        Ok(Transformed::Expression(expr))
+                    },
+                    None,
+                ),
             )),
             (
                 c @ Ty::Generic(TyName::Builtin(Builtin::Prelude(Prelude::InitAccount)), _),
                 Ty::Generic(TyName::Defined(_, DefinedType::Account), _),
             ) => Ok(c.into()),
+            // Wrap values that get passed as optionals
+            (c @ Ty::Generic(TyName::Builtin(Builtin::Python(Python::Optional)), _), t) => {
+                self.unify(c, t, loc)
+            }
             (c, t) => Err(Error::UnificationCast(t, c).core(loc)),
         }
     }
@@ -2348,3 +3253,160 @@ impl TryFrom<SignOutput> for CheckOutput {
 pub fn check(signed: SignOutput) -> CResult<CheckOutput> {
     signed.try_into()
 }
+
+/// If an if statement's test is `x is not None` or `x is None`, get the name of `x` and whether
+/// it's known to not be `None` in the body (`true`) or the else block (`false`).
+pub fn narrowed_var(test: &ast::Expression) -> Option<(&String, bool)> {
+    match &test.1 {
+        ast::ExpressionObj::BinOp { left, op, right } => match (&left.1, op, &right.1) {
+            (ast::ExpressionObj::Id(var), ast::Operator::NotEq, ast::ExpressionObj::None) => {
+                Some((var, true))
+            }
+            (ast::ExpressionObj::Id(var), ast::Operator::Eq, ast::ExpressionObj::None) => {
+                Some((var, false))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// A simplified pattern, used to check that match statements are exhaustive.
+#[derive(Clone, Debug)]
+enum Pat {
+    Wild,
+    Ctor {
+        name: String,
+        args: Vec<Pat>,
+        ctors: Rc<CtorSet>,
+    },
+    Or(Vec<Pat>),
+}
+
+/// Every constructor that a value of some type could be made with.
+#[derive(Debug)]
+enum CtorSet {
+    Enum(String, Vec<(String, usize)>),
+    Tuple(usize),
+    Bool,
+    // Ints and strings - can only be covered by a wildcard
+    Infinite,
+}
+
+impl CtorSet {
+    fn ctors(&self) -> Vec<(String, usize)> {
+        match self {
+            Self::Enum(_, variants) => variants.clone(),
+            Self::Tuple(len) => vec![("".to_string(), *len)],
+            Self::Bool => vec![("True".to_string(), 0), ("False".to_string(), 0)],
+            Self::Infinite => vec![],
+        }
+    }
+
+    fn display(&self, name: &String, args: Vec<String>) -> String {
+        match self {
+            Self::Enum(enum_name, _) if args.is_empty() => format!("{}.{}", enum_name, name),
+            Self::Enum(enum_name, _) => format!("{}.{}({})", enum_name, name, args.join(", ")),
+            Self::Tuple(..) => format!("({})", args.join(", ")),
+            Self::Bool | Self::Infinite => name.clone(),
+        }
+    }
+}
+
+/// Find a row of values that isn't matched by any of the given rows of patterns, where each row
+/// has `width` patterns. Returns `None` if the patterns are exhaustive.
+///
+/// This is the usual "usefulness" algorithm (checking whether a row of wildcards would be useful
+/// if it was added to the end), building up a witness of the missing values as it goes.
+fn missing_pattern(rows: Vec<Vec<Pat>>, width: usize) -> Option<Vec<String>> {
+    if width == 0 {
+        return match rows.is_empty() {
+            true => Some(vec![]),
+            false => None,
+        };
+    }
+
+    // Expand or-patterns in the first column into one row per option
+    let mut expanded = vec![];
+    let mut stack = rows;
+    stack.reverse();
+    while let Some(mut row) = stack.pop() {
+        match row[0].clone() {
+            Pat::Or(options) => {
+                for option in options.into_iter().rev() {
+                    row[0] = option;
+                    stack.push(row.clone());
+                }
+            }
+            _ => expanded.push(row),
+        }
+    }
+    let rows = expanded;
+
+    let ctors = rows.iter().find_map(|row| match &row[0] {
+        Pat::Ctor { ctors, .. } => Some(ctors.clone()),
+        _ => None,
+    });
+
+    let all = ctors.as_ref().map(|ctors| ctors.ctors()).unwrap_or(vec![]);
+    let used = all
+        .iter()
+        .filter(|(name, _)| {
+            rows.iter()
+                .any(|row| matches!(&row[0], Pat::Ctor { name: used, .. } if used == name))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if !all.is_empty() && used.len() == all.len() {
+        // Every constructor is used, so check the values made with each of them
+        let ctors = ctors.unwrap();
+        for (name, arity) in all.into_iter() {
+            let specialized = rows
+                .iter()
+                .filter_map(|row| {
+                    let head = match &row[0] {
+                        Pat::Ctor { name: used, args, .. } if *used == name => args.clone(),
+                        Pat::Wild => vec![Pat::Wild; arity],
+                        _ => return None,
+                    };
+
+                    Some(head.into_iter().chain(row[1..].iter().cloned()).collect())
+                })
+                .collect();
+
+            if let Some(mut witness) = missing_pattern(specialized, arity + width - 1) {
+                let rest = witness.split_off(arity);
+                let mut missing = vec![ctors.display(&name, witness)];
+                missing.extend(rest);
+
+                return Some(missing);
+            }
+        }
+
+        None
+    } else {
+        // Some constructor isn't used, so only the rows starting with a wildcard can match it
+        let default = rows
+            .iter()
+            .filter(|row| matches!(row[0], Pat::Wild))
+            .map(|row| row[1..].to_vec())
+            .collect();
+
+        let rest = missing_pattern(default, width - 1)?;
+        let head = match (
+            ctors,
+            all.iter().find(|ctor| !used.contains(ctor)),
+        ) {
+            (Some(ctors), Some((name, arity))) => {
+                ctors.display(name, vec!["_".to_string(); *arity])
+            }
+            _ => "_".to_string(),
+        };
+
+        let mut missing = vec![head];
+        missing.extend(rest);
+
+        Some(missing)
+    }
+}
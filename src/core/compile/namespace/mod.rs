@@ -211,6 +211,7 @@ impl TryFrom<pre::ModuleRegistry> for NamespaceOutput {
             Module::Python(..) => Wip::Empty,
             Module::SeahorsePrelude => Wip::Done(prelude::namespace()),
             Module::SeahorsePyth => Wip::Done(pyth::namespace()),
+            Module::Idl(..) => Wip::Empty,
         });
 
         build_namespace(&mut wip, &registry, &registry.origin)?;
@@ -284,6 +285,21 @@ impl Tree<Namespace> {
                         .located(loc.clone())),
                 }?;
 
+                // A trailing constant on a `List`, `Dict` or `str` declares its maximum length,
+                // which only matters when calculating account space - it isn't part of the type
+                let mut params = params;
+                let bounded_len = match &base {
+                    TyName::Builtin(Builtin::Python(python::Python::Str)) => 1,
+                    TyName::Builtin(Builtin::Python(python::Python::List)) => 2,
+                    TyName::Builtin(Builtin::Python(python::Python::Dict)) => 3,
+                    _ => 0,
+                };
+                if bounded_len > 0 && params.len() == bounded_len {
+                    if let Some(Ty::Const(..)) = params.last() {
+                        params.pop();
+                    }
+                }
+
                 Ok(Ty::Generic(base, params))
             }
             ca::TyExpressionObj::Const(n) => Ok(Ty::Const(*n)),
@@ -311,6 +327,13 @@ fn build_namespace(
             let module = match1!(registry.tree.get(path).unwrap(), Tree::Leaf(module) => module);
             let namespace = match module {
                 pre::Module::Python(module) => build_python_namespace(wip, registry, path, module)?,
+                // IDL types are declared like any other classes, next to the instructions
+                pre::Module::Idl(types, instructions) => {
+                    let mut namespace = build_python_namespace(wip, registry, path, types)?;
+                    namespace.append(&mut idl::namespace(instructions));
+
+                    namespace
+                }
                 _ => panic!(),
             };
 
@@ -13,22 +13,28 @@ use crate::core::{
 use crate::match1;
 // LOL I JUST LEARNED THAT I COULD DO THIS INSTEAD OF IMPORTING FROM CRATE
 use super::{
-    builtin::{pyth::ExprContext, Builtin, BuiltinSource, Python},
+    builtin::{Builtin, BuiltinSource, Python},
     check::{DefinedType, ParamType, Ty, TyName},
 };
 use quote::quote;
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap};
 
 enum Error {
     InvalidBase(Ty),
     EnumAccount,
     InvalidEnumVariant,
+    NestedClass,
+    InvalidErrorCodeVariant,
+    DuplicateErrorCode(String, String),
     InvalidClassField,
     InvalidClassConstructor,
     AccountConstructor,
     InvalidClassDecorator(ca::ExpressionObj),
     UnsupportedClassDecorator(String),
     DuplicateClassField(String),
+    ZeroCopyNonAccount,
+    ZeroCopyField(String, Ty),
+    ZeroCopyPadding(Option<String>),
 }
 
 impl Error {
@@ -41,7 +47,19 @@ impl Error {
             Self::EnumAccount => CoreError::make_raw("accounts may not be enums", ""),
             Self::InvalidEnumVariant => CoreError::make_raw(
                 "invalid enum variant",
-                "Help: `Enum` is a special type in Seahorse - you may only define variants like this:\n\n    variant_name = <unique int>"
+                "Help: `Enum` is a special type in Seahorse - you may only define variants like this:\n\n    variant_name = <unique int>\n\nVariants can also carry data, either with a type annotation:\n\n    variant_name: Tuple[u64, Pubkey]\n\nor as a nested dataclass:\n\n    @dataclass\n    class VariantName:\n        field_name: Type"
+            ),
+            Self::NestedClass => CoreError::make_raw(
+                "nested classes are only supported as enum variants",
+                ""
+            ),
+            Self::InvalidErrorCodeVariant => CoreError::make_raw(
+                "invalid error code",
+                "Help: `ErrorCode` is a special type in Seahorse - you may only define errors with a message like this:\n\n    ErrorName = 'error message'"
+            ),
+            Self::DuplicateErrorCode(first, second) => CoreError::make_raw(
+                format!("\"{}\" is a second ErrorCode class, after \"{}\"", second, first),
+                "Help: Anchor numbers a program's errors from 6000, so every error has to be declared in the same ErrorCode class to get its own code."
             ),
             Self::InvalidClassField => CoreError::make_raw(
                 "invalid class field",
@@ -64,7 +82,7 @@ impl Error {
             Self::UnsupportedClassDecorator(dec) => {
                 CoreError::make_raw(
                     format!("{} is not a supported class decorator", dec),
-                    "Hint: Only dataclass is currently supported for classes. Imported paths like \"seahorse.prelude.dataclass\" are not currently supported.",
+                    "Hint: Only dataclass and zero_copy are currently supported for classes. Imported paths like \"seahorse.prelude.dataclass\" are not currently supported.",
                 )
             }
             Self::DuplicateClassField(field) => {
@@ -73,6 +91,21 @@ impl Error {
                     "Hint: a field can only be declared in a class once"
                 )
             }
+            Self::ZeroCopyNonAccount => CoreError::make_raw(
+                "only accounts can be zero-copy",
+                "Hint: @zero_copy goes on a class that inherits from Account."
+            ),
+            Self::ZeroCopyField(field, ty) => CoreError::make_raw(
+                format!("field \"{}\" of a zero-copy account can not be a {}", field, ty),
+                "Hint: zero-copy accounts are used in place, so their fields can only be numbers (like u64 or f64), Pubkeys and Arrays of them."
+            ),
+            Self::ZeroCopyPadding(field) => CoreError::make_raw(
+                match field {
+                    Some(field) => format!("zero-copy account needs padding before field \"{}\"", field),
+                    None => "zero-copy account needs padding after its last field".to_string(),
+                },
+                "Hint: Anchor doesn't allow padding bytes in zero-copy accounts. Try ordering the fields from largest to smallest alignment (u128/i128, then 64-bit, 32-bit, 16-bit and 8-bit numbers, then Pubkeys), or add an explicit padding field like `_padding: Array[u8, 4]`."
+            ),
         }
         .located(loc.clone())
     }
@@ -104,6 +137,7 @@ pub enum Signature {
 pub enum ClassSignature {
     Struct(StructSignature),
     Enum(EnumSignature),
+    ErrorCode(ErrorCodeSignature),
 }
 
 /// Signature for a class that gets treated as a struct.
@@ -112,6 +146,7 @@ pub struct StructSignature {
     pub is_account: bool,
     pub is_event: bool,
     pub is_dataclass: bool,
+    pub is_zero_copy: bool,
     pub bases: Vec<Ty>,
     pub fields: HashMap<String, Ty>,
     pub methods: HashMap<String, (MethodType, FunctionSignature)>,
@@ -140,7 +175,7 @@ impl StructSignature {
 
                             // The __new__ function is defined on the loaded type
                             expr.obj = ExpressionObj::Rendered(quote! {
-                                <Loaded!(#class)>::__new__(#(#args),*)
+                                <Loaded!(#class)>::__new__(#(#args),*)?
                             });
 
                             Ok(Transformed::Expression(expr))
@@ -152,10 +187,52 @@ impl StructSignature {
     }
 }
 
+/// Wrap the return type of a user-defined function. Generated functions return a `Result` so that
+/// errors can be raised from anywhere, so every call needs to propagate the error (even from inside
+/// of a closure, which gets made fallible when it needs to be).
+pub fn fallible(returns: Ty) -> Ty {
+    Ty::Transformed(
+        returns.into(),
+        Transformation::new(|mut expr| {
+            let obj = expr.obj;
+
+            expr.obj = ExpressionObj::Rendered(quote! { #obj? });
+
+            Ok(Transformed::Expression(expr))
+        }),
+    )
+}
+
 /// Signature for a class that gets treated as an enum.
 #[derive(Clone, Debug)]
 pub struct EnumSignature {
-    pub variants: Vec<(String, ())>,
+    pub variants: Vec<(String, VariantSignature)>,
+}
+
+impl EnumSignature {
+    pub fn variant(&self, name: &String) -> Option<&VariantSignature> {
+        self.variants
+            .iter()
+            .find(|(variant, _)| variant == name)
+            .map(|(_, variant)| variant)
+    }
+}
+
+/// Signature for the data carried by an enum variant.
+#[derive(Clone, Debug)]
+pub enum VariantSignature {
+    Unit,
+    // Declared with a type annotation, a `Tuple[...]` annotation gives multiple fields
+    Tuple(Vec<Ty>),
+    // Declared as a nested dataclass
+    Struct(Vec<(String, Ty)>),
+}
+
+/// Signature for a class that gets treated as a set of Anchor error codes. Each variant has a
+/// message.
+#[derive(Clone, Debug)]
+pub struct ErrorCodeSignature {
+    pub variants: Vec<(String, String)>,
 }
 
 /// Signature for a function.
@@ -184,6 +261,9 @@ impl Tree<Signed> {
                                 ..
                             })) => DefinedType::Event,
                             Signature::Class(ClassSignature::Enum(..)) => DefinedType::Enum,
+                            Signature::Class(ClassSignature::ErrorCode(..)) => {
+                                DefinedType::ErrorCode
+                            }
                             _ => DefinedType::Struct,
                         };
 
@@ -200,6 +280,19 @@ impl Tree<Signed> {
             ty => ty,
         }
     }
+
+    /// Correct the typenames in a function signature.
+    fn correct_function(&self, signature: FunctionSignature) -> FunctionSignature {
+        let FunctionSignature { params, returns } = signature;
+
+        FunctionSignature {
+            params: params
+                .into_iter()
+                .map(|(name, ty, is_required)| (name, self.correct(ty), is_required))
+                .collect(),
+            returns: self.correct(returns),
+        }
+    }
 }
 
 impl TryFrom<NamespaceOutput> for SignOutput {
@@ -211,6 +304,9 @@ impl TryFrom<NamespaceOutput> for SignOutput {
         //    `TyName::Defined`.
         // 2. corrects the type names to include `TyName::DefinedAccount` as well.
 
+        // Every ErrorCode class in the program, by module
+        let error_codes = RefCell::new(vec![]);
+
         let raw_tree = namespace_output
             .tree
             .clone()
@@ -221,6 +317,14 @@ impl TryFrom<NamespaceOutput> for SignOutput {
                     match export {
                         NamespacedObject::Item(Item::Defined(def)) => {
                             let signature = build_signature(def, abs, &namespace_output.tree)?;
+                            if let Signature::Class(ClassSignature::ErrorCode(..)) = &signature {
+                                error_codes.borrow_mut().push((
+                                    abs.clone(),
+                                    def.0.clone(),
+                                    name.clone(),
+                                ));
+                            }
+
                             signatures.insert(name.clone(), signature);
                         }
                         NamespacedObject::Automatic(builtin)
@@ -235,6 +339,14 @@ impl TryFrom<NamespaceOutput> for SignOutput {
             })
             .transpose()?;
 
+        // Each class would become its own enum of error codes starting from 6000, so the codes
+        // would collide
+        let mut error_codes = error_codes.into_inner();
+        error_codes.sort_by_key(|(abs, loc, _)| (abs.clone(), loc.loc.row(), loc.loc.column()));
+        if let [(_, _, first), (_, loc, second), ..] = &error_codes[..] {
+            return Err(Error::DuplicateErrorCode(first.clone(), second.clone()).core(loc));
+        }
+
         let tree = raw_tree.clone().map(|signatures| {
             HashMap::from_iter(signatures.into_iter().map(|(name, signature)| {
                 (
@@ -244,6 +356,7 @@ impl TryFrom<NamespaceOutput> for SignOutput {
                             is_account,
                             is_event,
                             is_dataclass,
+                            is_zero_copy,
                             bases,
                             fields,
                             methods,
@@ -251,23 +364,54 @@ impl TryFrom<NamespaceOutput> for SignOutput {
                             is_account,
                             is_event,
                             is_dataclass,
+                            is_zero_copy,
                             bases,
                             fields: fields
                                 .into_iter()
                                 .map(|(name, ty)| (name, raw_tree.correct(ty)))
                                 .collect(),
-                            methods,
+                            methods: methods
+                                .into_iter()
+                                .map(|(name, (method_type, signature))| {
+                                    (name, (method_type, raw_tree.correct_function(signature)))
+                                })
+                                .collect(),
                         })),
-                        Signature::Function(FunctionSignature { params, returns }) => {
-                            Signature::Function(FunctionSignature {
-                                params: params
+                        Signature::Class(ClassSignature::Enum(EnumSignature { variants })) => {
+                            Signature::Class(ClassSignature::Enum(EnumSignature {
+                                variants: variants
                                     .into_iter()
-                                    .map(|(name, ty, is_required)| {
-                                        (name, raw_tree.correct(ty), is_required)
+                                    .map(|(name, variant)| {
+                                        (
+                                            name,
+                                            match variant {
+                                                VariantSignature::Unit => VariantSignature::Unit,
+                                                VariantSignature::Tuple(fields) => {
+                                                    VariantSignature::Tuple(
+                                                        fields
+                                                            .into_iter()
+                                                            .map(|ty| raw_tree.correct(ty))
+                                                            .collect(),
+                                                    )
+                                                }
+                                                VariantSignature::Struct(fields) => {
+                                                    VariantSignature::Struct(
+                                                        fields
+                                                            .into_iter()
+                                                            .map(|(name, ty)| {
+                                                                (name, raw_tree.correct(ty))
+                                                            })
+                                                            .collect(),
+                                                    )
+                                                }
+                                            },
+                                        )
                                     })
                                     .collect(),
-                                returns: raw_tree.correct(returns),
-                            })
+                            }))
+                        }
+                        Signature::Function(signature) => {
+                            Signature::Function(raw_tree.correct_function(signature))
                         }
                         signature => signature,
                     },
@@ -303,14 +447,19 @@ fn build_signature(
             let mut is_account = false;
             let mut is_enum = false;
             let mut is_event = false;
+            let mut is_error_code = false;
             let mut bases_ = vec![];
             let mut is_dataclass = false;
+            let mut is_zero_copy = false;
 
             for Located(loc, decorator) in decorator_list.into_iter() {
                 match decorator {
                     ca::ExpressionObj::Id(d) if d == "dataclass" => {
                         is_dataclass = true;
                     }
+                    ca::ExpressionObj::Id(d) if d == "zero_copy" => {
+                        is_zero_copy = true;
+                    }
                     ca::ExpressionObj::Id(d) => {
                         return Err(Error::UnsupportedClassDecorator(d.to_string()).core(&loc))
                     }
@@ -343,6 +492,12 @@ fn build_signature(
                     ) => {
                         is_event = true;
                     }
+                    Ty::Generic(
+                        TyName::Builtin(bi::Builtin::Prelude(bi::prelude::Prelude::ErrorCode)),
+                        _,
+                    ) => {
+                        is_error_code = true;
+                    }
                     ty => {
                         return Err(Error::InvalidBase(ty).core(&loc));
                     }
@@ -353,21 +508,99 @@ fn build_signature(
                 return Err(Error::EnumAccount.core(loc));
             }
 
-            if is_enum {
+            if is_zero_copy && !is_account {
+                return Err(Error::ZeroCopyNonAccount.core(loc));
+            }
+
+            if is_error_code {
                 let mut variants = vec![];
                 for statement in body.iter() {
                     let Located(loc, obj) = statement;
 
                     match obj {
-                        ca::ClassDefStatementObj::FieldDef { name, ty, value } => {
-                            if ty.is_some() || value.is_none() {
+                        ca::ClassDefStatementObj::FieldDef {
+                            name,
+                            ty: None,
+                            value: Some(Located(_, ca::ExpressionObj::Str(msg))),
+                        } => {
+                            variants.push((name.clone(), msg.clone()));
+                        }
+                        _ => {
+                            return Err(Error::InvalidErrorCodeVariant.core(loc));
+                        }
+                    }
+                }
+
+                Ok(Signature::Class(ClassSignature::ErrorCode(
+                    ErrorCodeSignature { variants },
+                )))
+            } else if is_enum {
+                let mut variants = vec![];
+                for statement in body.iter() {
+                    let Located(loc, obj) = statement;
+
+                    match obj {
+                        ca::ClassDefStatementObj::FieldDef {
+                            name,
+                            ty: None,
+                            value: Some(..),
+                        } => {
+                            variants.push((name.clone(), VariantSignature::Unit));
+                        }
+                        ca::ClassDefStatementObj::FieldDef {
+                            name,
+                            ty: Some(ty),
+                            value: None,
+                        } => {
+                            let fields = match root.build_ty(ty, abs)? {
+                                Ty::Generic(
+                                    TyName::Builtin(Builtin::Python(Python::Tuple)),
+                                    params,
+                                ) => params,
+                                ty => vec![ty],
+                            };
+
+                            variants.push((name.clone(), VariantSignature::Tuple(fields)));
+                        }
+                        ca::ClassDefStatementObj::ClassDef {
+                            name,
+                            body,
+                            decorator_list,
+                        } => {
+                            let is_dataclass = match decorator_list.as_slice() {
+                                [Located(_, ca::ExpressionObj::Id(d))] => d == "dataclass",
+                                _ => false,
+                            };
+
+                            if !is_dataclass {
                                 return Err(Error::InvalidEnumVariant.core(loc));
                             }
 
-                            variants.push((name.clone(), ()));
+                            let mut fields = vec![];
+                            for Located(loc, field) in body.iter() {
+                                match field {
+                                    ca::ClassDefStatementObj::FieldDef {
+                                        name,
+                                        ty: Some(ty),
+                                        value: None,
+                                    } => {
+                                        if fields.iter().any(|(field, _)| field == name) {
+                                            return Err(Error::DuplicateClassField(name.clone())
+                                                .core(loc));
+                                        }
+
+                                        fields.push((name.clone(), root.build_ty(ty, abs)?));
+                                    }
+                                    _ => {
+                                        return Err(Error::InvalidClassField.core(loc));
+                                    }
+                                }
+                            }
+
+                            variants.push((name.clone(), VariantSignature::Struct(fields)));
                         }
                         _ => {
-                            todo!();
+                            return Err(Error::InvalidEnumVariant.core(loc));
                         }
                     }
                 }
@@ -440,9 +673,17 @@ fn build_signature(
                                 );
                             }
                         }
+                        ca::ClassDefStatementObj::ClassDef { .. } => {
+                            return Err(Error::NestedClass.core(loc));
+                        }
                     }
                 }
 
+                if is_zero_copy {
+                    check_zero_copy_layout(&fields_ordered, &fields)
+                        .map_err(|err| err.core(loc))?;
+                }
+
                 if !has_ctor && is_dataclass {
                     // If there is no constructor for a dataclass, then for typechecking purposes
                     // we add a __init__ function, which takes all fields as params, and returns None
@@ -451,6 +692,7 @@ fn build_signature(
                     let param_objs = fields_ordered.iter().map(|(name, ty_expr)| ParamObj {
                         arg: name.to_string(),
                         annotation: ty_expr.clone(),
+                        default: None,
                     });
 
                     let params: Params = Params {
@@ -473,6 +715,7 @@ fn build_signature(
                     is_account,
                     is_event,
                     is_dataclass,
+                    is_zero_copy,
                     fields: fields
                         .iter()
                         .map(|(name, ty)| (name.clone(), ty.clone()))
@@ -492,6 +735,63 @@ fn build_signature(
     .map_err(|err: Error| err.core(loc))
 }
 
+/// Get the size and alignment of a type that can be stored in a zero-copy account, or `None` if
+/// the type isn't plain old data.
+fn zero_copy_layout(ty: &Ty) -> Option<(u64, u64)> {
+    match ty {
+        Ty::Generic(TyName::Builtin(Builtin::Prelude(builtin)), params) => match builtin {
+            // Ints are aligned to their size. 128-bit ints are only 8-aligned on some targets, but
+            // the stricter alignment makes sure that no target needs padding
+            bi::prelude::Prelude::RustInt(_, bits) => {
+                let size = (*bits / 8) as u64;
+
+                Some((size, size))
+            }
+            bi::prelude::Prelude::RustFloat => Some((8, 8)),
+            bi::prelude::Prelude::Pubkey => Some((32, 1)),
+            bi::prelude::Prelude::Array => match params.as_slice() {
+                [element, Ty::Const(len)] => {
+                    zero_copy_layout(element).map(|(size, align)| (size * len, align))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Check that the fields of a zero-copy account are plain old data, laid out without any padding
+/// (as required by `bytemuck::Pod`).
+fn check_zero_copy_layout(
+    fields_ordered: &Vec<(&String, ca::TyExpression)>,
+    fields: &HashMap<String, Ty>,
+) -> Result<(), Error> {
+    let mut offset = 0;
+    let mut max_align = 1;
+    for (name, _) in fields_ordered.iter() {
+        let ty = fields.get(*name).unwrap();
+        let (size, align) = match zero_copy_layout(ty) {
+            Some(layout) => layout,
+            None => return Err(Error::ZeroCopyField(name.to_string(), ty.clone())),
+        };
+
+        if offset % align != 0 {
+            return Err(Error::ZeroCopyPadding(Some(name.to_string())));
+        }
+
+        offset += size;
+        max_align = max_align.max(align);
+    }
+
+    // Trailing padding is needed if the size isn't a multiple of the alignment
+    if offset % max_align != 0 {
+        return Err(Error::ZeroCopyPadding(None));
+    }
+
+    Ok(())
+}
+
 fn build_function_signature(
     params: &ca::Params,
     returns: &Option<ca::TyExpression>,
@@ -501,7 +801,7 @@ fn build_function_signature(
     let params = params
         .params
         .iter()
-        .map(|Located(_, ca::ParamObj { arg, annotation })| {
+        .map(|Located(_, ca::ParamObj { arg, annotation, .. })| {
             let ty = root.build_ty(annotation, abs)?;
             Ok((arg.clone(), ty, ParamType::Required))
         })
@@ -1,8 +1,9 @@
 use crate::{
     core::{compile::{ast::*, build::BuildOutput}, util::*, CoreError}, data::SEAHORSE_UTIL, match1
 };
-use proc_macro2::{Ident, Literal as PM2Literal, TokenStream};
+use proc_macro2::{Ident, Literal as PM2Literal, TokenStream, TokenTree};
 use quote::{format_ident, quote, ToTokens};
+use heck::ToSnakeCase;
 use regex::Regex;
 #[cfg(not(target_arch = "wasm32"))]
 use rustfmt_wrapper::{config::*, rustfmt_config, Error as RustfmtError};
@@ -19,12 +20,14 @@ pub struct GenerateOutput {
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Feature {
     Pyth,
+    EventCpi,
 }
 
 impl Feature {
     pub fn name(&self) -> &'static str {
         match self {
             Self::Pyth => "pyth-sdk-solana",
+            Self::EventCpi => "event-cpi",
         }
     }
 }
@@ -34,6 +37,11 @@ fn ident<S: ToString>(name: &S) -> Ident {
     format_ident!("{}", name.to_string())
 }
 
+/// Make a loop label (`'name`).
+fn loop_label(name: &str) -> TokenStream {
+    format!("'{}", name).parse().unwrap()
+}
+
 /// Newtype for a `::`-separated path.
 struct StaticPath<'a>(&'a Vec<String>);
 impl ToTokens for StaticPath<'_> {
@@ -62,7 +70,7 @@ impl ToTokens for Artifact {
 
             // Default imports
             use crate::{id, seahorse_util::*};
-            use std::{rc::Rc, cell::RefCell};
+            use std::{rc::Rc, cell::RefCell, collections::BTreeMap};
             use anchor_lang::{prelude::*, solana_program};
             // TODO might not need these, contexts are defined in lib.rs now
             use anchor_spl::token::{self, Token, Mint, TokenAccount};
@@ -147,6 +155,7 @@ impl ToTokens for TypeDef {
             Self::Struct(type_def) => quote! { #type_def },
             Self::Account(type_def) => quote! { #type_def },
             Self::Enum(type_def) => quote! { #type_def },
+            Self::ErrorCode(type_def) => quote! { #type_def },
         });
     }
 }
@@ -163,6 +172,24 @@ fn loaded_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
                 Mutable::new(#expr.into_iter().map(|element| #inner).collect())
             }
         }
+        // Option<T> special case, only the inner value needs to be loaded
+        TyExpr::Generic { name, params, .. } if name == &["Option"] => {
+            let inner = loaded_field(quote! { value }, &params[0]);
+
+            match inner.to_string() == "value" {
+                true => expr,
+                false => quote! { #expr.map(|value| #inner) },
+            }
+        }
+        // BTreeMap<K, V> special case, same as Vec but for both keys and values
+        TyExpr::Generic { name, params, .. } if name == &["BTreeMap"] => {
+            let key = loaded_field(quote! { key }, &params[0]);
+            let value = loaded_field(quote! { value }, &params[1]);
+
+            quote! {
+                Mutable::new(#expr.into_iter().map(|(key, value)| (#key, #value)).collect())
+            }
+        }
         TyExpr::Generic {
             is_loadable,
             mutability,
@@ -184,10 +211,11 @@ fn loaded_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
             quote! { Mutable::new(#expr.map(|element| #inner)) }
         }
         TyExpr::Tuple(tuple) => {
-            let inner = tuple
-                .iter()
-                .enumerate()
-                .map(|(index, ty)| loaded_field(quote! { tuple.#index }, ty));
+            let inner = tuple.iter().enumerate().map(|(index, ty)| {
+                let index = PM2Literal::usize_unsuffixed(index);
+
+                loaded_field(quote! { tuple.#index }, ty)
+            });
 
             quote! {
                 {
@@ -196,11 +224,12 @@ fn loaded_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
                 }
             }
         }
+        TyExpr::Bounded { inner, .. } => loaded_field(expr, inner),
         _ => todo!(),
     }
 }
 
-fn stored_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
+pub fn stored_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
     let ty_expr = StoredTyExpr(ty);
 
     match ty {
@@ -211,6 +240,22 @@ fn stored_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
                 #expr.borrow().clone().into_iter().map(|element| #inner).collect()
             }
         }
+        TyExpr::Generic { name, params, .. } if name == &["Option"] => {
+            let inner = stored_field(quote! { value }, &params[0]);
+
+            match inner.to_string() == "value" {
+                true => expr,
+                false => quote! { #expr.map(|value| #inner) },
+            }
+        }
+        TyExpr::Generic { name, params, .. } if name == &["BTreeMap"] => {
+            let key = stored_field(quote! { key }, &params[0]);
+            let value = stored_field(quote! { value }, &params[1]);
+
+            quote! {
+                #expr.borrow().clone().into_iter().map(|(key, value)| (#key, #value)).collect()
+            }
+        }
         TyExpr::Generic {
             is_loadable,
             mutability,
@@ -234,10 +279,11 @@ fn stored_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
             }
         }
         TyExpr::Tuple(tuple) => {
-            let inner = tuple
-                .iter()
-                .enumerate()
-                .map(|(index, ty)| stored_field(quote! { tuple.#index }, ty));
+            let inner = tuple.iter().enumerate().map(|(index, ty)| {
+                let index = PM2Literal::usize_unsuffixed(index);
+
+                stored_field(quote! { tuple.#index }, ty)
+            });
 
             quote! {
                 {
@@ -246,10 +292,98 @@ fn stored_field(expr: TokenStream, ty: &TyExpr) -> TokenStream {
                 }
             }
         }
+        TyExpr::Bounded { inner, .. } => stored_field(expr, inner),
         _ => todo!(),
     }
 }
 
+/// Get the name of the handler param that holds the seeds a PDA account signs with.
+pub fn signer_seeds_ident(name: &str) -> Ident {
+    format_ident!("__{}_seeds__", name)
+}
+
+/// Get the space that a stored value of this type takes up once it's serialized. Collections
+/// without a max length fall back to their in-memory size, just like they always have.
+fn space(ty: &TyExpr) -> TokenStream {
+    match ty {
+        TyExpr::Bounded { inner, capacity } => {
+            let capacity = PM2Literal::usize_unsuffixed(*capacity);
+
+            match &**inner {
+                TyExpr::Generic { name, params, .. } => match name[0].as_str() {
+                    "String" => quote! { (4 + #capacity) },
+                    "Vec" => {
+                        let element = space(&params[0]);
+
+                        quote! { (4 + #capacity * #element) }
+                    }
+                    "BTreeMap" => {
+                        let key = space(&params[0]);
+                        let value = space(&params[1]);
+
+                        quote! { (4 + #capacity * (#key + #value)) }
+                    }
+                    _ => panic!(),
+                },
+                _ => panic!(),
+            }
+        }
+        TyExpr::Generic {
+            name,
+            params,
+            is_loadable,
+            ..
+        } => match name[0].as_str() {
+            "Option" if name.len() == 1 => {
+                let inner = space(&params[0]);
+
+                quote! { (1 + #inner) }
+            }
+            "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128"
+            | "f64" | "bool" | "Pubkey" | "String" | "Vec" | "BTreeMap"
+                if name.len() == 1 && !is_loadable =>
+            {
+                let ty = StoredTyExpr(ty);
+
+                quote! { std::mem::size_of::<#ty>() }
+            }
+            // Structs and enums know their own size
+            _ => {
+                let path = StaticPath(name);
+
+                quote! { #path::__SIZE__ }
+            }
+        },
+        TyExpr::Array { element, size } => {
+            let element = space(element);
+            let size = StoredTyExpr(size);
+
+            quote! { (#size * #element) }
+        }
+        TyExpr::Tuple(tuple) => {
+            let tuple = total_space(tuple.iter());
+
+            quote! { (#tuple) }
+        }
+        _ => {
+            let ty = StoredTyExpr(ty);
+
+            quote! { std::mem::size_of::<#ty>() }
+        }
+    }
+}
+
+/// Add up the space of several types.
+fn total_space<'a>(tys: impl Iterator<Item = &'a TyExpr>) -> TokenStream {
+    let spaces = tys.map(space).collect::<Vec<_>>();
+
+    if spaces.is_empty() {
+        return quote! { 0 };
+    }
+
+    quote! { #(#spaces)+* }
+}
+
 impl ToTokens for Struct {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let Self {
@@ -288,10 +422,10 @@ impl ToTokens for Struct {
             instance_methods.push(quote! { #func });
 
             static_methods.push(quote! {
-                pub fn __new__(#(#ext_params),*) -> Mutable<Self> {
+                pub fn __new__(#(#ext_params),*) -> Result<Mutable<Self>> {
                     let obj = Mutable::new(#name::default());
-                    obj.__init__(#(#ext_param_names),*);
-                    return obj;
+                    obj.__init__(#(#ext_param_names),*)?;
+                    return Ok(obj);
                 }
             });
         } else if *is_dataclass {
@@ -309,9 +443,9 @@ impl ToTokens for Struct {
             });
 
             static_methods.push(quote! {
-                pub fn __new__(#(#ctor_params), *) -> Mutable<Self> {
+                pub fn __new__(#(#ctor_params), *) -> Result<Mutable<Self>> {
                     let obj = #name { #(#ctor_param_names),* };
-                    return Mutable::new(obj);
+                    return Ok(Mutable::new(obj));
                 }
             });
         }
@@ -334,26 +468,37 @@ impl ToTokens for Struct {
         // `impl Class` block.
 
         let event_emit_fn = if *is_event {
-            let fs = fields.iter().map(|(name, ty, original_ty)| {
-                let name = ident(name);
+            let fs = fields
+                .iter()
+                .map(|(name, ty, original_ty)| {
+                    let name = ident(name);
 
-                let needs_clone = !original_ty.is_copy();
-                let field = if needs_clone {
-                    quote! { e.#name.clone() }
-                } else {
-                    quote! { e.#name }
-                };
+                    let needs_clone = !original_ty.is_copy();
+                    let field = if needs_clone {
+                        quote! { e.#name.clone() }
+                    } else {
+                        quote! { e.#name }
+                    };
 
-                let field = stored_field(quote! { #field }, ty);
+                    let field = stored_field(quote! { #field }, ty);
 
-                quote! { #name: #field }
-            });
+                    quote! { #name: #field }
+                })
+                .collect::<Vec<_>>();
 
+            // Emitting with a CPI uses Anchor's event-cpi feature, which is only enabled for
+            // programs that need it
             Some(quote! {
                 fn __emit__(&self) {
                     let e = self.borrow();
                     emit!(#stored_name { #(#fs),* })
                 }
+
+                #[cfg(feature = "event-cpi")]
+                fn __emit_cpi__(&self, event_authority: &EventAuthority) -> Result<()> {
+                    let e = self.borrow();
+                    event_authority.emit(#stored_name { #(#fs),* })
+                }
             })
         } else {
             None
@@ -376,11 +521,23 @@ impl ToTokens for Struct {
         };
 
         let stored_macros = if *is_event {
-            quote! { #[event] }
+            quote! {
+                #[event]
+                #[derive(Clone, Debug)]
+            }
         } else {
             quote! { #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)] }
         };
 
+        // Structs (events included) can be stored in accounts, so they need to know how much space
+        // they take up
+        let space = total_space(fields.iter().map(|(_, ty, _)| ty));
+        let space_impl = quote! {
+            impl #stored_name {
+                pub const __SIZE__: usize = #space;
+            }
+        };
+
         let macros = quote! { #[derive(Clone, Debug, Default)] };
 
         let stored_fields = fields.iter().map(|(name, ty, _)| {
@@ -420,6 +577,8 @@ impl ToTokens for Struct {
             #stored_macros
             pub struct #stored_name { #(#stored_fields),* }
 
+            #space_impl
+
             #macros
             pub struct #name { #(#fields),* }
 
@@ -448,11 +607,17 @@ impl ToTokens for Account {
             name,
             fields,
             methods,
+            is_zero_copy,
         } = self;
 
         let account_name = ident(name);
         let loaded_name = ident(&format!("Loaded{}", name));
 
+        if *is_zero_copy {
+            tokens.extend(zero_copy_account(self));
+            return;
+        }
+
         let account_fields = fields.iter().map(|(name, ty, _)| {
             let name = ident(name);
             let ty = StoredTyExpr(ty);
@@ -526,11 +691,18 @@ impl ToTokens for Account {
             None
         };
 
+        // Space needed to init this account (including the discriminator)
+        let space = fields.iter().map(|(_, ty, _)| space(ty));
+
         tokens.extend(quote! {
             #[account]
             #[derive(Debug)]
             pub struct #account_name { #(#account_fields),* }
 
+            impl #account_name {
+                pub const __SPACE__: usize = 8 #(+ #space)*;
+            }
+
             impl<'info, 'entrypoint> #account_name {
                 pub fn load(account: &'entrypoint mut Box<Account<'info, Self>>, programs_map: &'entrypoint ProgramsMap<'info>) -> Mutable<#loaded_name<'info, 'entrypoint>> {
                     #(#loads)*
@@ -561,30 +733,270 @@ impl ToTokens for Account {
     }
 }
 
+/// Generate a zero-copy account. Instead of copying its fields, the loaded account holds on to the
+/// account data, and derefs to it so that fields are used in place.
+fn zero_copy_account(account: &Account) -> TokenStream {
+    let Account {
+        name,
+        fields,
+        methods,
+        ..
+    } = account;
+
+    let account_name = ident(name);
+    let loaded_name = ident(&format!("Loaded{}", name));
+
+    let account_fields = fields.iter().map(|(name, ty, _)| {
+        let name = ident(name);
+        let ty = StoredTyExpr(ty);
+
+        quote! { pub #name: #ty }
+    });
+
+    let mut instance_methods = vec![];
+    let mut static_methods = vec![];
+
+    for (method_type, func) in methods.iter() {
+        match method_type {
+            MethodType::Instance => {
+                let method = InstanceMethod(func);
+
+                instance_methods.push(quote! { #method });
+            }
+            MethodType::Static => {
+                static_methods.push(quote! { #func });
+            }
+        }
+    }
+
+    let instance_impl = if !instance_methods.is_empty() {
+        Some(quote! { impl Mutable<#loaded_name<'_, '_>> { #(#instance_methods)* } })
+    } else {
+        None
+    };
+
+    quote! {
+        #[account(zero_copy)]
+        #[derive(Debug)]
+        pub struct #account_name { #(#account_fields),* }
+
+        impl #account_name {
+            pub const __SPACE__: usize = std::mem::size_of::<Self>() + 8;
+        }
+
+        impl<'info, 'entrypoint> #account_name {
+            pub fn load(account: &'entrypoint mut AccountLoader<'info, Self>, programs_map: &'entrypoint ProgramsMap<'info>) -> Mutable<#loaded_name<'info, 'entrypoint>> {
+                let account: &'entrypoint AccountLoader<'info, Self> = account;
+                let __data__ = account.load_mut().unwrap();
+
+                Mutable::new(#loaded_name {
+                    __account__: account,
+                    __programs__: programs_map,
+                    __data__
+                })
+            }
+
+            // New accounts don't have a discriminator yet, so they're loaded differently
+            pub fn load_init(account: &'entrypoint mut AccountLoader<'info, Self>, programs_map: &'entrypoint ProgramsMap<'info>) -> Mutable<#loaded_name<'info, 'entrypoint>> {
+                let account: &'entrypoint AccountLoader<'info, Self> = account;
+                let __data__ = account.load_init().unwrap();
+
+                Mutable::new(#loaded_name {
+                    __account__: account,
+                    __programs__: programs_map,
+                    __data__
+                })
+            }
+
+            #(#static_methods)*
+        }
+
+        #[derive(Debug)]
+        pub struct #loaded_name<'info, 'entrypoint> {
+            pub __account__: &'entrypoint AccountLoader<'info, #account_name>,
+            pub __programs__: &'entrypoint ProgramsMap<'info>,
+            pub __data__: std::cell::RefMut<'entrypoint, #account_name>,
+        }
+
+        impl<'info, 'entrypoint> std::ops::Deref for #loaded_name<'info, 'entrypoint> {
+            type Target = #account_name;
+
+            fn deref(&self) -> &Self::Target {
+                &self.__data__
+            }
+        }
+
+        impl<'info, 'entrypoint> std::ops::DerefMut for #loaded_name<'info, 'entrypoint> {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.__data__
+            }
+        }
+
+        #instance_impl
+    }
+}
+
 impl ToTokens for Enum {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let Self { name, variants } = self;
         let name = ident(name);
-        let variants_tokens = variants.iter().map(|(name, _)| {
+        let variants_tokens = variants.iter().map(|(name, variant)| {
             let name = ident(name);
 
-            quote! { #name }
+            match variant {
+                EnumVariant::Unit => quote! { #name },
+                EnumVariant::Tuple(fields) => {
+                    let fields = fields.iter().map(StoredTyExpr);
+
+                    quote! { #name(#(#fields),*) }
+                }
+                EnumVariant::Struct(fields) => {
+                    let fields = fields.iter().map(|(name, ty_expr)| {
+                        let name = ident(name);
+                        let ty_expr = StoredTyExpr(ty_expr);
+
+                        quote! { #name: #ty_expr }
+                    });
+
+                    quote! { #name { #(#fields),* } }
+                }
+            }
         });
 
-        let (first_variant, _) = &variants[0];
+        // Accessors for the data of each variant, used when reading `value.Variant` in Seahorse
+        let accessors = variants.iter().filter_map(|(variant, data)| {
+            let (pattern, tys, values) = match data {
+                EnumVariant::Unit => {
+                    return None;
+                }
+                EnumVariant::Tuple(fields) => {
+                    let values = (0..fields.len())
+                        .map(|i| format_ident!("field_{}", i))
+                        .collect::<Vec<_>>();
+
+                    (
+                        quote! { (#(#values),*) },
+                        fields.iter().collect::<Vec<_>>(),
+                        values,
+                    )
+                }
+                EnumVariant::Struct(fields) => {
+                    let values = fields
+                        .iter()
+                        .map(|(name, _)| ident(name))
+                        .collect::<Vec<_>>();
+
+                    (
+                        quote! { { #(#values),* } },
+                        fields.iter().map(|(_, ty_expr)| ty_expr).collect(),
+                        values,
+                    )
+                }
+            };
+
+            let method = ident(&variant.to_snake_case());
+            let msg = format!("{} is not {}.{}", self.name, self.name, variant);
+            let variant = ident(variant);
+            let tys = tys.into_iter().map(StoredTyExpr);
+
+            let (returns, value) = if values.len() == 1 {
+                (quote! { #(#tys)* }, quote! { #(#values.clone())* })
+            } else {
+                (quote! { (#(#tys),*) }, quote! { (#(#values.clone()),*) })
+            };
+
+            Some(quote! {
+                pub fn #method(&self) -> #returns {
+                    match self {
+                        Self::#variant #pattern => #value,
+                        _ => panic!(#msg),
+                    }
+                }
+            })
+        }).collect::<Vec<_>>();
+
+        let accessors = if !accessors.is_empty() {
+            Some(quote! {
+                impl #name {
+                    #(#accessors)*
+                }
+            })
+        } else {
+            None
+        };
+
+        // Enums that carry data might not be `Copy`
+        let is_copy = variants
+            .iter()
+            .all(|(_, variant)| matches!(variant, EnumVariant::Unit));
+        let copy = if is_copy { Some(quote! { Copy }) } else { None };
+
+        // An enum takes up one byte for its variant, plus enough space for its largest variant
+        let variant_spaces = variants.iter().map(|(_, variant)| match variant {
+            EnumVariant::Unit => quote! { 0 },
+            EnumVariant::Tuple(fields) => total_space(fields.iter()),
+            EnumVariant::Struct(fields) => total_space(fields.iter().map(|(_, ty)| ty)),
+        });
+
+        let (first_variant, first_data) = &variants[0];
         let first_variant = ident(first_variant);
+        let default = match first_data {
+            EnumVariant::Unit => quote! { #name::#first_variant },
+            EnumVariant::Tuple(fields) => {
+                let fields = fields.iter().map(|_| quote! { Default::default() });
+
+                quote! { #name::#first_variant(#(#fields),*) }
+            }
+            EnumVariant::Struct(fields) => {
+                let fields = fields.iter().map(|(name, _)| {
+                    let name = ident(name);
+
+                    quote! { #name: Default::default() }
+                });
+
+                quote! { #name::#first_variant { #(#fields),* } }
+            }
+        };
 
         tokens.extend(quote! {
-            #[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize, Copy)]
+            #[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize, #copy)]
             pub enum #name {
                 #(#variants_tokens),*
             }
 
             impl Default for #name {
                 fn default() -> Self {
-                    #name::#first_variant
+                    #default
                 }
             }
+
+            impl #name {
+                pub const __SIZE__: usize = 1 + max_space(&[#(#variant_spaces),*]);
+            }
+
+            #accessors
+        });
+    }
+}
+
+impl ToTokens for ErrorCode {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let Self { name, variants } = self;
+        let name = ident(name);
+        let variants = variants.iter().map(|(name, msg)| {
+            let name = ident(name);
+
+            quote! {
+                #[msg(#msg)]
+                #name
+            }
+        });
+
+        tokens.extend(quote! {
+            #[error_code]
+            pub enum #name {
+                #(#variants),*
+            }
         });
     }
 }
@@ -636,6 +1048,11 @@ impl<'a> ToTokens for LoadedTyExpr<'a> {
 
                 quote! { (#(#tuple),*) }
             }
+            TyExpr::Bounded { inner, .. } => {
+                let inner = LoadedTyExpr(inner.as_ref());
+
+                quote! { #inner }
+            }
             TyExpr::Account(path) => {
                 let mut path = path.clone();
                 *path.last_mut().unwrap() = format!("Loaded{}", path.last().unwrap());
@@ -655,7 +1072,7 @@ impl<'a> ToTokens for LoadedTyExpr<'a> {
 }
 
 /// Newtype to display the "stored" (used in on-chain interface) type of a type expression.
-struct StoredTyExpr<'a>(&'a TyExpr);
+pub struct StoredTyExpr<'a>(pub &'a TyExpr);
 impl<'a> ToTokens for StoredTyExpr<'a> {
     // Mutability is irrelevant in this context
     fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -684,6 +1101,11 @@ impl<'a> ToTokens for StoredTyExpr<'a> {
 
                 quote! { (#(#tuple),*) }
             }
+            TyExpr::Bounded { inner, .. } => {
+                let inner = StoredTyExpr(inner.as_ref());
+
+                quote! { #inner }
+            }
             TyExpr::Account(path) => {
                 let path = StaticPath(&path);
 
@@ -717,7 +1139,11 @@ impl ToTokens for Function {
             ident(name)
         };
 
-        let info_lifetime = if *info_lifetime {
+        let emit_cpi = ix_context
+            .as_ref()
+            .is_some_and(|ix_context| ix_context.emit_cpi);
+
+        let info_lifetime = if *info_lifetime || emit_cpi {
             Some(quote! { <'info> })
         } else {
             None
@@ -730,10 +1156,23 @@ impl ToTokens for Function {
             quote! { mut #name: #ty }
         });
 
+        // Instructions also get the seeds of any PDAs that sign CPIs, and the event authority if
+        // they emit events with CPIs
+        let signer_seeds = ix_context
+            .iter()
+            .flat_map(|ix_context| {
+                ix_context.signer_seeds.iter().map(|name| {
+                    let name = signer_seeds_ident(name);
+
+                    quote! { #name: Vec<Vec<u8>> }
+                })
+            })
+            .chain(emit_cpi.then(|| quote! { __event_authority__: EventAuthority<'info> }));
+
         let returns = LoadedTyExpr(returns);
 
         tokens.extend(quote! {
-            pub fn #name #info_lifetime(#(#params),*) -> #returns #body
+            pub fn #name #info_lifetime(#(#params,)* #(#signer_seeds),*) -> Result<#returns> #body
         });
     }
 }
@@ -776,7 +1215,7 @@ impl<'a> ToTokens for InstanceMethod<'a> {
         let returns = LoadedTyExpr(returns);
 
         tokens.extend(quote! {
-            pub fn #name #info_lifetime(#(#params),*) -> #returns #body
+            pub fn #name #info_lifetime(#(#params),*) -> Result<#returns> #body
         });
     }
 }
@@ -788,10 +1227,19 @@ impl ToTokens for InstructionContext {
             params,
             accounts,
             inferred_accounts,
+            emit_cpi,
+            ..
         } = self;
 
         let name = ident(name);
 
+        // Anchor adds the event authority and the program itself to the accounts
+        let event_cpi = if *emit_cpi {
+            Some(quote! { #[event_cpi] })
+        } else {
+            None
+        };
+
         let params = match params.len() {
             0 => quote! {},
             _ => {
@@ -843,6 +1291,7 @@ impl ToTokens for InstructionContext {
                 ));
 
         tokens.extend(quote! {
+            #event_cpi
             #[derive(Accounts)]
             #params
             pub struct #name<'info> { #(#accounts),* }
@@ -856,19 +1305,36 @@ impl ToTokens for AccountTyExpr {
             Self::Empty(ty_expr) => {
                 quote! { #ty_expr }
             }
+            Self::Optional(ty_expr) => {
+                quote! { Option<#ty_expr> }
+            }
             Self::Defined(ty_expr) => {
                 let ty_expr = StaticPath(ty_expr);
 
                 quote! { Box<Account<'info, #ty_expr>> }
             }
+            Self::ZeroCopy(ty_expr) => {
+                let ty_expr = StaticPath(ty_expr);
+
+                quote! { AccountLoader<'info, #ty_expr> }
+            }
             Self::Signer => quote! { Signer<'info> },
             Self::TokenMint => quote! { Box<Account<'info, Mint>> },
             Self::TokenAccount => quote! { Box<Account<'info, TokenAccount>> },
+            Self::Token2022Mint => quote! {
+                Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>
+            },
+            Self::Token2022Account => quote! {
+                Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>
+            },
             Self::UncheckedAccount => quote! {
                 UncheckedAccount<'info>
             },
             Self::SystemProgram => quote! { Program<'info, System> },
             Self::TokenProgram => quote! { Program<'info, Token> },
+            Self::Token2022Program => quote! {
+                Interface<'info, anchor_spl::token_interface::TokenInterface>
+            },
             Self::AssociatedTokenProgram => quote! { Program<'info, AssociatedToken> },
             Self::RentSysvar => quote! { Sysvar<'info, Rent> },
             Self::ClockSysvar => quote! { Sysvar<'info, Clock> },
@@ -894,6 +1360,8 @@ impl<'a> ToTokens for AccountAnnotationWithTyExpr<'a> {
                 mint_authority,
                 space,
                 padding,
+                close,
+                constraints,
             },
             ty_expr,
         ) = self;
@@ -906,14 +1374,14 @@ impl<'a> ToTokens for AccountAnnotationWithTyExpr<'a> {
         }
         if *init {
             let ty_expr = match1!(ty_expr, AccountTyExpr::Empty(ty_expr) => ty_expr);
-            if let AccountTyExpr::Defined(name) = &**ty_expr {
+            if let AccountTyExpr::Defined(name) | AccountTyExpr::ZeroCopy(name) = &**ty_expr {
                 let ty_expr = StaticPath(name);
 
                 let space = match (space, padding) {
-                    (None, None) => quote! { std::mem::size_of::<#ty_expr>() + 8 },
+                    (None, None) => quote! { #ty_expr::__SPACE__ },
                     (Some(s), None) => quote! { #s as usize },
                     (None, Some(p)) => {
-                        quote! { std::mem::size_of::<#ty_expr>() + 8 + (#p as usize) }
+                        quote! { #ty_expr::__SPACE__ + (#p as usize) }
                     }
                     (Some(_), Some(_)) => panic!(), // we protect against this in prelude.rs
                 };
@@ -954,10 +1422,31 @@ impl<'a> ToTokens for AccountAnnotationWithTyExpr<'a> {
                 quote! { associated_token::authority = #authority }
             }
         }));
+        // Token-2022 accounts have to name the program that owns them
+        if let (true, AccountTyExpr::Empty(ty_expr)) = (*init, ty_expr) {
+            params.push(match &**ty_expr {
+                AccountTyExpr::Token2022Mint => {
+                    Some(quote! { mint::token_program = token_2022_program })
+                }
+                AccountTyExpr::Token2022Account if *is_associated => {
+                    Some(quote! { associated_token::token_program = token_2022_program })
+                }
+                AccountTyExpr::Token2022Account => {
+                    Some(quote! { token::token_program = token_2022_program })
+                }
+                _ => None,
+            });
+        }
+
+        params.push(close.as_ref().map(|to| quote! { close = #to }));
+        params.extend(constraints.iter().map(|constraint| Some(quote! { #constraint })));
 
         let params = params.into_iter().filter_map(|param| param);
 
-        let unchecked = if let &&AccountTyExpr::UncheckedAccount = ty_expr {
+        let unchecked = if let AccountTyExpr::UncheckedAccount = match ty_expr {
+            AccountTyExpr::Optional(ty_expr) => &**ty_expr,
+            ty_expr => ty_expr,
+        } {
             Some(quote! {
                 /// CHECK: This account is unchecked.
             })
@@ -973,6 +1462,40 @@ impl<'a> ToTokens for AccountAnnotationWithTyExpr<'a> {
     }
 }
 
+impl ToTokens for AccountConstraint {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let (constraint, error) = match self {
+            Self::HasOne { field, error } => {
+                let field = ident(field);
+
+                (quote! { has_one = #field }, error)
+            }
+            Self::Constraint { cond, error } => (quote! { constraint = #cond }, error),
+            Self::Address { address, error } => (quote! { address = #address }, error),
+            Self::Realloc { space, payer, zero } => {
+                let zero = match zero {
+                    Some(zero) => quote! { #zero },
+                    None => quote! { false },
+                };
+
+                tokens.extend(quote! {
+                    realloc = #space as usize, realloc::payer = #payer, realloc::zero = #zero
+                });
+                return;
+            }
+            Self::Seeds { seeds, bump } => {
+                let bump = bump.as_ref().map(|bump| quote! { = #bump });
+
+                tokens.extend(quote! { seeds = [#(#seeds),*], bump #bump });
+                return;
+            }
+        };
+        let error = error.as_ref().map(|error| quote! { @ #error });
+
+        tokens.extend(quote! { #constraint #error });
+    }
+}
+
 impl ToTokens for Block {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
@@ -1060,6 +1583,11 @@ impl ToTokens for Statement {
                     assign!(#receiver, #value);
                 }
             }
+            // Blocks don't need a semicolon to be used as statements
+            Self::Expression(TypedExpression {
+                obj: ExpressionObj::Block(block),
+                ..
+            }) => quote! { #block },
             Self::Expression(expression) => {
                 let expression = Grouped(expression);
 
@@ -1068,9 +1596,19 @@ impl ToTokens for Statement {
             Self::Return(value) => {
                 let value = value.as_ref().map(|value| Grouped(value));
 
-                quote! { return #value; }
+                match value {
+                    Some(value) => quote! { return Ok(#value); },
+                    None => quote! { return Ok(()); },
+                }
             }
-            Self::Break => quote! { break; },
+            Self::Break { label } => match label {
+                Some(label) => {
+                    let label = loop_label(label);
+
+                    quote! { break #label; }
+                }
+                None => quote! { break; },
+            },
             Self::Continue => quote! { continue; },
             Self::Noop => quote! {},
             Self::AnchorRequire { cond, msg } => {
@@ -1082,6 +1620,18 @@ impl ToTokens for Statement {
                     }
                 }
             }
+            Self::Require { cond, error } => {
+                let cond = Grouped(cond);
+
+                quote! {
+                    require!(#cond, #error);
+                }
+            }
+            Self::Raise(error) => {
+                quote! {
+                    return Err(error!(#error));
+                }
+            }
             Self::If { cond, body, orelse } => {
                 let cond = Grouped(cond);
 
@@ -1091,24 +1641,132 @@ impl ToTokens for Statement {
                     None => quote! { if #cond #body },
                 }
             }
+            Self::IfSome {
+                name,
+                value,
+                body,
+                orelse,
+                write_back,
+            } => {
+                let name = ident(name);
+
+                // The unwrapped variable shadows the optional, so assignments to it are written
+                // back through a reference that's taken beforehand
+                let (reference, scrutinee) = match write_back {
+                    Some(optional) => {
+                        let optional = ident(optional);
+
+                        (
+                            quote! { let #optional = &mut #value; },
+                            quote! { #optional.clone() },
+                        )
+                    }
+                    None => (quote! {}, quote! { #value.clone() }),
+                };
+                let if_some = match orelse {
+                    Some(orelse) => quote! {
+                        if let Some(mut #name) = #scrutinee #body else #orelse
+                    },
+                    None => quote! { if let Some(mut #name) = #scrutinee #body },
+                };
+
+                match write_back {
+                    Some(_) => quote! {
+                        {
+                            #reference
+                            #if_some
+                        }
+                    },
+                    None => if_some,
+                }
+            }
             Self::While { cond, body } => {
                 let cond = Grouped(cond);
 
                 quote! { while #cond #body }
             }
-            Self::Loop { label, body } => {
-                let label = match label {
-                    Some(label) => Some(ident(label)),
-                    None => None,
-                };
+            Self::Loop { label, body } => match label {
+                Some(label) => {
+                    let label = loop_label(label);
 
-                quote! { #label loop #body }
-            }
+                    quote! { #label: loop #body }
+                }
+                None => quote! { loop #body },
+            },
             Self::For { target, iter, body } => {
                 let iter = Grouped(iter);
 
                 quote! { for #target in #iter #body }
             }
+            // The subject gets stored before matching it, otherwise any `borrow()`s it makes would
+            // last until the end of the match
+            Self::Match {
+                subject,
+                is_str,
+                arms,
+            } => {
+                let subject = match subject.ty.is_copy() {
+                    true => quote! { #subject },
+                    false => quote! { #subject.clone() },
+                };
+                let value = match is_str {
+                    true => quote! { __match_value__.as_str() },
+                    false => quote! { __match_value__ },
+                };
+
+                quote! {{
+                    let __match_value__ = #subject;
+
+                    match #value {
+                        #(#arms)*
+                    }
+                }}
+            }
+        });
+    }
+}
+
+impl ToTokens for MatchArm {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            pattern,
+            guard,
+            body,
+        } = self;
+
+        let guard = guard.as_ref().map(|guard| {
+            let guard = Grouped(guard);
+
+            quote! { if #guard }
+        });
+
+        tokens.extend(quote! { #pattern #guard => #body });
+    }
+}
+
+impl ToTokens for Pattern {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Wildcard => quote! { _ },
+            Self::Capture(name) => {
+                let name = ident(name);
+
+                quote! { mut #name }
+            }
+            Self::Literal(literal) => quote! { #literal },
+            Self::Value(value) => quote! { #value },
+            Self::TupleVariant(variant, fields) => quote! { #variant(#(#fields),*) },
+            Self::StructVariant(variant, fields) => {
+                let fields = fields.iter().map(|(name, pattern)| {
+                    let name = ident(name);
+
+                    quote! { #name: #pattern }
+                });
+
+                quote! { #variant { #(#fields,)* .. } }
+            }
+            Self::Tuple(elements) => quote! { (#(#elements,)*) },
+            Self::Or(options) => quote! { #(#options)|* },
         });
     }
 }
@@ -1142,7 +1800,7 @@ impl ToTokens for TypedExpression {
 }
 
 /// Newtype for an expression that doesn't need extra parentheses to group it.
-struct Grouped<'a>(&'a TypedExpression);
+pub(crate) struct Grouped<'a>(pub &'a TypedExpression);
 impl<'a> ToTokens for Grouped<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         tokens.extend(match &self.0.obj {
@@ -1184,10 +1842,34 @@ impl ToTokens for ExpressionObj {
 
                 quote! { #value :: #name }
             }
-            Self::Call { function, args } => {
-                let args = args.iter().map(|arg| Grouped(arg));
+            Self::Call { function, args } => match function.closure() {
+                // {
+                //     let mut param1 = arg1;
+                //     ...
+                //     body
+                // }
+                Some((params, body)) => {
+                    let params = params.iter().map(ident);
+                    let args = args.iter().map(Grouped);
+                    let body = Grouped(body);
+
+                    quote! {{
+                        #(let mut #params = #args;)*
+                        #body
+                    }}
+                }
+                None => {
+                    let args = args.iter().map(Grouped);
+
+                    quote! { #function(#(#args),*) }
+                }
+            },
+            Self::Closure { params, body } => {
+                let params = params.iter().map(ident);
+
+                let body = Grouped(body);
 
-                quote! { #function(#(#args),*) }
+                quote! { |#(mut #params),*| #body }
             }
             Self::Ternary { cond, body, orelse } => {
                 let cond = Grouped(&**cond);
@@ -1235,6 +1917,7 @@ impl ToTokens for ExpressionObj {
             }
             Self::BorrowMut(value) => quote! { #value . borrow_mut() },
             Self::BorrowImmut(value) => quote! { #value . borrow() },
+            Self::AnchorAccount(value) => quote! { #value . borrow() . __account__ },
             Self::Mutable(value) => {
                 let value = Grouped(&**value);
 
@@ -1299,6 +1982,15 @@ impl ToTokens for UnaryOperator {
     }
 }
 
+/// Check whether some tokens mention a name.
+fn mentions(tokens: &TokenStream, name: &str) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == name,
+        TokenTree::Group(group) => mentions(&group.stream(), name),
+        _ => false,
+    })
+}
+
 fn make_lib(
     origin: &Artifact,
     path: &Vec<String>,
@@ -1327,6 +2019,7 @@ fn make_lib(
              name,
              ix_context,
              params,
+             returns,
              ..
          }| {
             let ix_context = match ix_context {
@@ -1358,42 +2051,77 @@ fn make_lib(
                 |(name, ContextAccount { account_ty, ty, .. })| {
                     let name = ident(name);
 
-                    // Deconstruct `Empty` accounts
+                    // Deconstruct `Empty` and optional accounts
                     let (is_empty, account_ty) = match account_ty {
                         AccountTyExpr::Empty(empty) => (true, &**empty),
                         ty => (false, ty),
                     };
+                    let (is_optional, account_ty) = match account_ty {
+                        AccountTyExpr::Optional(optional) => (true, &**optional),
+                        ty => (false, ty),
+                    };
+
+                    // Optional accounts get loaded by mapping over the `Option`
+                    let (account, account_mut) = if is_optional {
+                        (quote! { account }, quote! { account })
+                    } else {
+                        (quote! { &ctx.accounts.#name }, quote! { &mut ctx.accounts.#name })
+                    };
 
                     let loaded = match account_ty {
                         AccountTyExpr::Defined(path) => {
                             let path = StaticPath(path);
 
-                            quote! { #path::load(&mut ctx.accounts.#name, &programs_map) }
+                            quote! { #path::load(#account_mut, &programs_map) }
+                        }
+                        AccountTyExpr::ZeroCopy(path) => {
+                            let path = StaticPath(path);
+
+                            if is_empty {
+                                quote! { #path::load_init(#account_mut, &programs_map) }
+                            } else {
+                                quote! { #path::load(#account_mut, &programs_map) }
+                            }
                         }
                         AccountTyExpr::Signer => quote! {
                             SeahorseSigner {
-                                account: &ctx.accounts.#name,
+                                account: #account,
                                 programs: &programs_map
                             }
                         },
                         AccountTyExpr::TokenMint | AccountTyExpr::TokenAccount => quote! {
                             SeahorseAccount {
-                                account: &ctx.accounts.#name,
+                                account: #account,
+                                programs: &programs_map
+                            }
+                        },
+                        AccountTyExpr::Token2022Mint | AccountTyExpr::Token2022Account => quote! {
+                            WithPrograms {
+                                account: #account,
                                 programs: &programs_map
                             }
                         },
                         AccountTyExpr::UncheckedAccount => quote! {
-                            &ctx.accounts.#name.clone()
+                            #account.clone()
                         },
                         AccountTyExpr::ClockSysvar => quote! {
-                            &ctx.accounts.#name.clone()
+                            #account.clone()
                         },
                         _ => {
                             return None;
                         }
                     };
 
-                    Some(if is_empty {
+                    Some(if is_optional {
+                        let as_ref = match account_ty {
+                            AccountTyExpr::Defined(..) => quote! { as_mut },
+                            _ => quote! { as_ref },
+                        };
+
+                        quote! {
+                            let #name = ctx.accounts.#name.#as_ref().map(|account| #loaded);
+                        }
+                    } else if is_empty {
                         quote! {
                             let #name = Empty {
                                 account: #loaded,
@@ -1408,6 +2136,86 @@ fn make_lib(
                 },
             );
 
+            // Remaining accounts get checked the same way as the instruction's accounts, then loaded
+            // into a list
+            let load_remaining_accounts =
+                ix_context
+                    .remaining_accounts
+                    .as_ref()
+                    .map(|(name, account_ty)| {
+                        let name = ident(name);
+
+                        let checked = match account_ty {
+                            AccountTyExpr::Signer => quote! { Signer::try_from(account) },
+                            AccountTyExpr::UncheckedAccount => {
+                                quote! { Ok(UncheckedAccount::try_from(account)) }
+                            }
+                            AccountTyExpr::Token2022Mint | AccountTyExpr::Token2022Account => {
+                                quote! { InterfaceAccount::try_from(account).map(Box::new) }
+                            }
+                            _ => quote! { Account::try_from(account).map(Box::new) },
+                        };
+
+                        let loaded = match account_ty {
+                            AccountTyExpr::Defined(path) => {
+                                let path = StaticPath(path);
+
+                                quote! { iter_mut().map(|account| #path::load(account, &programs_map)) }
+                            }
+                            AccountTyExpr::UncheckedAccount => {
+                                quote! { iter().map(|account| account.clone()) }
+                            }
+                            _ => quote! {
+                                iter().map(|account| WithPrograms {
+                                    account,
+                                    programs: &programs_map
+                                })
+                            },
+                        };
+
+                        quote! {
+                            let mut __remaining_accounts__ = ctx
+                                .remaining_accounts
+                                .iter()
+                                .map(|account| #checked)
+                                .collect::<Result<Vec<#account_ty>>>()?;
+                            let #name = Mutable::new(__remaining_accounts__.#loaded.collect::<Vec<_>>());
+                        }
+                    });
+
+            // Program accounts are stored like the instruction's accounts, but Anchor doesn't know to
+            // save them, so that's done here too
+            let store_remaining_accounts = ix_context.remaining_accounts.as_ref().and_then(
+                |(name, account_ty)| match account_ty {
+                    AccountTyExpr::Defined(path) => {
+                        let name = ident(name);
+                        let path = StaticPath(path);
+
+                        Some(quote! {
+                            for account in #name.borrow().iter() {
+                                #path::store(account.clone());
+                            }
+                            drop(#name);
+
+                            for account in __remaining_accounts__.iter() {
+                                account.exit(&crate::ID)?;
+                            }
+                        })
+                    }
+                    _ => None,
+                },
+            );
+
+            // Remaining accounts need to live as long as the instruction's accounts
+            let (info_lifetime, context) = if ix_context.remaining_accounts.is_some() {
+                (
+                    Some(quote! { <'info> }),
+                    quote! { Context<'_, '_, 'info, 'info, #context_name<'info>> },
+                )
+            } else {
+                (None, quote! { Context<#context_name> })
+            };
+
             let ix_params = params.iter().filter_map(|(name, ty)| {
                 if !ix_context.params.iter().any(|(name_, _)| name == name_) {
                     return None;
@@ -1430,6 +2238,64 @@ fn make_lib(
                 }
             });
 
+            // The seeds of PDAs that sign CPIs are collected before the accounts are loaded, while
+            // the seeds can still refer to the Anchor accounts
+            let signer_seeds = ix_context.signer_seeds.iter().map(|name| {
+                let (_, account) = ix_context
+                    .accounts
+                    .iter()
+                    .find(|(name_, _)| name == name_)
+                    .unwrap();
+                let (seeds, bump) = account.annotation.as_ref().unwrap().pda_seeds().unwrap();
+
+                // Bind the accounts that the seeds use
+                let used = quote! { #(#seeds)* #bump };
+                let accounts = ix_context
+                    .accounts
+                    .iter()
+                    .filter(|(name, _)| mentions(&used, name))
+                    .map(|(name, _)| {
+                        let name = ident(name);
+
+                        quote! { let #name = &ctx.accounts.#name; }
+                    });
+
+                let bump = match bump {
+                    Some(bump) => quote! { #bump },
+                    None => {
+                        let name = ident(name);
+
+                        quote! { ctx.bumps.#name }
+                    }
+                };
+
+                let name = signer_seeds_ident(name);
+
+                quote! {
+                    let #name = {
+                        #(#accounts)*
+                        vec![#(#seeds.to_vec(),)* vec![#bump]]
+                    };
+                }
+            });
+
+            let signer_seed_args = ix_context
+                .signer_seeds
+                .iter()
+                .map(|name| {
+                    let name = signer_seeds_ident(name);
+
+                    quote! { #name }
+                })
+                .chain(ix_context.emit_cpi.then(|| {
+                    quote! {
+                        EventAuthority {
+                            account: ctx.accounts.event_authority.to_account_info(),
+                            bump: ctx.bumps.event_authority
+                        }
+                    }
+                }));
+
             let store_accounts = ix_context.accounts.iter().filter_map(
                 |(name, ContextAccount { account_ty, .. })| {
                     // Deconstruct `Empty` accounts
@@ -1449,24 +2315,62 @@ fn make_lib(
                                 Some(quote! { #path::store(#name); })
                             }
                         }
+                        AccountTyExpr::Optional(optional) => match &**optional {
+                            AccountTyExpr::Defined(path) => {
+                                let name = ident(name);
+                                let path = StaticPath(path);
+
+                                Some(quote! {
+                                    if let Some(#name) = #name {
+                                        #path::store(#name);
+                                    }
+                                })
+                            }
+                            _ => None,
+                        },
                         _ => None,
                     }
                 },
             );
 
+            // Values returned from an instruction are stored, then Anchor serializes them into the
+            // transaction's return data
+            let (returns, call_handler, return_value) = match returns {
+                TyExpr::Tuple(elements) if elements.is_empty() => (
+                    quote! { () },
+                    quote! { #handler_name(#(#params,)* #(#signer_seed_args),*)?; },
+                    quote! { () },
+                ),
+                returns => {
+                    let stored = stored_field(quote! { __return__ }, returns);
+                    let returns = StoredTyExpr(returns);
+
+                    (
+                        quote! { #returns },
+                        quote! {
+                            let __return__ = #handler_name(#(#params,)* #(#signer_seed_args),*)?;
+                        },
+                        stored,
+                    )
+                }
+            };
+
             Some(quote! {
                 #ix_context
 
-                pub fn #name(ctx: Context<#context_name>, #(#ix_params),*) -> Result<()> {
+                pub fn #name #info_lifetime(ctx: #context, #(#ix_params),*) -> Result<#returns> {
                     let mut programs = HashMap::new();
                     #(#insert_programs)*
                     let programs_map = ProgramsMap(programs);
 
+                    #(#signer_seeds)*
                     #(#load_accounts)*
-                    #handler_name(#(#params),*);
+                    #load_remaining_accounts
+                    #call_handler
                     #(#store_accounts)*
+                    #store_remaining_accounts
 
-                    return Ok(());
+                    return Ok(#return_value);
                 }
             })
         },
@@ -1492,7 +2396,7 @@ fn make_lib(
         mod #program_name {
             use super::*;
             use seahorse_util::*;
-            use std::collections::HashMap;
+            use std::collections::{BTreeMap, HashMap};
 
             #(#instructions)*
         }
@@ -1598,6 +2502,10 @@ fn beautify_impl(tokens: TokenStream) -> CResult<String> {
     let re = Regex::new(r"<\s*(.*?)\s*>").unwrap();
     source = re.replace_all(&source, "<$1>").to_string();
 
+    // Remove spaces before the try operator, so load () ? to load()?
+    let re = Regex::new(r"\)\s+\?").unwrap();
+    source = re.replace_all(&source, ")?").to_string();
+
     // Remove spaces around periods
     let re = Regex::new(r"\s+\.\s+").unwrap();
     source = re.replace_all(&source, ".").to_string();
@@ -1,11 +1,14 @@
 use crate::core::{parse::ast::*, CoreError};
 use rustpython_parser::{
     error::{ParseError, ParseErrorType},
+    lexer::make_tokenizer,
     parser,
+    token::Tok,
 };
+use std::rc::Rc;
 
 pub fn parse(source: String) -> Result<Program, CoreError> {
-    let ast = parser::parse_program(&source).map_err(|error| {
+    let ast = parser::parse_program(&rewrite_match(&source)?).map_err(|error| {
         let ParseError { error, location } = error;
         match error {
             ParseErrorType::EOF => {
@@ -37,3 +40,210 @@ pub fn parse(source: String) -> Result<Program, CoreError> {
     })?;
     return Ok(ast);
 }
+
+/// Rewrite `match` statements into something the parser understands.
+///
+/// The parser predates Python 3.10, so `match`/`case` headers get turned into `with` statements
+/// before parsing:
+///
+/// ```text
+/// match x:                    with  x:
+///     case A(y) if y > 0:  ->     with A(y) ,  y > 0:
+///         ...                         ...
+/// ```
+///
+/// The rewrite works on the token stream, so strings, comments and headers that continue over
+/// several lines are handled the same way the parser handles them. Every `with` keyword lands on
+/// the column of the `match`/`case` it replaces, which is how `clean` recognizes these statements
+/// and turns them back into `match` statements, and every expression keeps its line and column,
+/// so errors still point at the right place in the original source.
+fn rewrite_match(source: &str) -> Result<String, CoreError> {
+    // Lexical errors are left for the parser to report
+    let tokens = match make_tokenizer(source).collect::<Result<Vec<_>, _>>() {
+        Ok(tokens) => tokens,
+        Err(..) => return Ok(source.to_string()),
+    };
+
+    let mut edits = vec![];
+    let mut level = 0;
+    // Indentation level of the `case` blocks of each `match` statement the current line is in
+    let mut matches: Vec<usize> = vec![];
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].1 {
+            Tok::Indent => level += 1,
+            Tok::Dedent => {
+                level -= 1;
+                while matches
+                    .last()
+                    .map(|case_level| *case_level > level)
+                    .unwrap_or(false)
+                {
+                    matches.pop();
+                }
+            }
+            Tok::Newline | Tok::EndOfFile => {}
+            _ => {
+                let end = tokens[i..]
+                    .iter()
+                    .position(|(_, tok, _)| matches!(tok, Tok::Newline | Tok::EndOfFile))
+                    .map(|len| i + len)
+                    .unwrap_or(tokens.len());
+                let line = &tokens[i..end];
+
+                if is_name(&line[0].1, "case") && matches.last() == Some(&level) {
+                    edits.extend(rewrite_case(source, line)?);
+                } else if is_name(&line[0].1, "match") && starts_case_block(&tokens[end..]) {
+                    if let Some(header) = rewrite_match_header(line) {
+                        edits.extend(header);
+                        matches.push(level + 1);
+                    }
+                }
+
+                i = end;
+                continue;
+            }
+        }
+
+        i += 1;
+    }
+
+    Ok(apply_edits(source, edits))
+}
+
+type Token = (Location, Tok, Location);
+
+/// A replacement of the source between two locations.
+type Edit = (Location, Location, &'static str);
+
+fn is_name(tok: &Tok, keyword: &str) -> bool {
+    match tok {
+        Tok::Name { name } => name == keyword,
+        _ => false,
+    }
+}
+
+/// Whether the tokens after the end of a line start an indented block that opens with `case`.
+fn starts_case_block(rest: &[Token]) -> bool {
+    match rest {
+        [(_, Tok::Newline, _), (_, Tok::Indent, _), (_, next, _), ..] => is_name(next, "case"),
+        _ => false,
+    }
+}
+
+/// Find the first token at the top level of a line (outside of brackets) that matches `f`.
+fn find_top_level(line: &[Token], f: impl Fn(&Tok) -> bool) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, (_, tok, _)) in line.iter().enumerate() {
+        match tok {
+            Tok::Lpar | Tok::Lsqb | Tok::Lbrace => depth += 1,
+            Tok::Rpar | Tok::Rsqb | Tok::Rbrace => depth -= 1,
+            tok if depth == 0 && f(tok) => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// `match x:` -> `with  x:`, `match x, y:` -> `with(x, y):`
+fn rewrite_match_header(line: &[Token]) -> Option<Vec<Edit>> {
+    // The body has to start on the next line, so the colon ends the header
+    let colon = find_top_level(line, |tok| *tok == Tok::Colon)?;
+    if colon != line.len() - 1 || colon < 2 {
+        return None;
+    }
+
+    let (start, _, end) = &line[0];
+    let colon = line[colon].0;
+
+    Some(match find_top_level(&line[1..], |tok| *tok == Tok::Comma) {
+        Some(_) => vec![(*start, *end, "with("), (colon, colon, ")")],
+        None => vec![(*start, *end, "with ")],
+    })
+}
+
+/// `case P:` -> `with P:`, `case P if G:` -> `with P , G:`. An unparenthesized tuple pattern gets
+/// wrapped in parentheses: `case a, b if G:` -> `with(a, b ), G:`.
+fn rewrite_case(source: &str, line: &[Token]) -> Result<Vec<Edit>, CoreError> {
+    let colon = match find_top_level(line, |tok| *tok == Tok::Colon) {
+        Some(colon) if colon > 1 => colon,
+        // Not something the parser will accept either way
+        _ => return Ok(vec![]),
+    };
+    let guard = find_top_level(&line[1..colon], |tok| *tok == Tok::If).map(|i| i + 1);
+    let pattern = &line[1..guard.unwrap_or(colon)];
+
+    if let Some((loc, ..)) = pattern.iter().find(|(_, tok, _)| *tok == Tok::As) {
+        return Err(CoreError::make_raw(
+            "unsupported pattern",
+            "Help: `as` patterns are not supported, try binding the value inside the case body instead.",
+        )
+        .with_loc(*loc)
+        .with_src(Rc::new(source.to_string())));
+    }
+
+    let (start, _, end) = &line[0];
+    let mut edits = vec![(*start, *end, "with")];
+    let wrap = find_top_level(pattern, |tok| *tok == Tok::Comma).is_some();
+
+    if wrap {
+        // Open the parenthesis on the whitespace before the pattern if there is any, so that the
+        // pattern keeps its columns
+        let first = pattern[0].0;
+        let before = Location::new(first.row(), first.column() - 1);
+        match first.row() == end.row() && char_at(source, before) == Some(' ') {
+            true => edits.push((before, first, "(")),
+            false => edits.push((first, first, "(")),
+        }
+    }
+
+    match guard {
+        Some(guard) => {
+            let (start, _, end) = &line[guard];
+            edits.push((*start, *end, if wrap { ")," } else { ", " }));
+        }
+        None if wrap => edits.push((line[colon].0, line[colon].0, ")")),
+        None => {}
+    }
+
+    Ok(edits)
+}
+
+fn char_at(source: &str, loc: Location) -> Option<char> {
+    source
+        .split('\n')
+        .nth(loc.row() - 1)?
+        .chars()
+        .nth(loc.column().checked_sub(1)?)
+}
+
+/// Apply a list of edits to the source. Edits may not overlap.
+fn apply_edits(source: &str, mut edits: Vec<Edit>) -> String {
+    let mut line_starts = vec![0];
+    line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+
+    let offset = |loc: Location| {
+        let line_start = line_starts[loc.row() - 1];
+        source[line_start..]
+            .char_indices()
+            .nth(loc.column() - 1)
+            .map(|(i, _)| line_start + i)
+            .unwrap_or(source.len())
+    };
+
+    edits.sort_by_key(|(start, ..)| (start.row(), start.column()));
+
+    let mut rewritten = String::with_capacity(source.len());
+    let mut copied = 0;
+    for (start, end, text) in edits {
+        rewritten.push_str(&source[copied..offset(start)]);
+        rewritten.push_str(text);
+        copied = offset(end);
+    }
+    rewritten.push_str(&source[copied..]);
+
+    rewritten
+}
@@ -14,7 +14,10 @@ use std::{
     path::PathBuf,
 };
 
-use super::compile::builtin::prelude::path_to_string;
+use super::compile::builtin::{
+    idl::{self, IdlInstruction},
+    prelude::path_to_string,
+};
 
 enum Error {
     CouldNotAddModule,
@@ -104,6 +107,24 @@ fn load_module(path: PathBuf) -> CResult<Module> {
     return Ok(Module::Python(module));
 }
 
+/// Load an Anchor IDL from path, for a module at `module`. Its types are declared in Seahorse source,
+/// which gets parsed and cleaned like a regular module.
+fn load_idl(path: PathBuf, module: &[String]) -> CResult<Module> {
+    let mut input = File::open(path.clone())
+        .map_err(|_| CoreError::make_raw(format!("could not open file {}", path.display()), ""))?;
+    let mut json = String::new();
+    input
+        .read_to_string(&mut json)
+        .map_err(|_| CoreError::make_raw("I/O error", ""))?;
+
+    let idl = idl::parse(&json, module)?;
+
+    let parsed = parse(idl.types.clone())?;
+    let types = clean(parsed, idl.types)?;
+
+    Ok(Module::Idl(types, idl.instructions))
+}
+
 fn from_os_string(os: &OsStr) -> String {
     os.to_str().unwrap().to_string()
 }
@@ -113,6 +134,8 @@ pub enum Module {
     Python(ca::Module),
     SeahorsePrelude,
     SeahorsePyth,
+    // An IDL's types, and its instructions
+    Idl(ca::Module, Vec<IdlInstruction>),
 }
 
 /// A combined registry tree + filesystem path.
@@ -172,6 +195,14 @@ impl ComboPath {
         return fs_path;
     }
 
+    /// Get the filesystem path for an IDL at this path. IDLs are all located directly in `fs_base`.
+    fn get_fs_idl_path(&self) -> PathBuf {
+        let mut fs_path = self.fs_base.clone();
+        fs_path.push(format!("{}.json", self.path.last().unwrap()));
+
+        fs_path
+    }
+
     fn push(&mut self, part: String) {
         self.path.push(part);
     }
@@ -189,6 +220,11 @@ impl ComboPath {
         let fs_path = self.get_fs_package_path();
         return fs_path.is_dir();
     }
+
+    fn is_idl(&self) -> bool {
+        let fs_path = self.get_fs_idl_path();
+        fs_path.is_file()
+    }
 }
 
 impl Display for ComboPath {
@@ -283,6 +319,28 @@ impl ModuleTreeBuilder {
 
                             // (Loop used so that nested scopes can immediately break here)
                             'done: loop {
+                                // Try an IDL import
+                                if *level == 0 && symbol_path.len() == 2 && symbol_path[0] == "idl"
+                                {
+                                    // IDL modules are local, since their types get compiled
+                                    // with the program
+                                    let mut path = ComboPath::new(
+                                        vec!["dot".to_string(), "idl".to_string()],
+                                        path.fs_base.clone(),
+                                    );
+                                    path.push(symbol_path[1].clone());
+
+                                    if path.is_idl() {
+                                        if self.tree.get(&path.get_path()).is_none() {
+                                            let module =
+                                                load_idl(path.get_fs_idl_path(), &path.get_path())
+                                                    .map_err(|err| err.located(loc.clone()))?;
+                                            self.add_module(module, path)?;
+                                        }
+                                        break 'done;
+                                    }
+                                }
+
                                 // Try a Seahorse import
                                 if *level == 0 {
                                     let mut path =
@@ -10,7 +10,7 @@ pub mod program;
 use crate::{id, seahorse_util::*};
 use anchor_lang::{prelude::*, solana_program};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 #[account]
 #[derive(Debug)]
@@ -19,6 +19,10 @@ pub struct Calculator {
     pub display: i64,
 }
 
+impl Calculator {
+    pub const __SPACE__: usize = 8 + std::mem::size_of::<Pubkey>() + std::mem::size_of::<i64>();
+}
+
 impl<'info, 'entrypoint> Calculator {
     pub fn load(
         account: &'entrypoint mut Box<Account<'info, Self>>,
@@ -69,12 +73,16 @@ impl Default for Operation {
     }
 }
 
+impl Operation {
+    pub const __SIZE__: usize = 1 + max_space(&[0, 0, 0, 0]);
+}
+
 pub fn do_operation_handler<'info>(
     mut owner: SeahorseSigner<'info, '_>,
     mut calculator: Mutable<LoadedCalculator<'info, '_>>,
     mut op: Operation,
     mut num: i64,
-) -> () {
+) -> Result<()> {
     if !(owner.key() == calculator.borrow().owner) {
         panic!("This is not your calculator!");
     }
@@ -106,21 +114,25 @@ pub fn do_operation_handler<'info>(
             }
         }
     }
+
+    Ok(())
 }
 
 pub fn init_calculator_handler<'info>(
     mut owner: SeahorseSigner<'info, '_>,
     mut calculator: Empty<Mutable<LoadedCalculator<'info, '_>>>,
-) -> () {
+) -> Result<()> {
     let mut calculator = calculator.account.clone();
 
     assign!(calculator.borrow_mut().owner, owner.key());
+
+    Ok(())
 }
 
 pub fn reset_calculator_handler<'info>(
     mut owner: SeahorseSigner<'info, '_>,
     mut calculator: Mutable<LoadedCalculator<'info, '_>>,
-) -> () {
+) -> Result<()> {
     solana_program::msg!(
         "{:?} {} {:?}",
         owner.key(),
@@ -133,6 +145,8 @@ pub fn reset_calculator_handler<'info>(
     }
 
     assign!(calculator.borrow_mut().display, 0);
+
+    Ok(())
 }
 
 // ===== lib.rs =====
@@ -154,301 +168,544 @@ use std::{cell::RefCell, rc::Rc};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-pub mod seahorse_util {
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod calculator {
     use super::*;
-    use std::{
-        collections::HashMap,
-        fmt::Debug,
-        ops::{Deref, Index, IndexMut},
-    };
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    #[instruction(op: Operation, num: i64)]
+    pub struct DoOperation<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        pub calculator: Box<Account<'info, dot::program::Calculator>>,
+    }
 
-    pub struct Mutable<T>(Rc<RefCell<T>>);
+    pub fn do_operation(ctx: Context<DoOperation>, op: Operation, num: i64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
 
-    impl<T> Mutable<T> {
-        pub fn new(obj: T) -> Self {
-            Self(Rc::new(RefCell::new(obj)))
-        }
+        let calculator =
+            dot::program::Calculator::load(&mut ctx.accounts.calculator, &programs_map);
+
+        do_operation_handler(owner.clone(), calculator.clone(), op, num)?;
+
+        dot::program::Calculator::store(calculator);
+
+        return Ok(());
     }
 
-    impl<T> Clone for Mutable<T> {
-        fn clone(&self) -> Self {
-            Self(self.0.clone())
-        }
+    #[derive(Accounts)]
+    pub struct InitCalculator<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(init, space = dot::program::Calculator::__SPACE__, payer = owner, seeds = ["Calculator".as_bytes().as_ref(), owner.key().as_ref()], bump)]
+        pub calculator: Box<Account<'info, dot::program::Calculator>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
     }
 
-    impl<T> Deref for Mutable<T> {
-        type Target = Rc<RefCell<T>>;
+    pub fn init_calculator(ctx: Context<InitCalculator>) -> Result<()> {
+        let mut programs = HashMap::new();
 
-        fn deref(&self) -> &Self::Target {
-            &self.0
-        }
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let calculator = Empty {
+            account: dot::program::Calculator::load(&mut ctx.accounts.calculator, &programs_map),
+            bump: Some(ctx.bumps.calculator),
+        };
+
+        init_calculator_handler(owner.clone(), calculator.clone())?;
+
+        dot::program::Calculator::store(calculator.account);
+
+        return Ok(());
     }
 
-    impl<T: Debug> Debug for Mutable<T> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{:?}", self.0)
-        }
+    #[derive(Accounts)]
+    pub struct ResetCalculator<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        pub calculator: Box<Account<'info, dot::program::Calculator>>,
     }
 
-    impl<T: Default> Default for Mutable<T> {
-        fn default() -> Self {
-            Self::new(T::default())
-        }
+    pub fn reset_calculator(ctx: Context<ResetCalculator>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let calculator =
+            dot::program::Calculator::load(&mut ctx.accounts.calculator, &programs_map);
+
+        reset_calculator_handler(owner.clone(), calculator.clone())?;
+
+        dot::program::Calculator::store(calculator);
+
+        return Ok(());
     }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
 
-    pub trait IndexWrapped {
-        type Output;
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
 
-        fn index_wrapped(&self, index: i128) -> &Self::Output;
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
     }
+}
 
-    pub trait IndexWrappedMut: IndexWrapped {
-        fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
     }
+}
 
-    impl<T> IndexWrapped for Vec<T> {
-        type Output = T;
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
 
-        fn index_wrapped(&self, mut index: i128) -> &Self::Output {
-            if index < 0 {
-                index += self.len() as i128;
-            }
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
 
-            let index: usize = index.try_into().unwrap();
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
 
-            self.index(index)
-        }
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
     }
+}
 
-    impl<T> IndexWrappedMut for Vec<T> {
-        fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
-            if index < 0 {
-                index += self.len() as i128;
-            }
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
 
-            let index: usize = index.try_into().unwrap();
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
 
-            self.index_mut(index)
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
         }
+
+        i += 1;
     }
 
-    impl<T, const N: usize> IndexWrapped for [T; N] {
-        type Output = T;
+    max
+}
 
-        fn index_wrapped(&self, mut index: i128) -> &Self::Output {
-            if index < 0 {
-                index += N as i128;
-            }
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
 
-            let index: usize = index.try_into().unwrap();
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
 
-            self.index(index)
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
         }
-    }
 
-    impl<T, const N: usize> IndexWrappedMut for [T; N] {
-        fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
-            if index < 0 {
-                index += N as i128;
-            }
+        let index: usize = index.try_into().unwrap();
 
-            let index: usize = index.try_into().unwrap();
+        self.index(index)
+    }
+}
 
-            self.index_mut(index)
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
         }
-    }
 
-    #[derive(Clone)]
-    pub struct Empty<T: Clone> {
-        pub account: T,
-        pub bump: Option<u8>,
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
     }
+}
 
-    #[derive(Clone, Debug)]
-    pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
 
-    impl<'info> ProgramsMap<'info> {
-        pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
-            self.0.get(name).unwrap().clone()
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
         }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
     }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
 
-    #[derive(Clone, Debug)]
-    pub struct WithPrograms<'info, 'entrypoint, A> {
-        pub account: &'entrypoint A,
-        pub programs: &'entrypoint ProgramsMap<'info>,
+        self.index_mut(index)
     }
+}
 
-    impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
-        type Target = A;
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
 
-        fn deref(&self) -> &Self::Target {
-            &self.account
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
         }
-    }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
 
-    pub type SeahorseAccount<'info, 'entrypoint, A> =
-        WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
 
-    pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
 
-    #[derive(Clone, Debug)]
-    pub struct CpiAccount<'info> {
-        #[doc = "CHECK: CpiAccounts temporarily store AccountInfos."]
-        pub account_info: AccountInfo<'info>,
-        pub is_writable: bool,
-        pub is_signer: bool,
-        pub seeds: Option<Vec<Vec<u8>>>,
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
     }
+}
 
-    #[macro_export]
-    macro_rules! seahorse_const {
-        ($ name : ident , $ value : expr) => {
-            macro_rules! $name {
-                () => {
-                    $value
-                };
-            }
+impl SliceWrapped for str {
+    type Output = String;
 
-            pub(crate) use $name;
-        };
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
     }
+}
 
-    pub trait Loadable {
-        type Loaded;
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
 
-        fn load(stored: Self) -> Self::Loaded;
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
 
-        fn store(loaded: Self::Loaded) -> Self;
+            ProgramError::InvalidArgument.into()
+        })
     }
+}
 
-    macro_rules! Loaded {
-        ($ name : ty) => {
-            <$name as Loadable>::Loaded
-        };
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
     }
+    grouped.push_str(&formatted[end..]);
 
-    pub(crate) use Loaded;
+    grouped
+}
 
-    #[macro_export]
-    macro_rules! assign {
-        ($ lval : expr , $ rval : expr) => {{
-            let temp = $rval;
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
 
-            $lval = temp;
-        }};
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
     }
+}
 
-    #[macro_export]
-    macro_rules! index_assign {
-        ($ lval : expr , $ idx : expr , $ rval : expr) => {
-            let temp_rval = $rval;
-            let temp_idx = $idx;
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
 
-            $lval[temp_idx] = temp_rval;
-        };
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
     }
+}
 
-    pub(crate) use assign;
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
 
-    pub(crate) use index_assign;
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
 
-    pub(crate) use seahorse_const;
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
 }
 
-#[program]
-mod calculator {
-    use super::*;
-    use seahorse_util::*;
-    use std::collections::HashMap;
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
 
-    #[derive(Accounts)]
-    # [instruction (op : Operation , num : i64)]
-    pub struct DoOperation<'info> {
-        #[account(mut)]
-        pub owner: Signer<'info>,
-        #[account(mut)]
-        pub calculator: Box<Account<'info, dot::program::Calculator>>,
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
     }
+}
 
-    pub fn do_operation(ctx: Context<DoOperation>, op: Operation, num: i64) -> Result<()> {
-        let mut programs = HashMap::new();
-        let programs_map = ProgramsMap(programs);
-        let owner = SeahorseSigner {
-            account: &ctx.accounts.owner,
-            programs: &programs_map,
-        };
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
 
-        let calculator =
-            dot::program::Calculator::load(&mut ctx.accounts.calculator, &programs_map);
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
 
-        do_operation_handler(owner.clone(), calculator.clone(), op, num);
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
 
-        dot::program::Calculator::store(calculator);
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
 
-        return Ok(());
-    }
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
 
-    #[derive(Accounts)]
-    pub struct InitCalculator<'info> {
-        #[account(mut)]
-        pub owner: Signer<'info>,
-        # [account (init , space = std :: mem :: size_of :: < dot :: program :: Calculator > () + 8 , payer = owner , seeds = ["Calculator" . as_bytes () . as_ref () , owner . key () . as_ref ()] , bump)]
-        pub calculator: Box<Account<'info, dot::program::Calculator>>,
-        pub rent: Sysvar<'info, Rent>,
-        pub system_program: Program<'info, System>,
-    }
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
 
-    pub fn init_calculator(ctx: Context<InitCalculator>) -> Result<()> {
-        let mut programs = HashMap::new();
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
 
-        programs.insert(
-            "system_program",
-            ctx.accounts.system_program.to_account_info(),
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
         );
 
-        let programs_map = ProgramsMap(programs);
-        let owner = SeahorseSigner {
-            account: &ctx.accounts.owner,
-            programs: &programs_map,
-        };
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
 
-        let calculator = Empty {
-            account: dot::program::Calculator::load(&mut ctx.accounts.calculator, &programs_map),
-            bump: Some(ctx.bumps.calculator),
-        };
+        Ok(())
+    }
+}
 
-        init_calculator_handler(owner.clone(), calculator.clone());
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
 
-        dot::program::Calculator::store(calculator.account);
+        pub(crate) use $name;
+    };
+}
 
-        return Ok(());
-    }
+pub trait Loadable {
+    type Loaded;
 
-    #[derive(Accounts)]
-    pub struct ResetCalculator<'info> {
-        #[account(mut)]
-        pub owner: Signer<'info>,
-        #[account(mut)]
-        pub calculator: Box<Account<'info, dot::program::Calculator>>,
-    }
+    fn load(stored: Self) -> Self::Loaded;
 
-    pub fn reset_calculator(ctx: Context<ResetCalculator>) -> Result<()> {
-        let mut programs = HashMap::new();
-        let programs_map = ProgramsMap(programs);
-        let owner = SeahorseSigner {
-            account: &ctx.accounts.owner,
-            programs: &programs_map,
-        };
+    fn store(loaded: Self::Loaded) -> Self;
+}
 
-        let calculator =
-            dot::program::Calculator::load(&mut ctx.accounts.calculator, &programs_map);
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
 
-        reset_calculator_handler(owner.clone(), calculator.clone());
+pub(crate) use Loaded;
 
-        dot::program::Calculator::store(calculator);
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
 
-        return Ok(());
-    }
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
 }
 
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
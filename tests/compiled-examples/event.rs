@@ -10,15 +10,21 @@ pub mod program;
 use crate::{id, seahorse_util::*};
 use anchor_lang::{prelude::*, solana_program};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 #[event]
+#[derive(Clone, Debug)]
 pub struct HelloEvent {
     pub data: u8,
     pub title: String,
     pub owner: Pubkey,
 }
 
+impl HelloEvent {
+    pub const __SIZE__: usize =
+        std::mem::size_of::<u8>() + std::mem::size_of::<String>() + std::mem::size_of::<Pubkey>();
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LoadedHelloEvent {
     pub data: u8,
@@ -36,13 +42,24 @@ impl Mutable<LoadedHelloEvent> {
             owner: e.owner.clone()
         })
     }
+
+    #[cfg(feature = "event-cpi")]
+    fn __emit_cpi__(&self, event_authority: &EventAuthority) -> Result<()> {
+        let e = self.borrow();
+
+        event_authority.emit(HelloEvent {
+            data: e.data,
+            title: e.title.clone(),
+            owner: e.owner.clone(),
+        })
+    }
 }
 
 impl LoadedHelloEvent {
-    pub fn __new__(data: u8, title: String, owner: Pubkey) -> Mutable<Self> {
+    pub fn __new__(data: u8, title: String, owner: Pubkey) -> Result<Mutable<Self>> {
         let obj = LoadedHelloEvent { data, title, owner };
 
-        return Mutable::new(obj);
+        return Ok(Mutable::new(obj));
     }
 }
 
@@ -70,10 +87,12 @@ pub fn send_event_handler<'info>(
     mut sender: SeahorseSigner<'info, '_>,
     mut data: u8,
     mut title: String,
-) -> () {
-    let mut event = <Loaded!(HelloEvent)>::__new__(data.clone(), title.clone(), sender.key());
+) -> Result<()> {
+    let mut event = <Loaded!(HelloEvent)>::__new__(data.clone(), title.clone(), sender.key())?;
 
     event.__emit__();
+
+    Ok(())
 }
 
 // ===== lib.rs =====
@@ -95,232 +114,475 @@ use std::{cell::RefCell, rc::Rc};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-pub mod seahorse_util {
-    use super::*;
-    use std::{
-        collections::HashMap,
-        fmt::Debug,
-        ops::{Deref, Index, IndexMut},
-    };
+mod seahorse_util;
 
-    pub struct Mutable<T>(Rc<RefCell<T>>);
+use seahorse_util::*;
 
-    impl<T> Mutable<T> {
-        pub fn new(obj: T) -> Self {
-            Self(Rc::new(RefCell::new(obj)))
-        }
+#[program]
+mod event {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    #[instruction(data: u8, title: String)]
+    pub struct SendEvent<'info> {
+        #[account(mut)]
+        pub sender: Signer<'info>,
     }
 
-    impl<T> Clone for Mutable<T> {
-        fn clone(&self) -> Self {
-            Self(self.0.clone())
-        }
+    pub fn send_event(ctx: Context<SendEvent>, data: u8, title: String) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let sender = SeahorseSigner {
+            account: &ctx.accounts.sender,
+            programs: &programs_map,
+        };
+
+        send_event_handler(sender.clone(), data, title)?;
+
+        return Ok(());
     }
+}
 
-    impl<T> Deref for Mutable<T> {
-        type Target = Rc<RefCell<T>>;
+// ===== seahorse_util.rs =====
 
-        fn deref(&self) -> &Self::Target {
-            &self.0
-        }
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
     }
+}
 
-    impl<T: Debug> Debug for Mutable<T> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{:?}", self.0)
-        }
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
     }
+}
 
-    impl<T: Default> Default for Mutable<T> {
-        fn default() -> Self {
-            Self::new(T::default())
-        }
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
     }
+}
 
-    pub trait IndexWrapped {
-        type Output;
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
 
-        fn index_wrapped(&self, index: i128) -> &Self::Output;
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
     }
+}
 
-    pub trait IndexWrappedMut: IndexWrapped {
-        fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
     }
 
-    impl<T> IndexWrapped for Vec<T> {
-        type Output = T;
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
 
-        fn index_wrapped(&self, mut index: i128) -> &Self::Output {
-            if index < 0 {
-                index += self.len() as i128;
-            }
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
 
-            let index: usize = index.try_into().unwrap();
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
 
-            self.index(index)
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
         }
+
+        i += 1;
     }
 
-    impl<T> IndexWrappedMut for Vec<T> {
-        fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
-            if index < 0 {
-                index += self.len() as i128;
-            }
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
 
-            let index: usize = index.try_into().unwrap();
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
 
-            self.index_mut(index)
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
         }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
     }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
 
-    impl<T, const N: usize> IndexWrapped for [T; N] {
-        type Output = T;
+        let index: usize = index.try_into().unwrap();
 
-        fn index_wrapped(&self, mut index: i128) -> &Self::Output {
-            if index < 0 {
-                index += N as i128;
-            }
+        self.index_mut(index)
+    }
+}
 
-            let index: usize = index.try_into().unwrap();
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
 
-            self.index(index)
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
         }
-    }
 
-    impl<T, const N: usize> IndexWrappedMut for [T; N] {
-        fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
-            if index < 0 {
-                index += N as i128;
-            }
+        let index: usize = index.try_into().unwrap();
 
-            let index: usize = index.try_into().unwrap();
+        self.index(index)
+    }
+}
 
-            self.index_mut(index)
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
         }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
     }
+}
 
-    #[derive(Clone)]
-    pub struct Empty<T: Clone> {
-        pub account: T,
-        pub bump: Option<u8>,
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
     }
 
-    #[derive(Clone, Debug)]
-    pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
 
-    impl<'info> ProgramsMap<'info> {
-        pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
-            self.0.get(name).unwrap().clone()
+            Some(next as usize)
+        } else {
+            None
         }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
     }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
 
-    #[derive(Clone, Debug)]
-    pub struct WithPrograms<'info, 'entrypoint, A> {
-        pub account: &'entrypoint A,
-        pub programs: &'entrypoint ProgramsMap<'info>,
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
     }
+}
 
-    impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
-        type Target = A;
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
 
-        fn deref(&self) -> &Self::Target {
-            &self.account
-        }
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
     }
+}
 
-    pub type SeahorseAccount<'info, 'entrypoint, A> =
-        WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
 
-    pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
 
-    #[derive(Clone, Debug)]
-    pub struct CpiAccount<'info> {
-        #[doc = "CHECK: CpiAccounts temporarily store AccountInfos."]
-        pub account_info: AccountInfo<'info>,
-        pub is_writable: bool,
-        pub is_signer: bool,
-        pub seeds: Option<Vec<Vec<u8>>>,
+        grouped.push(digit);
     }
+    grouped.push_str(&formatted[end..]);
 
-    #[macro_export]
-    macro_rules! seahorse_const {
-        ($ name : ident , $ value : expr) => {
-            macro_rules! $name {
-                () => {
-                    $value
-                };
-            }
+    grouped
+}
 
-            pub(crate) use $name;
-        };
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
     }
+}
 
-    pub trait Loadable {
-        type Loaded;
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
 
-        fn load(stored: Self) -> Self::Loaded;
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
 
-        fn store(loaded: Self::Loaded) -> Self;
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
     }
+}
 
-    macro_rules! Loaded {
-        ($ name : ty) => {
-            <$name as Loadable>::Loaded
-        };
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
     }
+}
 
-    pub(crate) use Loaded;
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
 
-    #[macro_export]
-    macro_rules! assign {
-        ($ lval : expr , $ rval : expr) => {{
-            let temp = $rval;
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
 
-            $lval = temp;
-        }};
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
     }
+}
 
-    #[macro_export]
-    macro_rules! index_assign {
-        ($ lval : expr , $ idx : expr , $ rval : expr) => {
-            let temp_rval = $rval;
-            let temp_idx = $idx;
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
 
-            $lval[temp_idx] = temp_rval;
-        };
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
     }
+}
 
-    pub(crate) use assign;
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
 
-    pub(crate) use index_assign;
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
 
-    pub(crate) use seahorse_const;
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
 }
 
-#[program]
-mod event {
-    use super::*;
-    use seahorse_util::*;
-    use std::collections::HashMap;
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
 
-    #[derive(Accounts)]
-    # [instruction (data : u8 , title : String)]
-    pub struct SendEvent<'info> {
-        #[account(mut)]
-        pub sender: Signer<'info>,
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
     }
+}
 
-    pub fn send_event(ctx: Context<SendEvent>, data: u8, title: String) -> Result<()> {
-        let mut programs = HashMap::new();
-        let programs_map = ProgramsMap(programs);
-        let sender = SeahorseSigner {
-            account: &ctx.accounts.sender,
-            programs: &programs_map,
-        };
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
 
-        send_event_handler(sender.clone(), data, title);
+        pub(crate) use $name;
+    };
+}
 
-        return Ok(());
-    }
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
 }
 
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
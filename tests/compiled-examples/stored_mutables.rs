@@ -13,7 +13,7 @@ use crate::dot::util::more_data::MoreData;
 use crate::{id, seahorse_util::*};
 use anchor_lang::{prelude::*, solana_program};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 #[account]
 #[derive(Debug)]
@@ -28,6 +28,18 @@ pub struct Data {
     pub more_data: MoreData,
 }
 
+impl Data {
+    pub const __SPACE__: usize = 8
+        +(2 *(2 * std::mem::size_of::<i32>()))
+        + std::mem::size_of::<Vec<i32>>()
+        + std::mem::size_of::<Vec<Vec<i32>>>()
+        + std::mem::size_of::<String>()
+        + Nested::__SIZE__
+        + std::mem::size_of::<Vec<Nested>>()
+        + Flag::__SIZE__
+        + MoreData::__SIZE__;
+}
+
 impl<'info, 'entrypoint> Data {
     pub fn load(
         account: &'entrypoint mut Box<Account<'info, Self>>,
@@ -174,24 +186,30 @@ pub struct Deep {
     pub num: i32,
 }
 
+impl Deep {
+    pub const __SIZE__: usize = std::mem::size_of::<i32>();
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LoadedDeep {
     pub num: i32,
 }
 
 impl Mutable<LoadedDeep> {
-    pub fn __init__(&self, mut num: i32) -> () {
+    pub fn __init__(&self, mut num: i32) -> Result<()> {
         assign!(self.borrow_mut().num, num);
+
+        Ok(())
     }
 }
 
 impl LoadedDeep {
-    pub fn __new__(num: i32) -> Mutable<Self> {
+    pub fn __new__(num: i32) -> Result<Mutable<Self>> {
         let obj = Mutable::new(LoadedDeep::default());
 
-        obj.__init__(num);
+        obj.__init__(num)?;
 
-        return obj;
+        return Ok(obj);
     }
 }
 
@@ -219,11 +237,20 @@ impl Default for Flag {
     }
 }
 
+impl Flag {
+    pub const __SIZE__: usize = 1 + max_space(&[0, 0]);
+}
+
 #[event]
+#[derive(Clone, Debug)]
 pub struct MyEvent {
     pub nums: Vec<i32>,
 }
 
+impl MyEvent {
+    pub const __SIZE__: usize = std::mem::size_of::<Vec<i32>>();
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LoadedMyEvent {
     pub nums: Mutable<Vec<i32>>,
@@ -244,6 +271,22 @@ impl Mutable<LoadedMyEvent> {
                 .collect()
         })
     }
+
+    #[cfg(feature = "event-cpi")]
+    fn __emit_cpi__(&self, event_authority: &EventAuthority) -> Result<()> {
+        let e = self.borrow();
+
+        event_authority.emit(MyEvent {
+            nums: e
+                .nums
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+        })
+    }
 }
 
 impl Loadable for MyEvent {
@@ -274,31 +317,39 @@ pub struct Nested {
     pub deep: Deep,
 }
 
+impl Nested {
+    pub const __SIZE__: usize = Deep::__SIZE__;
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LoadedNested {
     pub deep: Mutable<Loaded!(Deep)>,
 }
 
 impl Mutable<LoadedNested> {
-    pub fn __init__(&self, mut num: i32) -> () {
+    pub fn __init__(&self, mut num: i32) -> Result<()> {
         assign!(
             self.borrow_mut().deep,
-            <Loaded!(Deep)>::__new__(num.clone())
+            <Loaded!(Deep)>::__new__(num.clone())?
         );
+
+        Ok(())
     }
 
-    pub fn reset(&self) -> () {
-        assign!(self.borrow_mut().deep, <Loaded!(Deep)>::__new__(0));
+    pub fn reset(&self) -> Result<()> {
+        assign!(self.borrow_mut().deep, <Loaded!(Deep)>::__new__(0)?);
+
+        Ok(())
     }
 }
 
 impl LoadedNested {
-    pub fn __new__(num: i32) -> Mutable<Self> {
+    pub fn __new__(num: i32) -> Result<Mutable<Self>> {
         let obj = Mutable::new(LoadedNested::default());
 
-        obj.__init__(num);
+        obj.__init__(num)?;
 
-        return obj;
+        return Ok(obj);
     }
 }
 
@@ -321,7 +372,7 @@ impl Loadable for Nested {
 pub fn init_handler<'info>(
     mut signer: SeahorseSigner<'info, '_>,
     mut data: Empty<Mutable<LoadedData<'info, '_>>>,
-) -> () {
+) -> Result<()> {
     let mut init_data = data.account.clone();
 
     assign!(init_data.borrow_mut().int_list, Mutable::new(vec![1, 2]));
@@ -333,28 +384,32 @@ pub fn init_handler<'info>(
 
     assign!(init_data.borrow_mut().string, "Hello".to_string());
 
-    assign!(init_data.borrow_mut().nested, <Loaded!(Nested)>::__new__(7));
+    assign!(
+        init_data.borrow_mut().nested,
+        <Loaded!(Nested)>::__new__(7)?
+    );
 
     assign!(
         init_data.borrow_mut().nested_list,
         Mutable::new(vec![
-            <Loaded!(Nested)>::__new__(8),
-            <Loaded!(Nested)>::__new__(9)
+            <Loaded!(Nested)>::__new__(8)?,
+            <Loaded!(Nested)>::__new__(9)?
         ])
     );
 
     assign!(
         init_data.borrow_mut().more_data,
-        <Loaded!(MoreData)>::__new__(10)
+        <Loaded!(MoreData)>::__new__(10)?
     );
+
+    Ok(())
 }
 
 pub fn test_stored_mutables_handler<'info>(
     mut signer: SeahorseSigner<'info, '_>,
     mut data: Mutable<LoadedData<'info, '_>>,
-) -> () {
-    assign!(
-        (*(*data
+) -> Result<()> {
+    assign!((*(*data
             .borrow_mut()
             .array_2d
             .borrow_mut()
@@ -366,8 +421,7 @@ pub fn test_stored_mutables_handler<'info>(
 
     data.borrow().int_list.borrow_mut().push(0);
 
-    assign!(
-        (*(*data
+    assign!((*(*data
             .borrow_mut()
             .int_list_2d
             .borrow_mut()
@@ -382,21 +436,21 @@ pub fn test_stored_mutables_handler<'info>(
         data.borrow().string.clone() + &" World".to_string()
     );
 
-    data.borrow().nested.reset();
-
-    (*data.borrow().nested_list.borrow().index_wrapped(0.into())).reset();
+    data.borrow().nested.reset()?;(*data.borrow().nested_list.borrow().index_wrapped(0.into())).reset()?;
 
     data.borrow()
         .nested_list
         .borrow_mut()
-        .push(<Loaded!(Nested)>::__new__(10));
+        .push(<Loaded!(Nested)>::__new__(10)?);
 
     assign!(data.borrow_mut().flag, Flag::ON);
 
     assign!(
         data.borrow_mut().more_data,
-        <Loaded!(MoreData)>::__new__(11)
+        <Loaded!(MoreData)>::__new__(11)?
     );
+
+    Ok(())
 }
 
 // ===== dot/util/mod.rs =====
@@ -411,31 +465,37 @@ pub mod more_data;
 use crate::{id, seahorse_util::*};
 use anchor_lang::{prelude::*, solana_program};
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct MoreData {
     pub num: i32,
 }
 
+impl MoreData {
+    pub const __SIZE__: usize = std::mem::size_of::<i32>();
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct LoadedMoreData {
     pub num: i32,
 }
 
 impl Mutable<LoadedMoreData> {
-    pub fn __init__(&self, mut num: i32) -> () {
+    pub fn __init__(&self, mut num: i32) -> Result<()> {
         assign!(self.borrow_mut().num, num);
+
+        Ok(())
     }
 }
 
 impl LoadedMoreData {
-    pub fn __new__(num: i32) -> Mutable<Self> {
+    pub fn __new__(num: i32) -> Result<Mutable<Self>> {
         let obj = Mutable::new(LoadedMoreData::default());
 
-        obj.__init__(num);
+        obj.__init__(num)?;
 
-        return obj;
+        return Ok(obj);
     }
 }
 
@@ -470,273 +530,516 @@ use std::{cell::RefCell, rc::Rc};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
-pub mod seahorse_util {
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod stored_mutables {
     use super::*;
-    use std::{
-        collections::HashMap,
-        fmt::Debug,
-        ops::{Deref, Index, IndexMut},
-    };
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
 
-    pub struct Mutable<T>(Rc<RefCell<T>>);
+    #[derive(Accounts)]
+    pub struct Init<'info> {
+        #[account(mut)]
+        pub signer: Signer<'info>,
+        #[account(init, space = dot::program::Data::__SPACE__ +(1024 as usize), payer = signer, seeds = [signer.key().as_ref()], bump)]
+        pub data: Box<Account<'info, dot::program::Data>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
 
-    impl<T> Mutable<T> {
-        pub fn new(obj: T) -> Self {
-            Self(Rc::new(RefCell::new(obj)))
-        }
+    pub fn init(ctx: Context<Init>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let signer = SeahorseSigner {
+            account: &ctx.accounts.signer,
+            programs: &programs_map,
+        };
+
+        let data = Empty {
+            account: dot::program::Data::load(&mut ctx.accounts.data, &programs_map),
+            bump: Some(ctx.bumps.data),
+        };
+
+        init_handler(signer.clone(), data.clone())?;
+
+        dot::program::Data::store(data.account);
+
+        return Ok(());
     }
 
-    impl<T> Clone for Mutable<T> {
-        fn clone(&self) -> Self {
-            Self(self.0.clone())
-        }
+    #[derive(Accounts)]
+    pub struct TestStoredMutables<'info> {
+        #[account(mut)]
+        pub signer: Signer<'info>,
+        #[account(mut)]
+        pub data: Box<Account<'info, dot::program::Data>>,
     }
 
-    impl<T> Deref for Mutable<T> {
-        type Target = Rc<RefCell<T>>;
+    pub fn test_stored_mutables(ctx: Context<TestStoredMutables>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let signer = SeahorseSigner {
+            account: &ctx.accounts.signer,
+            programs: &programs_map,
+        };
+
+        let data = dot::program::Data::load(&mut ctx.accounts.data, &programs_map);
 
-        fn deref(&self) -> &Self::Target {
-            &self.0
-        }
+        test_stored_mutables_handler(signer.clone(), data.clone())?;
+
+        dot::program::Data::store(data);
+
+        return Ok(());
     }
+}
 
-    impl<T: Debug> Debug for Mutable<T> {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{:?}", self.0)
-        }
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
     }
+}
 
-    impl<T: Default> Default for Mutable<T> {
-        fn default() -> Self {
-            Self::new(T::default())
-        }
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
     }
+}
 
-    pub trait IndexWrapped {
-        type Output;
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
 
-        fn index_wrapped(&self, index: i128) -> &Self::Output;
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
     }
+}
 
-    pub trait IndexWrappedMut: IndexWrapped {
-        fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
     }
 
-    impl<T> IndexWrapped for Vec<T> {
-        type Output = T;
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
 
-        fn index_wrapped(&self, mut index: i128) -> &Self::Output {
-            if index < 0 {
-                index += self.len() as i128;
-            }
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
 
-            let index: usize = index.try_into().unwrap();
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
 
-            self.index(index)
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
         }
+
+        i += 1;
     }
 
-    impl<T> IndexWrappedMut for Vec<T> {
-        fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
-            if index < 0 {
-                index += self.len() as i128;
-            }
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
 
-            let index: usize = index.try_into().unwrap();
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
 
-            self.index_mut(index)
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
         }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
     }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
 
-    impl<T, const N: usize> IndexWrapped for [T; N] {
-        type Output = T;
+        let index: usize = index.try_into().unwrap();
 
-        fn index_wrapped(&self, mut index: i128) -> &Self::Output {
-            if index < 0 {
-                index += N as i128;
-            }
+        self.index_mut(index)
+    }
+}
 
-            let index: usize = index.try_into().unwrap();
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
 
-            self.index(index)
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
         }
-    }
 
-    impl<T, const N: usize> IndexWrappedMut for [T; N] {
-        fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
-            if index < 0 {
-                index += N as i128;
-            }
+        let index: usize = index.try_into().unwrap();
 
-            let index: usize = index.try_into().unwrap();
+        self.index(index)
+    }
+}
 
-            self.index_mut(index)
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
         }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
     }
+}
 
-    #[derive(Clone)]
-    pub struct Empty<T: Clone> {
-        pub account: T,
-        pub bump: Option<u8>,
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
     }
 
-    #[derive(Clone, Debug)]
-    pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
 
-    impl<'info> ProgramsMap<'info> {
-        pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
-            self.0.get(name).unwrap().clone()
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
         }
-    }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
 
-    #[derive(Clone, Debug)]
-    pub struct WithPrograms<'info, 'entrypoint, A> {
-        pub account: &'entrypoint A,
-        pub programs: &'entrypoint ProgramsMap<'info>,
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
     }
+}
 
-    impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
-        type Target = A;
+impl SliceWrapped for str {
+    type Output = String;
 
-        fn deref(&self) -> &Self::Target {
-            &self.account
-        }
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
     }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
 
-    pub type SeahorseAccount<'info, 'entrypoint, A> =
-        WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
 
-    pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
 
-    #[derive(Clone, Debug)]
-    pub struct CpiAccount<'info> {
-        #[doc = "CHECK: CpiAccounts temporarily store AccountInfos."]
-        pub account_info: AccountInfo<'info>,
-        pub is_writable: bool,
-        pub is_signer: bool,
-        pub seeds: Option<Vec<Vec<u8>>>,
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
     }
 
-    #[macro_export]
-    macro_rules! seahorse_const {
-        ($ name : ident , $ value : expr) => {
-            macro_rules! $name {
-                () => {
-                    $value
-                };
-            }
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
 
-            pub(crate) use $name;
-        };
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
     }
+    grouped.push_str(&formatted[end..]);
 
-    pub trait Loadable {
-        type Loaded;
+    grouped
+}
 
-        fn load(stored: Self) -> Self::Loaded;
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
 
-        fn store(loaded: Self::Loaded) -> Self;
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
     }
+}
 
-    macro_rules! Loaded {
-        ($ name : ty) => {
-            <$name as Loadable>::Loaded
-        };
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
     }
+}
 
-    pub(crate) use Loaded;
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
 
-    #[macro_export]
-    macro_rules! assign {
-        ($ lval : expr , $ rval : expr) => {{
-            let temp = $rval;
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
 
-            $lval = temp;
-        }};
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
     }
+}
 
-    #[macro_export]
-    macro_rules! index_assign {
-        ($ lval : expr , $ idx : expr , $ rval : expr) => {
-            let temp_rval = $rval;
-            let temp_idx = $idx;
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
 
-            $lval[temp_idx] = temp_rval;
-        };
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
     }
+}
 
-    pub(crate) use assign;
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
 
-    pub(crate) use index_assign;
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
 
-    pub(crate) use seahorse_const;
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
 }
 
-#[program]
-mod stored_mutables {
-    use super::*;
-    use seahorse_util::*;
-    use std::collections::HashMap;
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
 
-    #[derive(Accounts)]
-    pub struct Init<'info> {
-        #[account(mut)]
-        pub signer: Signer<'info>,
-        # [account (init , space = std :: mem :: size_of :: < dot :: program :: Data > () + 8 + (1024 as usize) , payer = signer , seeds = [signer . key () . as_ref ()] , bump)]
-        pub data: Box<Account<'info, dot::program::Data>>,
-        pub rent: Sysvar<'info, Rent>,
-        pub system_program: Program<'info, System>,
-    }
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
 
-    pub fn init(ctx: Context<Init>) -> Result<()> {
-        let mut programs = HashMap::new();
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
 
-        programs.insert(
-            "system_program",
-            ctx.accounts.system_program.to_account_info(),
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
         );
 
-        let programs_map = ProgramsMap(programs);
-        let signer = SeahorseSigner {
-            account: &ctx.accounts.signer,
-            programs: &programs_map,
-        };
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
 
-        let data = Empty {
-            account: dot::program::Data::load(&mut ctx.accounts.data, &programs_map),
-            bump: Some(ctx.bumps.data),
-        };
+        Ok(())
+    }
+}
 
-        init_handler(signer.clone(), data.clone());
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
 
-        dot::program::Data::store(data.account);
+        pub(crate) use $name;
+    };
+}
 
-        return Ok(());
-    }
+pub trait Loadable {
+    type Loaded;
 
-    #[derive(Accounts)]
-    pub struct TestStoredMutables<'info> {
-        #[account(mut)]
-        pub signer: Signer<'info>,
-        #[account(mut)]
-        pub data: Box<Account<'info, dot::program::Data>>,
-    }
+    fn load(stored: Self) -> Self::Loaded;
 
-    pub fn test_stored_mutables(ctx: Context<TestStoredMutables>) -> Result<()> {
-        let mut programs = HashMap::new();
-        let programs_map = ProgramsMap(programs);
-        let signer = SeahorseSigner {
-            account: &ctx.accounts.signer,
-            programs: &programs_map,
-        };
+    fn store(loaded: Self::Loaded) -> Self;
+}
 
-        let data = dot::program::Data::load(&mut ctx.accounts.data, &programs_map);
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
 
-        test_stored_mutables_handler(signer.clone(), data.clone());
+pub(crate) use Loaded;
 
-        dot::program::Data::store(data);
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
 
-        return Ok(());
-    }
+        $lval = temp;
+    }};
 }
 
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
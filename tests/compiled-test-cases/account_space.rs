@@ -0,0 +1,763 @@
+// ===== dot/mod.rs =====
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Badge {
+    pub label: String,
+    pub level: u8,
+}
+
+impl Badge {
+    pub const __SIZE__: usize =(4 + 16) + std::mem::size_of::<u8>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedBadge {
+    pub label: String,
+    pub level: u8,
+}
+
+impl Loadable for Badge {
+    type Loaded = LoadedBadge;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            label: stored.label,
+            level: stored.level,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            label: loaded.label.clone(),
+            level: loaded.level,
+        }
+    }
+}
+
+#[account]
+#[derive(Debug)]
+pub struct Profile {
+    pub owner: Pubkey,
+    pub name: String,
+    pub scores: Vec<u64>,
+    pub badges: Vec<Badge>,
+    pub rank: Rank,
+    pub referrer: Option<Pubkey>,
+    pub history: [u16; 8],
+}
+
+impl Profile {
+    pub const __SPACE__: usize = 8
+        + std::mem::size_of::<Pubkey>()
+        +(4 + 32)
+        +(4 + 10 * std::mem::size_of::<u64>())
+        +(4 + 4 * Badge::__SIZE__)
+        + Rank::__SIZE__
+        +(1 + std::mem::size_of::<Pubkey>())
+        +(8 * std::mem::size_of::<u16>());
+}
+
+impl<'info, 'entrypoint> Profile {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedProfile<'info, 'entrypoint>> {
+        let owner = account.owner.clone();
+        let name = account.name.clone();
+        let scores = Mutable::new(
+            account
+                .scores
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+        );
+
+        let badges = Mutable::new(
+            account
+                .badges
+                .clone()
+                .into_iter()
+                .map(|element| Mutable::new(Badge::load(element)))
+                .collect(),
+        );
+
+        let rank = account.rank.clone();
+        let referrer = account.referrer.clone();
+        let history = Mutable::new(account.history.clone().map(|element| element));
+
+        Mutable::new(LoadedProfile {
+            __account__: account,
+            __programs__: programs_map,
+            owner,
+            name,
+            scores,
+            badges,
+            rank,
+            referrer,
+            history,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedProfile>) {
+        let mut loaded = loaded.borrow_mut();
+        let owner = loaded.owner.clone();
+
+        loaded.__account__.owner = owner;
+
+        let name = loaded.name.clone();
+
+        loaded.__account__.name = name;
+
+        let scores = loaded
+            .scores
+            .clone()
+            .borrow()
+            .clone()
+            .into_iter()
+            .map(|element| element)
+            .collect();
+
+        loaded.__account__.scores = scores;
+
+        let badges = loaded
+            .badges
+            .clone()
+            .borrow()
+            .clone()
+            .into_iter()
+            .map(|element| Badge::store(element.borrow().clone()))
+            .collect();
+
+        loaded.__account__.badges = badges;
+
+        let rank = loaded.rank.clone();
+
+        loaded.__account__.rank = rank;
+
+        let referrer = loaded.referrer.clone();
+
+        loaded.__account__.referrer = referrer;
+
+        let history = loaded
+            .history
+            .clone()
+            .borrow()
+            .clone()
+            .map(|element| element);
+
+        loaded.__account__.history = history;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedProfile<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, Profile>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub owner: Pubkey,
+    pub name: String,
+    pub scores: Mutable<Vec<u64>>,
+    pub badges: Mutable<Vec<Mutable<Loaded!(Badge)>>>,
+    pub rank: Rank,
+    pub referrer: Option<Pubkey>,
+    pub history: Mutable<[u16; 8]>,
+}
+
+#[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize, Copy)]
+pub enum Rank {
+    Member,
+    Admin,
+}
+
+impl Default for Rank {
+    fn default() -> Self {
+        Rank::Member
+    }
+}
+
+impl Rank {
+    pub const __SIZE__: usize = 1 + max_space(&[0, 0]);
+}
+
+pub fn extend_profile_handler<'info>(
+    mut owner: SeahorseSigner<'info, '_>,
+    mut profile: Mutable<LoadedProfile<'info, '_>>,
+    mut extra: u64,
+) -> Result<()> {
+    solana_program::msg!(
+        "{}",
+        format!(
+            "Profile is now {} bytes",((Profile::__SPACE__ as u64) + extra)
+        )
+    );
+
+    Ok(())
+}
+
+pub fn init_profile_handler<'info>(
+    mut owner: SeahorseSigner<'info, '_>,
+    mut profile: Empty<Mutable<LoadedProfile<'info, '_>>>,
+    mut name: String,
+) -> Result<()> {
+    let mut profile = profile.account.clone();
+
+    assign!(profile.borrow_mut().owner, owner.key());
+
+    assign!(profile.borrow_mut().name, name);
+
+    Ok(())
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod account_space {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    #[instruction(extra: u64)]
+    pub struct ExtendProfile<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut, realloc =((Profile::__SPACE__ as u64) + extra) as usize, realloc::payer = owner, realloc::zero = false)]
+        pub profile: Box<Account<'info, dot::program::Profile>>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn extend_profile(ctx: Context<ExtendProfile>, extra: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let profile = dot::program::Profile::load(&mut ctx.accounts.profile, &programs_map);
+
+        extend_profile_handler(owner.clone(), profile.clone(), extra)?;
+
+        dot::program::Profile::store(profile);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(name: String)]
+    pub struct InitProfile<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(init, space = dot::program::Profile::__SPACE__, payer = owner, seeds = ["profile".as_bytes().as_ref(), owner.key().as_ref()], bump)]
+        pub profile: Box<Account<'info, dot::program::Profile>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+    }
+
+    pub fn init_profile(ctx: Context<InitProfile>, name: String) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let profile = Empty {
+            account: dot::program::Profile::load(&mut ctx.accounts.profile, &programs_map),
+            bump: Some(ctx.bumps.profile),
+        };
+
+        init_profile_handler(owner.clone(), profile.clone(), name)?;
+
+        dot::program::Profile::store(profile.account);
+
+        return Ok(());
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
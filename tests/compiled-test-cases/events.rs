@@ -0,0 +1,974 @@
+// ===== dot/mod.rs =====
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Fill {
+    pub price: u64,
+    pub size: u64,
+}
+
+impl Fill {
+    pub const __SIZE__: usize = std::mem::size_of::<u64>() + std::mem::size_of::<u64>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedFill {
+    pub price: u64,
+    pub size: u64,
+}
+
+impl Mutable<LoadedFill> {
+    pub fn __init__(&self, mut price: u64, mut size: u64) -> Result<()> {
+        assign!(self.borrow_mut().price, price);
+
+        assign!(self.borrow_mut().size, size);
+
+        Ok(())
+    }
+}
+
+impl LoadedFill {
+    pub fn __new__(price: u64, size: u64) -> Result<Mutable<Self>> {
+        let obj = Mutable::new(LoadedFill::default());
+
+        obj.__init__(price, size)?;
+
+        return Ok(obj);
+    }
+}
+
+impl Loadable for Fill {
+    type Loaded = LoadedFill;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            price: stored.price,
+            size: stored.size,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            price: loaded.price,
+            size: loaded.size,
+        }
+    }
+}
+
+#[account]
+#[derive(Debug)]
+pub struct Market {
+    pub last_trade: Trade,
+}
+
+impl Market {
+    pub const __SPACE__: usize = 8 + Trade::__SIZE__;
+}
+
+impl<'info, 'entrypoint> Market {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedMarket<'info, 'entrypoint>> {
+        let last_trade = Mutable::new(Trade::load(account.last_trade.clone()));
+
+        Mutable::new(LoadedMarket {
+            __account__: account,
+            __programs__: programs_map,
+            last_trade,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedMarket>) {
+        let mut loaded = loaded.borrow_mut();
+        let last_trade = Trade::store(loaded.last_trade.clone().borrow().clone());
+
+        loaded.__account__.last_trade = last_trade;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedMarket<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, Market>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub last_trade: Mutable<Loaded!(Trade)>,
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct OrderFilled {
+    pub trader: Pubkey,
+    pub side: Side,
+    pub first: Fill,
+    pub fills: Vec<Fill>,
+    pub prices: Vec<u64>,
+    pub memo: Option<String>,
+}
+
+impl OrderFilled {
+    pub const __SIZE__: usize = std::mem::size_of::<Pubkey>()
+        + Side::__SIZE__
+        + Fill::__SIZE__
+        + std::mem::size_of::<Vec<Fill>>()
+        + std::mem::size_of::<Vec<u64>>()
+        +(1 + std::mem::size_of::<String>());
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedOrderFilled {
+    pub trader: Pubkey,
+    pub side: Side,
+    pub first: Mutable<Loaded!(Fill)>,
+    pub fills: Mutable<Vec<Mutable<Loaded!(Fill)>>>,
+    pub prices: Mutable<Vec<u64>>,
+    pub memo: Option<String>,
+}
+
+impl Mutable<LoadedOrderFilled> {
+    pub fn __init__(
+        &self,
+        mut trader: Pubkey,
+        mut side: Side,
+        mut first: Mutable<Loaded!(Fill)>,
+        mut fills: Mutable<Vec<Mutable<Loaded!(Fill)>>>,
+        mut prices: Mutable<Vec<u64>>,
+        mut memo: Option<String>,
+    ) -> Result<()> {
+        assign!(self.borrow_mut().trader, trader);
+
+        assign!(self.borrow_mut().side, side);
+
+        assign!(self.borrow_mut().first, first);
+
+        assign!(self.borrow_mut().fills, fills);
+
+        assign!(self.borrow_mut().prices, prices);
+
+        assign!(self.borrow_mut().memo, memo);
+
+        Ok(())
+    }
+
+    fn __emit__(&self) {
+        let e = self.borrow();
+
+        emit!(OrderFilled {
+            trader: e.trader.clone(),
+            side: e.side.clone(),
+            first: Fill::store(e.first.clone().borrow().clone()),
+            fills: e
+                .fills
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| Fill::store(element.borrow().clone()))
+                .collect(),
+            prices: e
+                .prices
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+            memo: e.memo.clone()
+        })
+    }
+
+    #[cfg(feature = "event-cpi")]
+    fn __emit_cpi__(&self, event_authority: &EventAuthority) -> Result<()> {
+        let e = self.borrow();
+
+        event_authority.emit(OrderFilled {
+            trader: e.trader.clone(),
+            side: e.side.clone(),
+            first: Fill::store(e.first.clone().borrow().clone()),
+            fills: e
+                .fills
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| Fill::store(element.borrow().clone()))
+                .collect(),
+            prices: e
+                .prices
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+            memo: e.memo.clone(),
+        })
+    }
+}
+
+impl LoadedOrderFilled {
+    pub fn __new__(
+        trader: Pubkey,
+        side: Side,
+        first: Mutable<Loaded!(Fill)>,
+        fills: Mutable<Vec<Mutable<Loaded!(Fill)>>>,
+        prices: Mutable<Vec<u64>>,
+        memo: Option<String>,
+    ) -> Result<Mutable<Self>> {
+        let obj = Mutable::new(LoadedOrderFilled::default());
+
+        obj.__init__(trader, side, first, fills, prices, memo)?;
+
+        return Ok(obj);
+    }
+}
+
+impl Loadable for OrderFilled {
+    type Loaded = LoadedOrderFilled;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            trader: stored.trader,
+            side: stored.side,
+            first: Mutable::new(Fill::load(stored.first)),
+            fills: Mutable::new(
+                stored
+                    .fills
+                    .into_iter()
+                    .map(|element| Mutable::new(Fill::load(element)))
+                    .collect(),
+            ),
+            prices: Mutable::new(stored.prices.into_iter().map(|element| element).collect()),
+            memo: stored.memo,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            trader: loaded.trader.clone(),
+            side: loaded.side.clone(),
+            first: Fill::store(loaded.first.clone().borrow().clone()),
+            fills: loaded
+                .fills
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| Fill::store(element.borrow().clone()))
+                .collect(),
+            prices: loaded
+                .prices
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+            memo: loaded.memo.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize, Copy)]
+pub enum Side {
+    BUY,
+    SELL,
+}
+
+impl Default for Side {
+    fn default() -> Self {
+        Side::BUY
+    }
+}
+
+impl Side {
+    pub const __SIZE__: usize = 1 + max_space(&[0, 0]);
+}
+
+#[event]
+#[derive(Clone, Debug)]
+pub struct Trade {
+    pub trader: Pubkey,
+    pub price: u64,
+}
+
+impl Trade {
+    pub const __SIZE__: usize = std::mem::size_of::<Pubkey>() + std::mem::size_of::<u64>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedTrade {
+    pub trader: Pubkey,
+    pub price: u64,
+}
+
+impl Mutable<LoadedTrade> {
+    pub fn __init__(&self, mut trader: Pubkey, mut price: u64) -> Result<()> {
+        assign!(self.borrow_mut().trader, trader);
+
+        assign!(self.borrow_mut().price, price);
+
+        Ok(())
+    }
+
+    fn __emit__(&self) {
+        let e = self.borrow();
+
+        emit!(Trade {
+            trader: e.trader.clone(),
+            price: e.price
+        })
+    }
+
+    #[cfg(feature = "event-cpi")]
+    fn __emit_cpi__(&self, event_authority: &EventAuthority) -> Result<()> {
+        let e = self.borrow();
+
+        event_authority.emit(Trade {
+            trader: e.trader.clone(),
+            price: e.price,
+        })
+    }
+}
+
+impl LoadedTrade {
+    pub fn __new__(trader: Pubkey, price: u64) -> Result<Mutable<Self>> {
+        let obj = Mutable::new(LoadedTrade::default());
+
+        obj.__init__(trader, price)?;
+
+        return Ok(obj);
+    }
+}
+
+impl Loadable for Trade {
+    type Loaded = LoadedTrade;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            trader: stored.trader,
+            price: stored.price,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            trader: loaded.trader.clone(),
+            price: loaded.price,
+        }
+    }
+}
+
+pub fn fill_order_handler<'info>(
+    mut trader: SeahorseSigner<'info, '_>,
+    mut price: u64,
+    mut size: u64,
+    __event_authority__: EventAuthority<'info>,
+) -> Result<()> {
+    let mut first = <Loaded!(Fill)>::__new__(price.clone(), size.clone())?;
+    let mut event = <Loaded!(OrderFilled)>::__new__(
+        trader.key(),
+        Side::BUY,
+        first.clone(),
+        Mutable::new(vec![
+            first.clone(),
+            <Loaded!(Fill)>::__new__((price + 1), size.clone())?,
+        ]),
+        Mutable::new(vec![price.clone(),(price + 1)]),
+        None,
+    )?;
+
+    event.__emit_cpi__(&__event_authority__)?;
+
+    Ok(())
+}
+
+pub fn log_order_handler<'info>(
+    mut trader: SeahorseSigner<'info, '_>,
+    mut price: u64,
+) -> Result<()> {
+    let mut fill = <Loaded!(Fill)>::__new__(price.clone(), 1)?;
+    let mut event = <Loaded!(OrderFilled)>::__new__(
+        trader.key(),
+        Side::SELL,
+        fill.clone(),
+        Mutable::new(vec![fill.clone()]),
+        Mutable::new(vec![price.clone()]),
+        Some("logged".to_string()),
+    )?;
+
+    event.__emit__();
+
+    Ok(())
+}
+
+pub fn record_trade_handler<'info>(
+    mut trader: SeahorseSigner<'info, '_>,
+    mut market: Mutable<LoadedMarket<'info, '_>>,
+    mut price: u64,
+) -> Result<()> {
+    let mut trade = <Loaded!(Trade)>::__new__(trader.key(), price.clone())?;
+
+    assign!(market.borrow_mut().last_trade, trade);
+
+    trade.__emit__();
+
+    Ok(())
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod events {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[event_cpi]
+    #[derive(Accounts)]
+    #[instruction(price: u64, size: u64)]
+    pub struct FillOrder<'info> {
+        #[account(mut)]
+        pub trader: Signer<'info>,
+    }
+
+    pub fn fill_order(ctx: Context<FillOrder>, price: u64, size: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let trader = SeahorseSigner {
+            account: &ctx.accounts.trader,
+            programs: &programs_map,
+        };
+
+        fill_order_handler(
+            trader.clone(),
+            price,
+            size,
+            EventAuthority {
+                account: ctx.accounts.event_authority.to_account_info(),
+                bump: ctx.bumps.event_authority,
+            },
+        )?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(price: u64)]
+    pub struct LogOrder<'info> {
+        #[account(mut)]
+        pub trader: Signer<'info>,
+    }
+
+    pub fn log_order(ctx: Context<LogOrder>, price: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let trader = SeahorseSigner {
+            account: &ctx.accounts.trader,
+            programs: &programs_map,
+        };
+
+        log_order_handler(trader.clone(), price)?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(price: u64)]
+    pub struct RecordTrade<'info> {
+        #[account(mut)]
+        pub trader: Signer<'info>,
+        #[account(mut)]
+        pub market: Box<Account<'info, dot::program::Market>>,
+    }
+
+    pub fn record_trade(ctx: Context<RecordTrade>, price: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let trader = SeahorseSigner {
+            account: &ctx.accounts.trader,
+            programs: &programs_map,
+        };
+
+        let market = dot::program::Market::load(&mut ctx.accounts.market, &programs_map);
+
+        record_trade_handler(trader.clone(), market.clone(), price)?;
+
+        dot::program::Market::store(market);
+
+        return Ok(());
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
@@ -0,0 +1,1521 @@
+// ===== dot/idl/legacy_vault.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Limits {
+    pub max_deposit: u64,
+    pub daily_withdrawals: Vec<u64>,
+}
+
+impl Limits {
+    pub const __SIZE__: usize = std::mem::size_of::<u64>() + std::mem::size_of::<Vec<u64>>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedLimits {
+    pub max_deposit: u64,
+    pub daily_withdrawals: Mutable<Vec<u64>>,
+}
+
+impl LoadedLimits {
+    pub fn __new__(
+        max_deposit: u64,
+        daily_withdrawals: Mutable<Vec<u64>>,
+    ) -> Result<Mutable<Self>> {
+        let obj = LoadedLimits {
+            max_deposit,
+            daily_withdrawals,
+        };
+
+        return Ok(Mutable::new(obj));
+    }
+}
+
+impl Loadable for Limits {
+    type Loaded = LoadedLimits;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            max_deposit: stored.max_deposit,
+            daily_withdrawals: Mutable::new(
+                stored
+                    .daily_withdrawals
+                    .into_iter()
+                    .map(|element| element)
+                    .collect(),
+            ),
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            max_deposit: loaded.max_deposit,
+            daily_withdrawals: loaded
+                .daily_withdrawals
+                .clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VaultState {
+    pub owner: Pubkey,
+    pub limits: Limits,
+}
+
+impl VaultState {
+    pub const __SIZE__: usize = std::mem::size_of::<Pubkey>() + Limits::__SIZE__;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedVaultState {
+    pub owner: Pubkey,
+    pub limits: Mutable<Loaded!(Limits)>,
+}
+
+impl LoadedVaultState {
+    pub fn __new__(owner: Pubkey, limits: Mutable<Loaded!(Limits)>) -> Result<Mutable<Self>> {
+        let obj = LoadedVaultState { owner, limits };
+
+        return Ok(Mutable::new(obj));
+    }
+}
+
+impl Loadable for VaultState {
+    type Loaded = LoadedVaultState;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            owner: stored.owner,
+            limits: Mutable::new(Limits::load(stored.limits)),
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            owner: loaded.owner.clone(),
+            limits: Limits::store(loaded.limits.clone().borrow().clone()),
+        }
+    }
+}
+
+// ===== dot/idl/mod.rs =====
+
+pub mod legacy_vault;
+
+pub mod my_dex;
+
+// ===== dot/idl/my_dex.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Config {
+    pub fee: u16,
+    pub type_: PoolType,
+    pub oracle: Option<Pubkey>,
+}
+
+impl Config {
+    pub const __SIZE__: usize =
+        std::mem::size_of::<u16>() + PoolType::__SIZE__ +(1 + std::mem::size_of::<Pubkey>());
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedConfig {
+    pub fee: u16,
+    pub type_: PoolType,
+    pub oracle: Option<Pubkey>,
+}
+
+impl LoadedConfig {
+    pub fn __new__(fee: u16, type_: PoolType, oracle: Option<Pubkey>) -> Result<Mutable<Self>> {
+        let obj = LoadedConfig { fee, type_, oracle };
+
+        return Ok(Mutable::new(obj));
+    }
+}
+
+impl Loadable for Config {
+    type Loaded = LoadedConfig;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            fee: stored.fee,
+            type_: stored.type_,
+            oracle: stored.oracle,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            fee: loaded.fee,
+            type_: loaded.type_.clone(),
+            oracle: loaded.oracle.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize)]
+pub enum FeeTier {
+    Free,
+    Flat(u64),
+    Stepped(u64, u16),
+    Custom { authority: Pubkey, bps: u16 },
+}
+
+impl Default for FeeTier {
+    fn default() -> Self {
+        FeeTier::Free
+    }
+}
+
+impl FeeTier {
+    pub const __SIZE__: usize = 1 + max_space(&[
+        0,
+        std::mem::size_of::<u64>(),
+        std::mem::size_of::<u64>() + std::mem::size_of::<u16>(),
+        std::mem::size_of::<Pubkey>() + std::mem::size_of::<u16>(),
+    ]);
+}
+
+impl FeeTier {
+    pub fn flat(&self) -> u64 {
+        match self {
+            Self::Flat(field_0) => field_0.clone(),
+            _ => panic!("FeeTier is not FeeTier.Flat"),
+        }
+    }
+
+    pub fn stepped(&self) ->(u64, u16) {
+        match self {
+            Self::Stepped(field_0, field_1) =>(field_0.clone(), field_1.clone()),
+            _ => panic!("FeeTier is not FeeTier.Stepped"),
+        }
+    }
+
+    pub fn custom(&self) ->(Pubkey, u16) {
+        match self {
+            Self::Custom { authority, bps } =>(authority.clone(), bps.clone()),
+            _ => panic!("FeeTier is not FeeTier.Custom"),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Pool {
+    pub config: Config,
+    pub reserves: [u64; 2],
+}
+
+impl Pool {
+    pub const __SIZE__: usize = Config::__SIZE__ +(2 * std::mem::size_of::<u64>());
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedPool {
+    pub config: Mutable<Loaded!(Config)>,
+    pub reserves: Mutable<[u64; 2]>,
+}
+
+impl LoadedPool {
+    pub fn __new__(
+        config: Mutable<Loaded!(Config)>,
+        reserves: Mutable<[u64; 2]>,
+    ) -> Result<Mutable<Self>> {
+        let obj = LoadedPool { config, reserves };
+
+        return Ok(Mutable::new(obj));
+    }
+}
+
+impl Loadable for Pool {
+    type Loaded = LoadedPool;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            config: Mutable::new(Config::load(stored.config)),
+            reserves: Mutable::new(stored.reserves.map(|element| element)),
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            config: Config::store(loaded.config.clone().borrow().clone()),
+            reserves: loaded
+                .reserves
+                .clone()
+                .borrow()
+                .clone()
+                .map(|element| element),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, AnchorSerialize, AnchorDeserialize, Copy)]
+pub enum PoolType {
+    ConstantProduct,
+    Stable,
+}
+
+impl Default for PoolType {
+    fn default() -> Self {
+        PoolType::ConstantProduct
+    }
+}
+
+impl PoolType {
+    pub const __SIZE__: usize = 1 + max_space(&[0, 0]);
+}
+
+// ===== dot/mod.rs =====
+
+pub mod idl;
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::dot::idl::{
+    legacy_vault::Limits,
+    my_dex::{Config, FeeTier, PoolType},
+};
+
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+pub fn configure_handler<'info>(
+    mut admin: SeahorseSigner<'info, '_>,
+    mut pool: UncheckedAccount<'info>,
+    mut dex: UncheckedAccount<'info>,
+    mut recipient: Pubkey,
+) -> Result<()> {
+    {
+        let account_infos = [
+            dex.clone().to_account_info(),
+            admin.clone().to_account_info(),
+            pool.clone().to_account_info(),
+        ];
+
+        let args:(i16, Pubkey, String) =((-5), recipient.clone(), "lower fees".to_string());
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                3u8, 61u8, 224u8, 194u8, 16u8, 226u8, 118u8, 152u8, 56u8, 251u8, 122u8, 109u8, 1u8,
+                241u8, 197u8, 180u8, 140u8, 214u8, 8u8, 49u8, 164u8, 29u8, 12u8, 92u8, 71u8, 24u8,
+                105u8, 0u8, 0u8, 0u8, 0u8, 0u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![18u8, 154u8, 24u8, 18u8, 237u8, 214u8, 19u8, 80u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        args.1.serialize(&mut data).unwrap();
+
+        args.2.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: false,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(&instruction, &account_infos, &[]).unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn deposit_handler<'info>(
+    mut owner: SeahorseSigner<'info, '_>,
+    mut vault_state: UncheckedAccount<'info>,
+    mut vault_program: UncheckedAccount<'info>,
+    mut vault_authority: UncheckedAccount<'info>,
+    __vault_authority_seeds__: Vec<Vec<u8>>,
+) -> Result<()> {
+    {
+        let account_infos = [
+            vault_program.clone().to_account_info(),
+            owner.clone().to_account_info(),
+            vault_state.clone().to_account_info(),
+            vault_authority.clone().to_account_info(),
+        ];
+
+        let args:(u64,) =(1000,);
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                218u8, 7u8, 92u8, 178u8, 255u8, 94u8, 198u8, 129u8, 118u8, 19u8, 222u8, 83u8, 11u8,
+                105u8, 42u8, 135u8, 53u8, 71u8, 119u8, 105u8, 218u8, 71u8, 67u8, 12u8, 189u8,
+                129u8, 84u8, 51u8, 92u8, 74u8, 131u8, 39u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![202u8, 39u8, 52u8, 211u8, 53u8, 20u8, 250u8, 88u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: account_infos[3].key(),
+                    is_signer: true,
+                    is_writable: false,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(
+            &instruction,
+            &account_infos,
+            &[Mutable::new(
+                __vault_authority_seeds__
+                    .iter()
+                    .map(|seed| seed.as_slice())
+                    .collect::<Vec<_>>(),
+            )
+            .borrow()
+            .as_slice()],
+        )
+        .unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn deposit_as_pda_handler<'info>(
+    mut owner: SeahorseSigner<'info, '_>,
+    mut vault_state: UncheckedAccount<'info>,
+    mut vault_program: UncheckedAccount<'info>,
+    mut vault_authority: UncheckedAccount<'info>,
+    __vault_authority_seeds__: Vec<Vec<u8>>,
+) -> Result<()> {
+    {
+        let account_infos = [
+            vault_program.clone().to_account_info(),
+            owner.clone().to_account_info(),
+            vault_state.clone().to_account_info(),
+            vault_authority.clone().to_account_info(),
+        ];
+
+        let args:(u64,) =(500,);
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                218u8, 7u8, 92u8, 178u8, 255u8, 94u8, 198u8, 129u8, 118u8, 19u8, 222u8, 83u8, 11u8,
+                105u8, 42u8, 135u8, 53u8, 71u8, 119u8, 105u8, 218u8, 71u8, 67u8, 12u8, 189u8,
+                129u8, 84u8, 51u8, 92u8, 74u8, 131u8, 39u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![202u8, 39u8, 52u8, 211u8, 53u8, 20u8, 250u8, 88u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: account_infos[3].key(),
+                    is_signer: true,
+                    is_writable: false,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(
+            &instruction,
+            &account_infos,
+            &[Mutable::new(
+                __vault_authority_seeds__
+                    .iter()
+                    .map(|seed| seed.as_slice())
+                    .collect::<Vec<_>>(),
+            )
+            .borrow()
+            .as_slice()],
+        )
+        .unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn limit_handler<'info>(
+    mut owner: SeahorseSigner<'info, '_>,
+    mut vault_state: UncheckedAccount<'info>,
+    mut vault_program: UncheckedAccount<'info>,
+    mut max_deposit: u64,
+) -> Result<()> {
+    {
+        let account_infos = [
+            vault_program.clone().to_account_info(),
+            owner.clone().to_account_info(),
+            vault_state.clone().to_account_info(),
+        ];
+
+        let args:(crate::dot::idl::legacy_vault::Limits,) =(crate::dot::idl::legacy_vault::Limits::store(
+                <Loaded!(Limits)>::__new__(
+                    max_deposit.clone(),
+                    Mutable::new(vec![(max_deposit / 2)]),
+                )?
+                .borrow()
+                .clone(),
+            ),);
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                218u8, 7u8, 92u8, 178u8, 255u8, 94u8, 198u8, 129u8, 118u8, 19u8, 222u8, 83u8, 11u8,
+                105u8, 42u8, 135u8, 53u8, 71u8, 119u8, 105u8, 218u8, 71u8, 67u8, 12u8, 189u8,
+                129u8, 84u8, 51u8, 92u8, 74u8, 131u8, 39u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![207u8, 50u8, 250u8, 67u8, 211u8, 33u8, 70u8, 91u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: false,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(&instruction, &account_infos, &[]).unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn reconfigure_handler<'info>(
+    mut admin: SeahorseSigner<'info, '_>,
+    mut pool: UncheckedAccount<'info>,
+    mut dex: UncheckedAccount<'info>,
+    mut fee: u16,
+    mut oracle: Pubkey,
+) -> Result<()> {
+    let mut config = <Loaded!(Config)>::__new__(fee.clone(), PoolType::Stable, Some(oracle))?;
+
+    if fee == 0 {
+        assign!(config.borrow_mut().oracle, None);
+    }
+
+    {
+        let account_infos = [
+            dex.clone().to_account_info(),
+            admin.clone().to_account_info(),
+            pool.clone().to_account_info(),
+        ];
+
+        let args:(
+            crate::dot::idl::my_dex::Config,
+            Vec<crate::dot::idl::my_dex::FeeTier>,
+        ) =(
+            crate::dot::idl::my_dex::Config::store(config.clone().borrow().clone()),
+            Mutable::new(vec![
+                FeeTier::Free,
+                FeeTier::Flat(<u64 as TryFrom<_>>::try_from(fee.clone()).unwrap()),
+                FeeTier::Custom {
+                    authority: admin.key(),
+                    bps: fee.clone(),
+                },
+            ])
+            .borrow()
+            .clone()
+            .into_iter()
+            .map(|element| element)
+            .collect(),
+        );
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                3u8, 61u8, 224u8, 194u8, 16u8, 226u8, 118u8, 152u8, 56u8, 251u8, 122u8, 109u8, 1u8,
+                241u8, 197u8, 180u8, 140u8, 214u8, 8u8, 49u8, 164u8, 29u8, 12u8, 92u8, 71u8, 24u8,
+                105u8, 0u8, 0u8, 0u8, 0u8, 0u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![108u8, 158u8, 154u8, 175u8, 212u8, 98u8, 52u8, 66u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        args.1.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: false,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(&instruction, &account_infos, &[]).unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn route_handler<'info>(
+    mut admin: SeahorseSigner<'info, '_>,
+    mut pool: UncheckedAccount<'info>,
+    mut dex: UncheckedAccount<'info>,
+    mut hop: Pubkey,
+) -> Result<()> {
+    let mut hops = Mutable::new(vec![hop.clone(), admin.key()]);
+
+    {
+        let account_infos = [
+            dex.clone().to_account_info(),
+            admin.clone().to_account_info(),
+            pool.clone().to_account_info(),
+        ];
+
+        let args:(Vec<Pubkey>, Vec<[u16; 2]>, Option<Pubkey>, [u8; 4], Vec<u8>) =(
+            hops.clone()
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+            Mutable::new(vec![Mutable::new([1, 2]), Mutable::new([3, 4])])
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element.borrow().clone().map(|element| element))
+                .collect(),
+            None,
+            Mutable::new([0, 1, 2, 3])
+                .borrow()
+                .clone()
+                .map(|element| element),
+            Mutable::new(vec![1, 2, 3])
+                .borrow()
+                .clone()
+                .into_iter()
+                .map(|element| element)
+                .collect(),
+        );
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                3u8, 61u8, 224u8, 194u8, 16u8, 226u8, 118u8, 152u8, 56u8, 251u8, 122u8, 109u8, 1u8,
+                241u8, 197u8, 180u8, 140u8, 214u8, 8u8, 49u8, 164u8, 29u8, 12u8, 92u8, 71u8, 24u8,
+                105u8, 0u8, 0u8, 0u8, 0u8, 0u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![51u8, 37u8, 154u8, 98u8, 231u8, 202u8, 113u8, 20u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        args.1.serialize(&mut data).unwrap();
+
+        args.2.serialize(&mut data).unwrap();
+
+        args.3.serialize(&mut data).unwrap();
+
+        args.4.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: false,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(&instruction, &account_infos, &[]).unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn trade_handler<'info>(
+    mut user: SeahorseSigner<'info, '_>,
+    mut pool: UncheckedAccount<'info>,
+    mut dex: UncheckedAccount<'info>,
+    mut token_program: UncheckedAccount<'info>,
+    mut amount: u64,
+) -> Result<()> {
+    {
+        let account_infos = [
+            dex.clone().to_account_info(),
+            user.clone().to_account_info(),
+            pool.clone().to_account_info(),
+            token_program.clone().to_account_info(),
+        ];
+
+        let args:(u64, u64, bool) =(amount.clone(),((amount * 99) / 100), true);
+
+        require_keys_eq!(
+            account_infos[0].key(),
+            Pubkey::new_from_array([
+                3u8, 61u8, 224u8, 194u8, 16u8, 226u8, 118u8, 152u8, 56u8, 251u8, 122u8, 109u8, 1u8,
+                241u8, 197u8, 180u8, 140u8, 214u8, 8u8, 49u8, 164u8, 29u8, 12u8, 92u8, 71u8, 24u8,
+                105u8, 0u8, 0u8, 0u8, 0u8, 0u8
+            ]),
+            anchor_lang::error::ErrorCode::InvalidProgramId
+        );
+
+        let mut data = vec![248u8, 198u8, 158u8, 145u8, 225u8, 117u8, 135u8, 200u8];
+
+        args.0.serialize(&mut data).unwrap();
+
+        args.1.serialize(&mut data).unwrap();
+
+        args.2.serialize(&mut data).unwrap();
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: account_infos[0].key(),
+            accounts: vec![
+                AccountMeta {
+                    pubkey: account_infos[1].key(),
+                    is_signer: true,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: account_infos[2].key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: account_infos[3].key(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+            ],
+            data,
+        };
+
+        solana_program::program::invoke_signed(&instruction, &account_infos, &[]).unwrap();
+    };
+
+    Ok(())
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod idl_cpi {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    #[instruction(recipient: Pubkey)]
+    pub struct Configure<'info> {
+        #[account(mut)]
+        pub admin: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub pool: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub dex: UncheckedAccount<'info>,
+    }
+
+    pub fn configure(ctx: Context<Configure>, recipient: Pubkey) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let admin = SeahorseSigner {
+            account: &ctx.accounts.admin,
+            programs: &programs_map,
+        };
+
+        let pool = &ctx.accounts.pool.clone();
+        let dex = &ctx.accounts.dex.clone();
+
+        configure_handler(admin.clone(), pool.clone(), dex.clone(), recipient)?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct Deposit<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub vault_state: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub vault_program: UncheckedAccount<'info>,
+        #[account(mut, seeds = ["authority".as_bytes().as_ref(), owner.key().as_ref()], bump)]
+        /// CHECK: This account is unchecked.
+        pub vault_authority: UncheckedAccount<'info>,
+    }
+
+    pub fn deposit(ctx: Context<Deposit>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let __vault_authority_seeds__ = {
+            let owner = &ctx.accounts.owner;
+
+            vec![
+                "authority".as_bytes().as_ref().to_vec(),
+                owner.key().as_ref().to_vec(),
+                vec![ctx.bumps.vault_authority],
+            ]
+        };
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let vault_state = &ctx.accounts.vault_state.clone();
+        let vault_program = &ctx.accounts.vault_program.clone();
+        let vault_authority = &ctx.accounts.vault_authority.clone();
+
+        deposit_handler(
+            owner.clone(),
+            vault_state.clone(),
+            vault_program.clone(),
+            vault_authority.clone(),
+            __vault_authority_seeds__,
+        )?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct DepositAsPda<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub vault_state: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub vault_program: UncheckedAccount<'info>,
+        #[account(mut, seeds = ["authority".as_bytes().as_ref(), owner.key().as_ref()], bump)]
+        /// CHECK: This account is unchecked.
+        pub vault_authority: UncheckedAccount<'info>,
+    }
+
+    pub fn deposit_as_pda(ctx: Context<DepositAsPda>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let __vault_authority_seeds__ = {
+            let owner = &ctx.accounts.owner;
+
+            vec![
+                "authority".as_bytes().as_ref().to_vec(),
+                owner.key().as_ref().to_vec(),
+                vec![ctx.bumps.vault_authority],
+            ]
+        };
+
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let vault_state = &ctx.accounts.vault_state.clone();
+        let vault_program = &ctx.accounts.vault_program.clone();
+        let vault_authority = &ctx.accounts.vault_authority.clone();
+
+        deposit_as_pda_handler(
+            owner.clone(),
+            vault_state.clone(),
+            vault_program.clone(),
+            vault_authority.clone(),
+            __vault_authority_seeds__,
+        )?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(max_deposit: u64)]
+    pub struct Limit<'info> {
+        #[account(mut)]
+        pub owner: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub vault_state: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub vault_program: UncheckedAccount<'info>,
+    }
+
+    pub fn limit(ctx: Context<Limit>, max_deposit: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let owner = SeahorseSigner {
+            account: &ctx.accounts.owner,
+            programs: &programs_map,
+        };
+
+        let vault_state = &ctx.accounts.vault_state.clone();
+        let vault_program = &ctx.accounts.vault_program.clone();
+
+        limit_handler(
+            owner.clone(),
+            vault_state.clone(),
+            vault_program.clone(),
+            max_deposit,
+        )?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(fee: u16, oracle: Pubkey)]
+    pub struct Reconfigure<'info> {
+        #[account(mut)]
+        pub admin: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub pool: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub dex: UncheckedAccount<'info>,
+    }
+
+    pub fn reconfigure(ctx: Context<Reconfigure>, fee: u16, oracle: Pubkey) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let admin = SeahorseSigner {
+            account: &ctx.accounts.admin,
+            programs: &programs_map,
+        };
+
+        let pool = &ctx.accounts.pool.clone();
+        let dex = &ctx.accounts.dex.clone();
+
+        reconfigure_handler(admin.clone(), pool.clone(), dex.clone(), fee, oracle)?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(hop: Pubkey)]
+    pub struct Route<'info> {
+        #[account(mut)]
+        pub admin: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub pool: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub dex: UncheckedAccount<'info>,
+    }
+
+    pub fn route(ctx: Context<Route>, hop: Pubkey) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let admin = SeahorseSigner {
+            account: &ctx.accounts.admin,
+            programs: &programs_map,
+        };
+
+        let pool = &ctx.accounts.pool.clone();
+        let dex = &ctx.accounts.dex.clone();
+
+        route_handler(admin.clone(), pool.clone(), dex.clone(), hop)?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(amount: u64)]
+    pub struct Trade<'info> {
+        #[account(mut)]
+        pub user: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub pool: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub dex: UncheckedAccount<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub token_program: UncheckedAccount<'info>,
+    }
+
+    pub fn trade(ctx: Context<Trade>, amount: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let user = SeahorseSigner {
+            account: &ctx.accounts.user,
+            programs: &programs_map,
+        };
+
+        let pool = &ctx.accounts.pool.clone();
+        let dex = &ctx.accounts.dex.clone();
+        let token_program = &ctx.accounts.token_program.clone();
+
+        trade_handler(
+            user.clone(),
+            pool.clone(),
+            dex.clone(),
+            token_program.clone(),
+            amount,
+        )?;
+
+        return Ok(());
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
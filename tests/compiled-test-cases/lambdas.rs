@@ -0,0 +1,898 @@
+// ===== dot/mod.rs =====
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[account]
+#[derive(Debug)]
+pub struct Auction {
+    pub bids: [Bid; 4],
+    pub leader: Pubkey,
+}
+
+impl Auction {
+    pub const __SPACE__: usize = 8 +(4 * Bid::__SIZE__) + std::mem::size_of::<Pubkey>();
+}
+
+impl<'info, 'entrypoint> Auction {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedAuction<'info, 'entrypoint>> {
+        let bids = Mutable::new(
+            account
+                .bids
+                .clone()
+                .map(|element| Mutable::new(Bid::load(element))),
+        );
+
+        let leader = account.leader.clone();
+
+        Mutable::new(LoadedAuction {
+            __account__: account,
+            __programs__: programs_map,
+            bids,
+            leader,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedAuction>) {
+        let mut loaded = loaded.borrow_mut();
+        let bids = loaded
+            .bids
+            .clone()
+            .borrow()
+            .clone()
+            .map(|element| Bid::store(element.borrow().clone()));
+
+        loaded.__account__.bids = bids;
+
+        let leader = loaded.leader.clone();
+
+        loaded.__account__.leader = leader;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedAuction<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, Auction>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub bids: Mutable<[Mutable<Loaded!(Bid)>; 4]>,
+    pub leader: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Bid {
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+impl Bid {
+    pub const __SIZE__: usize = std::mem::size_of::<Pubkey>() + std::mem::size_of::<u64>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedBid {
+    pub bidder: Pubkey,
+    pub amount: u64,
+}
+
+impl Mutable<LoadedBid> {
+    pub fn __init__(&self, mut bidder: Pubkey, mut amount: u64) -> Result<()> {
+        assign!(self.borrow_mut().bidder, bidder);
+
+        assign!(self.borrow_mut().amount, amount);
+
+        Ok(())
+    }
+}
+
+impl LoadedBid {
+    pub fn __new__(bidder: Pubkey, amount: u64) -> Result<Mutable<Self>> {
+        let obj = Mutable::new(LoadedBid::default());
+
+        obj.__init__(bidder, amount)?;
+
+        return Ok(obj);
+    }
+}
+
+impl Loadable for Bid {
+    type Loaded = LoadedBid;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            bidder: stored.bidder,
+            amount: stored.amount,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            bidder: loaded.bidder.clone(),
+            amount: loaded.amount,
+        }
+    }
+}
+
+#[error_code]
+pub enum BidError {
+    #[msg("This bid is too low")]
+    TooLow,
+}
+
+pub fn checked_weight(mut bid: Mutable<Loaded!(Bid)>, mut minimum: u64) -> Result<u64> {
+    if bid.borrow().amount < minimum {
+        return Err(error!(BidError::TooLow));
+    }
+
+    return Ok(weight(bid.clone())?);
+}
+
+pub fn settle_handler<'info>(
+    mut signer: SeahorseSigner<'info, '_>,
+    mut auction: Mutable<LoadedAuction<'info, '_>>,
+    mut minimum: u64,
+    mut descending: bool,
+) -> Result<()> {
+    let mut amounts = Mutable::new(((auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .map(|elem| {
+            let mut bid = elem.clone();
+
+            bid.borrow().amount
+        }))
+        .collect::<Vec<_>>(),
+    );
+
+    let mut valid = Mutable::new(((auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .filter(|elem| {
+            let mut bid = elem.clone();
+
+            bid.borrow().amount >= minimum
+        }))
+        .collect::<Vec<_>>(),
+    );
+
+    let mut weights = Mutable::new((valid
+            .borrow()
+            .iter()
+            .map(|elem| elem.clone())
+            .map(|elem| -> Result<_> {
+                Ok({
+                    let mut bid = elem.clone();
+
+                    weight(bid.clone())?
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter())
+        .collect::<Vec<_>>(),
+    );
+
+    assign!(
+        auction.borrow_mut().leader,(auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .map(|elem|(
+            {
+                let mut bid = elem.clone();
+
+                bid.borrow().amount
+            },
+            elem
+        ))
+        .min_by(|a, b| PartialOrd::partial_cmp(&b.0, &a.0).unwrap())
+        .unwrap()
+        .1
+        .borrow()
+        .bidder
+    );
+
+    let mut lowest = amounts
+        .borrow()
+        .iter()
+        .map(|elem| elem.clone())
+        .min_by(|a, b| PartialOrd::partial_cmp(a, b).unwrap())
+        .unwrap();
+
+    let mut smallest = vec![3, 1, 2]
+        .into_iter()
+        .map(|elem| {(
+                {
+                    let mut x = elem.clone();
+
+                    0 - x
+                },
+                elem,
+            )
+        })
+        .min_by(|a, b| PartialOrd::partial_cmp(&a.0, &b.0).unwrap())
+        .unwrap()
+        .1;
+
+    let mut ranked = Mutable::new({
+        let mut temp =(auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .map(|elem| {(
+                {
+                    let mut bid = elem.clone();
+
+                    bid.borrow().amount
+                },
+                elem,
+            )
+        })
+        .collect::<Vec<_>>();
+
+        temp.sort_by(|a, b| PartialOrd::partial_cmp(&b.0, &a.0).unwrap());
+
+        temp.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>()
+    });
+
+    let mut ordered = Mutable::new({
+        let reverse = descending.clone();
+        let mut temp = amounts
+            .borrow()
+            .iter()
+            .map(|elem| elem.clone())
+            .collect::<Vec<_>>();
+
+        temp.sort_by(|a, b| {
+            if reverse {
+                PartialOrd::partial_cmp(b, a).unwrap()
+            } else {
+                PartialOrd::partial_cmp(a, b).unwrap()
+            }
+        });
+
+        temp
+    });
+
+    let mut by_parity = Mutable::new({
+        let mut temp = amounts
+            .borrow()
+            .iter()
+            .map(|elem| elem.clone())
+            .map(|elem| {(
+                    {
+                        let mut amount = elem.clone();
+
+                        amount % 2
+                    },
+                    elem,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        temp.sort_by(|a, b| PartialOrd::partial_cmp(&a.0, &b.0).unwrap());
+
+        temp.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>()
+    });
+
+    solana_program::msg!(
+        "{:?} {} {} {} {:?} {:?}",
+        weights,
+        lowest,
+        smallest,(*ranked.borrow().index_wrapped(0.into())).borrow().amount,
+        ordered,
+        by_parity
+    );
+
+    let mut checked = Mutable::new(((auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .map(|elem| -> Result<_> {
+            Ok({
+                let mut bid = elem.clone();
+
+                checked_weight(bid.clone(), minimum.clone())?
+            })
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter())
+        .collect::<Vec<_>>(),
+    );
+
+    let mut heavy = Mutable::new(((auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .map(|elem| -> Result<_> {
+            Ok((
+                {
+                    let mut bid = elem.clone();
+
+                    weight(bid.clone())? > minimum
+                },
+                elem,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(keep, _)| *keep)
+        .map(|(_, elem)| elem))
+        .collect::<Vec<_>>(),
+    );
+
+    let mut by_weight = Mutable::new({
+        let mut temp =(auction
+            .borrow()
+            .bids
+            .borrow()
+            .iter()
+            .map(|element| element.clone()))
+        .map(|elem| -> Result<_> { Ok((weight.clone()(elem.clone())?, elem)) })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .collect::<Vec<_>>();
+
+        temp.sort_by(|a, b| PartialOrd::partial_cmp(&a.0, &b.0).unwrap());
+
+        temp.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>()
+    });
+
+    let mut heaviest =(auction
+        .borrow()
+        .bids
+        .borrow()
+        .iter()
+        .map(|element| element.clone()))
+    .map(|elem| -> Result<_> {
+        Ok((
+            {
+                let mut bid = elem.clone();
+
+                checked_weight(bid.clone(), minimum.clone())?
+            },
+            elem,
+        ))
+    })
+    .collect::<Result<Vec<_>>>()?
+    .into_iter()
+    .min_by(|a, b| PartialOrd::partial_cmp(&b.0, &a.0).unwrap())
+    .unwrap()
+    .1;
+
+    solana_program::msg!(
+        "{:?} {} {} {}",
+        checked,(heavy.borrow().len() as u64),(*by_weight.borrow().index_wrapped(0.into()))
+            .borrow()
+            .amount,
+        heaviest.borrow().amount
+    );
+
+    Ok(())
+}
+
+pub fn weight(mut bid: Mutable<Loaded!(Bid)>) -> Result<u64> {
+    return Ok(bid.borrow().amount * 2);
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod lambdas {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    #[instruction(minimum: u64, descending: bool)]
+    pub struct Settle<'info> {
+        #[account(mut)]
+        pub signer: Signer<'info>,
+        #[account(mut)]
+        pub auction: Box<Account<'info, dot::program::Auction>>,
+    }
+
+    pub fn settle(ctx: Context<Settle>, minimum: u64, descending: bool) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let signer = SeahorseSigner {
+            account: &ctx.accounts.signer,
+            programs: &programs_map,
+        };
+
+        let auction = dot::program::Auction::load(&mut ctx.accounts.auction, &programs_map);
+
+        settle_handler(signer.clone(), auction.clone(), minimum, descending)?;
+
+        dot::program::Auction::store(auction);
+
+        return Ok(());
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
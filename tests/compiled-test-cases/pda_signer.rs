@@ -0,0 +1,975 @@
+// ===== dot/mod.rs =====
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[account]
+#[derive(Debug)]
+pub struct Treasury {
+    pub bump: u8,
+    pub mint: Pubkey,
+}
+
+impl Treasury {
+    pub const __SPACE__: usize = 8 + std::mem::size_of::<u8>() + std::mem::size_of::<Pubkey>();
+}
+
+impl<'info, 'entrypoint> Treasury {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedTreasury<'info, 'entrypoint>> {
+        let bump = account.bump;
+        let mint = account.mint.clone();
+
+        Mutable::new(LoadedTreasury {
+            __account__: account,
+            __programs__: programs_map,
+            bump,
+            mint,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedTreasury>) {
+        let mut loaded = loaded.borrow_mut();
+        let bump = loaded.bump;
+
+        loaded.__account__.bump = bump;
+
+        let mint = loaded.mint.clone();
+
+        loaded.__account__.mint = mint;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedTreasury<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, Treasury>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub bump: u8,
+    pub mint: Pubkey,
+}
+
+pub fn burn_from_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut mint: SeahorseAccount<'info, '_, Mint>,
+    mut holder: SeahorseAccount<'info, '_, TokenAccount>,
+    mut treasury: Mutable<LoadedTreasury<'info, '_>>,
+) -> Result<()> {
+    token::burn(
+        CpiContext::new(
+            mint.programs.get("token_program"),
+            token::Burn {
+                mint: mint.to_account_info(),
+                authority: payer.clone().to_account_info(),
+                from: holder.clone().to_account_info(),
+            },
+        ),
+        1,
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+pub fn init_treasury_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut treasury: Empty<Mutable<LoadedTreasury<'info, '_>>>,
+    mut mint: Empty<SeahorseAccount<'info, '_, Mint>>,
+) -> Result<()> {
+    let mut bump = treasury.bump.unwrap();
+    let mut treasury = treasury.account.clone();
+    let mut mint = mint.account.clone();
+
+    assign!(treasury.borrow_mut().bump, bump);
+
+    assign!(treasury.borrow_mut().mint, mint.key());
+
+    Ok(())
+}
+
+pub fn notify_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut program: UncheckedAccount<'info>,
+    mut treasury: Mutable<LoadedTreasury<'info, '_>>,
+    __treasury_seeds__: Vec<Vec<u8>>,
+) -> Result<()> {
+    {
+        let program = program;
+        let cpi_accounts = Mutable::new(vec![CpiAccount {
+            account_info: treasury.borrow().__account__.to_account_info(),
+            is_writable: false,
+            is_signer: false,
+            seeds: Some(
+                Mutable::new(
+                    __treasury_seeds__
+                        .iter()
+                        .map(|seed| seed.as_slice())
+                        .collect::<Vec<_>>(),
+                )
+                .borrow()
+                .iter()
+                .map(|seed| Vec::from(*seed))
+                .collect(),
+            ),
+        }]);
+
+        let instruction = solana_program::instruction::Instruction {
+            program_id: program.key(),
+            data: Mutable::new(vec![0]).borrow().clone(),
+            accounts: cpi_accounts
+                .borrow()
+                .iter()
+                .map(
+                    |CpiAccount {
+                         account_info,
+                         is_signer,
+                         is_writable,
+                         ..
+                     }| AccountMeta {
+                        pubkey: account_info.key(),
+                        is_signer: *is_signer,
+                        is_writable: *is_writable,
+                    },
+                )
+                .collect(),
+        };
+
+        let account_infos = [program.to_account_info()]
+            .into_iter()
+            .chain(
+                cpi_accounts
+                    .borrow()
+                    .iter()
+                    .map(|CpiAccount { account_info, .. }| account_info.clone()),
+            )
+            .collect::<Vec<_>>();
+
+        let seeds0 = cpi_accounts
+            .borrow()
+            .iter()
+            .filter_map(|cpi_account| cpi_account.seeds.clone())
+            .collect::<Vec<_>>();
+
+        let seeds1 = seeds0
+            .iter()
+            .map(|seeds1| seeds1.iter().map(|seeds2| &seeds2[..]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let seeds2 = seeds1.iter().map(|seeds1| &seeds1[..]).collect::<Vec<_>>();
+
+        solana_program::program::invoke_signed(&instruction, account_infos.as_ref(), &seeds2[..])
+            .unwrap();
+    };
+
+    Ok(())
+}
+
+pub fn reward_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut mint: SeahorseAccount<'info, '_, Mint>,
+    mut to: SeahorseAccount<'info, '_, TokenAccount>,
+    mut amount: u64,
+    mut treasury: Mutable<LoadedTreasury<'info, '_>>,
+    __treasury_seeds__: Vec<Vec<u8>>,
+) -> Result<()> {
+    token::mint_to(
+        CpiContext::new_with_signer(
+            mint.programs.get("token_program"),
+            token::MintTo {
+                mint: mint.to_account_info(),
+                authority: treasury.borrow().__account__.to_account_info(),
+                to: to.clone().to_account_info(),
+            },
+            &[Mutable::new(
+                __treasury_seeds__
+                    .iter()
+                    .map(|seed| seed.as_slice())
+                    .collect::<Vec<_>>(),
+            )
+            .borrow()
+            .as_slice()],
+        ),
+        amount.clone(),
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+pub fn sweep_handler<'info>(
+    mut vault: SeahorseAccount<'info, '_, TokenAccount>,
+    mut to: SeahorseAccount<'info, '_, TokenAccount>,
+    mut vault_authority: UncheckedAccount<'info>,
+    __vault_authority_seeds__: Vec<Vec<u8>>,
+) -> Result<()> {
+    token::transfer(
+        CpiContext::new_with_signer(
+            vault.programs.get("token_program"),
+            token::Transfer {
+                from: vault.to_account_info(),
+                authority: vault_authority.clone().to_account_info(),
+                to: to.clone().to_account_info(),
+            },
+            &[Mutable::new(
+                __vault_authority_seeds__
+                    .iter()
+                    .map(|seed| seed.as_slice())
+                    .collect::<Vec<_>>(),
+            )
+            .borrow()
+            .as_slice()],
+        ),
+        vault.amount,
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod pda_signer {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    pub struct BurnFrom<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub mint: Box<Account<'info, Mint>>,
+        #[account(mut)]
+        pub holder: Box<Account<'info, TokenAccount>>,
+        #[account(mut)]
+        pub treasury: Box<Account<'info, dot::program::Treasury>>,
+        pub token_program: Program<'info, Token>,
+    }
+
+    pub fn burn_from(ctx: Context<BurnFrom>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "token_program",
+            ctx.accounts.token_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let mint = SeahorseAccount {
+            account: &ctx.accounts.mint,
+            programs: &programs_map,
+        };
+
+        let holder = SeahorseAccount {
+            account: &ctx.accounts.holder,
+            programs: &programs_map,
+        };
+
+        let treasury = dot::program::Treasury::load(&mut ctx.accounts.treasury, &programs_map);
+
+        burn_from_handler(
+            payer.clone(),
+            mint.clone(),
+            holder.clone(),
+            treasury.clone(),
+        )?;
+
+        dot::program::Treasury::store(treasury);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct InitTreasury<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(init, space = dot::program::Treasury::__SPACE__, payer = payer, seeds = ["treasury".as_bytes().as_ref(), payer.key().as_ref()], bump)]
+        pub treasury: Box<Account<'info, dot::program::Treasury>>,
+        #[account(init, payer = payer, seeds = ["mint".as_bytes().as_ref()], bump, mint::decimals = 6, mint::authority = treasury)]
+        pub mint: Box<Account<'info, Mint>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+        pub token_program: Program<'info, Token>,
+    }
+
+    pub fn init_treasury(ctx: Context<InitTreasury>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        programs.insert(
+            "token_program",
+            ctx.accounts.token_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let treasury = Empty {
+            account: dot::program::Treasury::load(&mut ctx.accounts.treasury, &programs_map),
+            bump: Some(ctx.bumps.treasury),
+        };
+
+        let mint = Empty {
+            account: SeahorseAccount {
+                account: &ctx.accounts.mint,
+                programs: &programs_map,
+            },
+            bump: Some(ctx.bumps.mint),
+        };
+
+        init_treasury_handler(payer.clone(), treasury.clone(), mint.clone())?;
+
+        dot::program::Treasury::store(treasury.account);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct Notify<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        /// CHECK: This account is unchecked.
+        pub program: UncheckedAccount<'info>,
+        #[account(mut, seeds = ["treasury".as_bytes().as_ref(), payer.key().as_ref()], bump = treasury.bump)]
+        pub treasury: Box<Account<'info, dot::program::Treasury>>,
+    }
+
+    pub fn notify(ctx: Context<Notify>) -> Result<()> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let __treasury_seeds__ = {
+            let payer = &ctx.accounts.payer;
+            let treasury = &ctx.accounts.treasury;
+
+            vec![
+                "treasury".as_bytes().as_ref().to_vec(),
+                payer.key().as_ref().to_vec(),
+                vec![treasury.bump],
+            ]
+        };
+
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let program = &ctx.accounts.program.clone();
+        let treasury = dot::program::Treasury::load(&mut ctx.accounts.treasury, &programs_map);
+
+        notify_handler(
+            payer.clone(),
+            program.clone(),
+            treasury.clone(),
+            __treasury_seeds__,
+        )?;
+
+        dot::program::Treasury::store(treasury);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(amount: u64)]
+    pub struct Reward<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub mint: Box<Account<'info, Mint>>,
+        #[account(mut)]
+        pub to: Box<Account<'info, TokenAccount>>,
+        #[account(mut, seeds = ["treasury".as_bytes().as_ref(), payer.key().as_ref()], bump = treasury.bump)]
+        pub treasury: Box<Account<'info, dot::program::Treasury>>,
+        pub token_program: Program<'info, Token>,
+    }
+
+    pub fn reward(ctx: Context<Reward>, amount: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "token_program",
+            ctx.accounts.token_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let __treasury_seeds__ = {
+            let payer = &ctx.accounts.payer;
+            let treasury = &ctx.accounts.treasury;
+
+            vec![
+                "treasury".as_bytes().as_ref().to_vec(),
+                payer.key().as_ref().to_vec(),
+                vec![treasury.bump],
+            ]
+        };
+
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let mint = SeahorseAccount {
+            account: &ctx.accounts.mint,
+            programs: &programs_map,
+        };
+
+        let to = SeahorseAccount {
+            account: &ctx.accounts.to,
+            programs: &programs_map,
+        };
+
+        let treasury = dot::program::Treasury::load(&mut ctx.accounts.treasury, &programs_map);
+
+        reward_handler(
+            payer.clone(),
+            mint.clone(),
+            to.clone(),
+            amount,
+            treasury.clone(),
+            __treasury_seeds__,
+        )?;
+
+        dot::program::Treasury::store(treasury);
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct Sweep<'info> {
+        #[account(mut)]
+        pub vault: Box<Account<'info, TokenAccount>>,
+        #[account(mut)]
+        pub to: Box<Account<'info, TokenAccount>>,
+        #[account(mut, seeds = ["vault".as_bytes().as_ref(), vault.mint.as_ref()], bump)]
+        /// CHECK: This account is unchecked.
+        pub vault_authority: UncheckedAccount<'info>,
+        pub token_program: Program<'info, Token>,
+    }
+
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "token_program",
+            ctx.accounts.token_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let __vault_authority_seeds__ = {
+            let vault = &ctx.accounts.vault;
+
+            vec![
+                "vault".as_bytes().as_ref().to_vec(),
+                vault.mint.as_ref().to_vec(),
+                vec![ctx.bumps.vault_authority],
+            ]
+        };
+
+        let vault = SeahorseAccount {
+            account: &ctx.accounts.vault,
+            programs: &programs_map,
+        };
+
+        let to = SeahorseAccount {
+            account: &ctx.accounts.to,
+            programs: &programs_map,
+        };
+
+        let vault_authority = &ctx.accounts.vault_authority.clone();
+
+        sweep_handler(
+            vault.clone(),
+            to.clone(),
+            vault_authority.clone(),
+            __vault_authority_seeds__,
+        )?;
+
+        return Ok(());
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
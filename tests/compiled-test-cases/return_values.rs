@@ -0,0 +1,760 @@
+// ===== dot/mod.rs =====
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+#[account]
+#[derive(Debug)]
+pub struct Pool {
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub owner: Option<Pubkey>,
+}
+
+impl Pool {
+    pub const __SPACE__: usize = 8
+        + std::mem::size_of::<u64>()
+        + std::mem::size_of::<u64>()
+        +(1 + std::mem::size_of::<Pubkey>());
+}
+
+impl<'info, 'entrypoint> Pool {
+    pub fn load(
+        account: &'entrypoint mut Box<Account<'info, Self>>,
+        programs_map: &'entrypoint ProgramsMap<'info>,
+    ) -> Mutable<LoadedPool<'info, 'entrypoint>> {
+        let reserve_a = account.reserve_a;
+        let reserve_b = account.reserve_b;
+        let owner = account.owner.clone();
+
+        Mutable::new(LoadedPool {
+            __account__: account,
+            __programs__: programs_map,
+            reserve_a,
+            reserve_b,
+            owner,
+        })
+    }
+
+    pub fn store(loaded: Mutable<LoadedPool>) {
+        let mut loaded = loaded.borrow_mut();
+        let reserve_a = loaded.reserve_a;
+
+        loaded.__account__.reserve_a = reserve_a;
+
+        let reserve_b = loaded.reserve_b;
+
+        loaded.__account__.reserve_b = reserve_b;
+
+        let owner = loaded.owner.clone();
+
+        loaded.__account__.owner = owner;
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadedPool<'info, 'entrypoint> {
+    pub __account__: &'entrypoint mut Box<Account<'info, Pool>>,
+    pub __programs__: &'entrypoint ProgramsMap<'info>,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub owner: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Reserves {
+    pub a: u64,
+    pub b: u64,
+}
+
+impl Reserves {
+    pub const __SIZE__: usize = std::mem::size_of::<u64>() + std::mem::size_of::<u64>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedReserves {
+    pub a: u64,
+    pub b: u64,
+}
+
+impl Mutable<LoadedReserves> {
+    pub fn __init__(&self, mut a: u64, mut b: u64) -> Result<()> {
+        assign!(self.borrow_mut().a, a);
+
+        assign!(self.borrow_mut().b, b);
+
+        Ok(())
+    }
+}
+
+impl LoadedReserves {
+    pub fn __new__(a: u64, b: u64) -> Result<Mutable<Self>> {
+        let obj = Mutable::new(LoadedReserves::default());
+
+        obj.__init__(a, b)?;
+
+        return Ok(obj);
+    }
+}
+
+impl Loadable for Reserves {
+    type Loaded = LoadedReserves;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            a: stored.a,
+            b: stored.b,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            a: loaded.a,
+            b: loaded.b,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapQuote {
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+impl SwapQuote {
+    pub const __SIZE__: usize = std::mem::size_of::<u64>() + std::mem::size_of::<u64>();
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadedSwapQuote {
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+impl Mutable<LoadedSwapQuote> {
+    pub fn __init__(&self, mut amount_out: u64, mut fee: u64) -> Result<()> {
+        assign!(self.borrow_mut().amount_out, amount_out);
+
+        assign!(self.borrow_mut().fee, fee);
+
+        Ok(())
+    }
+}
+
+impl LoadedSwapQuote {
+    pub fn __new__(amount_out: u64, fee: u64) -> Result<Mutable<Self>> {
+        let obj = Mutable::new(LoadedSwapQuote::default());
+
+        obj.__init__(amount_out, fee)?;
+
+        return Ok(obj);
+    }
+}
+
+impl Loadable for SwapQuote {
+    type Loaded = LoadedSwapQuote;
+
+    fn load(stored: Self) -> Self::Loaded {
+        Self::Loaded {
+            amount_out: stored.amount_out,
+            fee: stored.fee,
+        }
+    }
+
+    fn store(loaded: Self::Loaded) -> Self {
+        Self {
+            amount_out: loaded.amount_out,
+            fee: loaded.fee,
+        }
+    }
+}
+
+pub fn amount_out<'info>(
+    mut pool: Mutable<LoadedPool<'info, '_>>,
+    mut amount_in: u64,
+) -> Result<u64> {
+    return Ok((pool.borrow().reserve_b * amount_in) /(pool.borrow().reserve_a + amount_in));
+}
+
+pub fn owner_handler<'info>(mut pool: Mutable<LoadedPool<'info, '_>>) -> Result<Option<Pubkey>> {
+    return Ok(pool.borrow().owner);
+}
+
+pub fn quote_handler<'info>(
+    mut pool: Mutable<LoadedPool<'info, '_>>,
+    mut amount_in: u64,
+) -> Result<u64> {
+    return Ok(amount_out(pool.clone(), amount_in.clone())?);
+}
+
+pub fn quote_with_fee_handler<'info>(
+    mut pool: Mutable<LoadedPool<'info, '_>>,
+    mut amount_in: u64,
+) -> Result<Mutable<Loaded!(SwapQuote)>> {
+    let mut fee = amount_in / 100;
+
+    return Ok(<Loaded!(SwapQuote)>::__new__(
+        amount_out(pool.clone(),(amount_in - fee))?,
+        fee.clone(),
+    )?);
+}
+
+pub fn reserves_handler<'info>(
+    mut pool: Mutable<LoadedPool<'info, '_>>,
+) -> Result<Mutable<Loaded!(Reserves)>> {
+    return Ok(<Loaded!(Reserves)>::__new__(
+        pool.borrow().reserve_a.clone(),
+        pool.borrow().reserve_b.clone(),
+    )?);
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod return_values {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    pub struct Owner<'info> {
+        #[account(mut)]
+        pub pool: Box<Account<'info, dot::program::Pool>>,
+    }
+
+    pub fn owner(ctx: Context<Owner>) -> Result<Option<Pubkey>> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let pool = dot::program::Pool::load(&mut ctx.accounts.pool, &programs_map);
+        let __return__ = owner_handler(pool.clone())?;
+
+        dot::program::Pool::store(pool);
+
+        return Ok(__return__);
+    }
+
+    #[derive(Accounts)]
+    #[instruction(amount_in: u64)]
+    pub struct Quote<'info> {
+        #[account(mut)]
+        pub pool: Box<Account<'info, dot::program::Pool>>,
+    }
+
+    pub fn quote(ctx: Context<Quote>, amount_in: u64) -> Result<u64> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let pool = dot::program::Pool::load(&mut ctx.accounts.pool, &programs_map);
+        let __return__ = quote_handler(pool.clone(), amount_in)?;
+
+        dot::program::Pool::store(pool);
+
+        return Ok(__return__);
+    }
+
+    #[derive(Accounts)]
+    #[instruction(amount_in: u64)]
+    pub struct QuoteWithFee<'info> {
+        #[account(mut)]
+        pub pool: Box<Account<'info, dot::program::Pool>>,
+    }
+
+    pub fn quote_with_fee(ctx: Context<QuoteWithFee>, amount_in: u64) -> Result<SwapQuote> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let pool = dot::program::Pool::load(&mut ctx.accounts.pool, &programs_map);
+        let __return__ = quote_with_fee_handler(pool.clone(), amount_in)?;
+
+        dot::program::Pool::store(pool);
+
+        return Ok(SwapQuote::store(__return__.borrow().clone()));
+    }
+
+    #[derive(Accounts)]
+    pub struct Reserves<'info> {
+        #[account(mut)]
+        pub pool: Box<Account<'info, dot::program::Pool>>,
+    }
+
+    pub fn reserves(ctx: Context<Reserves>) -> Result<Reserves> {
+        let mut programs = HashMap::new();
+        let programs_map = ProgramsMap(programs);
+        let pool = dot::program::Pool::load(&mut ctx.accounts.pool, &programs_map);
+        let __return__ = reserves_handler(pool.clone())?;
+
+        dot::program::Pool::store(pool);
+
+        return Ok(Reserves::store(__return__.borrow().clone()));
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
@@ -0,0 +1,940 @@
+// ===== dot/mod.rs =====
+
+pub mod program;
+
+// ===== dot/program.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+use crate::{id, seahorse_util::*};
+use anchor_lang::{prelude::*, solana_program};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+pub fn close_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut account: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+    >,
+) -> Result<()> {
+    anchor_spl::token_interface::close_account(CpiContext::new(
+        account.programs.get("token_2022_program"),
+        anchor_spl::token_interface::CloseAccount {
+            account: account.to_account_info(),
+            destination: payer.clone().to_account_info(),
+            authority: payer.clone().to_account_info(),
+        },
+    ))
+    .unwrap();
+
+    Ok(())
+}
+
+pub fn init_accounts_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut mint: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+    >,
+    mut vault: Empty<
+        WithPrograms<
+            'info,
+            '_,
+            Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        >,
+    >,
+    mut wallet: Empty<
+        WithPrograms<
+            'info,
+            '_,
+            Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        >,
+    >,
+) -> Result<()> {
+    vault.account.clone();
+
+    wallet.account.clone();
+
+    Ok(())
+}
+
+pub fn init_mint_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut mint: Empty<WithPrograms<'info, '_, Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>>,
+    >,
+) -> Result<()> {
+    mint.account.clone();
+
+    Ok(())
+}
+
+pub fn mint_and_burn_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut mint: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+    >,
+    mut to: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+    >,
+    mut amount: u64,
+) -> Result<()> {
+    anchor_spl::token_interface::mint_to(
+        CpiContext::new(
+            mint.programs.get("token_2022_program"),
+            anchor_spl::token_interface::MintTo {
+                mint: mint.to_account_info(),
+                authority: payer.clone().to_account_info(),
+                to: to.clone().to_account_info(),
+            },
+        ),
+        amount.clone(),
+    )
+    .unwrap();
+
+    anchor_spl::token_interface::burn(
+        CpiContext::new(
+            mint.programs.get("token_2022_program"),
+            anchor_spl::token_interface::Burn {
+                mint: mint.to_account_info(),
+                authority: payer.clone().to_account_info(),
+                from: to.clone().to_account_info(),
+            },
+        ),(amount / 2),
+    )
+    .unwrap();
+
+    solana_program::msg!("{} {} {}", mint.supply, mint.decimals, to.amount);
+
+    Ok(())
+}
+
+pub fn send_handler<'info>(
+    mut payer: SeahorseSigner<'info, '_>,
+    mut mint: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+    >,
+    mut source: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+    >,
+    mut dest: WithPrograms<
+        'info,
+        '_,
+        Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+    >,
+    mut amount: u64,
+) -> Result<()> {
+    let mut fee = {
+        use anchor_spl::token_2022::spl_token_2022::{
+            extension::{
+                transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+            },
+            state::Mint,
+        };
+
+        let info = mint.to_account_info();
+        let data = info.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint>::unpack(&data)?;
+        let extension = state.get_extension::<TransferFeeConfig>()?;
+
+        extension
+            .calculate_epoch_fee(Clock::get()?.epoch, amount.clone())
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    };
+
+    if !(fee <= {
+        use anchor_spl::token_2022::spl_token_2022::{
+            extension::{
+                transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+            },
+            state::Mint,
+        };
+
+        let info = mint.to_account_info();
+        let data = info.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint>::unpack(&data)?;
+        let extension = state.get_extension::<TransferFeeConfig>()?;
+
+        u64::from(extension.get_epoch_fee(Clock::get()?.epoch).maximum_fee)
+    }) {
+        panic!("Fee too high");
+    }
+
+    solana_program::msg!("{}", {
+        use anchor_spl::token_2022::spl_token_2022::{
+            extension::{
+                transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions,
+            },
+            state::Mint,
+        };
+
+        let info = mint.to_account_info();
+        let data = info.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint>::unpack(&data)?;
+        let extension = state.get_extension::<TransferFeeConfig>()?;
+
+        u16::from(
+            extension
+                .get_epoch_fee(Clock::get()?.epoch)
+                .transfer_fee_basis_points,
+        )
+    });
+
+    let mut metadata = {
+        use anchor_spl::token_2022::spl_token_2022::{
+            extension::{
+                metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions,
+            },
+            state::Mint,
+        };
+
+        let info = mint.to_account_info();
+        let data = info.try_borrow_data()?;
+        let state = StateWithExtensions::<Mint>::unpack(&data)?;
+
+        match state.get_extension::<MetadataPointer>() {
+            Ok(extension) => Option::<Pubkey>::from(extension.metadata_address),
+            Err(_) => None,
+        }
+    };
+
+    if let Some(mut metadata) = metadata.clone() {
+        solana_program::msg!("{}", format!("Metadata: {:?}", metadata));
+    }
+
+    anchor_spl::token_interface::transfer_checked(
+        CpiContext::new(
+            source.programs.get("token_2022_program"),
+            anchor_spl::token_interface::TransferChecked {
+                from: source.to_account_info(),
+                mint: mint.clone().to_account_info(),
+                to: dest.clone().to_account_info(),
+                authority: payer.clone().to_account_info(),
+            },
+        ),(amount + fee),
+        mint.clone().decimals,
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+// ===== lib.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+pub mod dot;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+use dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+mod seahorse_util;
+
+use seahorse_util::*;
+
+#[program]
+mod token_2022 {
+    use super::*;
+    use seahorse_util::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Accounts)]
+    pub struct Close<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub account: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        pub token_2022_program: Interface<'info, anchor_spl::token_interface::TokenInterface>,
+    }
+
+    pub fn close(ctx: Context<Close>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "token_2022_program",
+            ctx.accounts.token_2022_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let account = WithPrograms {
+            account: &ctx.accounts.account,
+            programs: &programs_map,
+        };
+
+        close_handler(payer.clone(), account.clone())?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct InitAccounts<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+        #[account(init, payer = payer, seeds = ["vault".as_bytes().as_ref(), mint.key().as_ref()], bump, token::mint = mint, token::authority = payer, token::token_program = token_2022_program)]
+        pub vault: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        #[account(init, payer = payer, associated_token::mint = mint, associated_token::authority = payer, associated_token::token_program = token_2022_program)]
+        pub wallet: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        pub associated_token_program: Program<'info, AssociatedToken>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+        pub token_2022_program: Interface<'info, anchor_spl::token_interface::TokenInterface>,
+    }
+
+    pub fn init_accounts(ctx: Context<InitAccounts>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "associated_token_program",
+            ctx.accounts.associated_token_program.to_account_info(),
+        );
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        programs.insert(
+            "token_2022_program",
+            ctx.accounts.token_2022_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let mint = WithPrograms {
+            account: &ctx.accounts.mint,
+            programs: &programs_map,
+        };
+
+        let vault = Empty {
+            account: WithPrograms {
+                account: &ctx.accounts.vault,
+                programs: &programs_map,
+            },
+            bump: Some(ctx.bumps.vault),
+        };
+
+        let wallet = Empty {
+            account: WithPrograms {
+                account: &ctx.accounts.wallet,
+                programs: &programs_map,
+            },
+            bump: Some(ctx.bumps.wallet),
+        };
+
+        init_accounts_handler(payer.clone(), mint.clone(), vault.clone(), wallet.clone())?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    pub struct InitMint<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(init, payer = payer, seeds = ["mint".as_bytes().as_ref()], bump, mint::decimals = 6, mint::authority = payer, mint::token_program = token_2022_program)]
+        pub mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+        pub rent: Sysvar<'info, Rent>,
+        pub system_program: Program<'info, System>,
+        pub token_2022_program: Interface<'info, anchor_spl::token_interface::TokenInterface>,
+    }
+
+    pub fn init_mint(ctx: Context<InitMint>) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "system_program",
+            ctx.accounts.system_program.to_account_info(),
+        );
+
+        programs.insert(
+            "token_2022_program",
+            ctx.accounts.token_2022_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let mint = Empty {
+            account: WithPrograms {
+                account: &ctx.accounts.mint,
+                programs: &programs_map,
+            },
+            bump: Some(ctx.bumps.mint),
+        };
+
+        init_mint_handler(payer.clone(), mint.clone())?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(amount: u64)]
+    pub struct MintAndBurn<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+        #[account(mut)]
+        pub to: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        pub token_2022_program: Interface<'info, anchor_spl::token_interface::TokenInterface>,
+    }
+
+    pub fn mint_and_burn(ctx: Context<MintAndBurn>, amount: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "token_2022_program",
+            ctx.accounts.token_2022_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let mint = WithPrograms {
+            account: &ctx.accounts.mint,
+            programs: &programs_map,
+        };
+
+        let to = WithPrograms {
+            account: &ctx.accounts.to,
+            programs: &programs_map,
+        };
+
+        mint_and_burn_handler(payer.clone(), mint.clone(), to.clone(), amount)?;
+
+        return Ok(());
+    }
+
+    #[derive(Accounts)]
+    #[instruction(amount: u64)]
+    pub struct Send<'info> {
+        #[account(mut)]
+        pub payer: Signer<'info>,
+        #[account(mut)]
+        pub mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+        #[account(mut)]
+        pub source: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        #[account(mut)]
+        pub dest: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+        pub token_2022_program: Interface<'info, anchor_spl::token_interface::TokenInterface>,
+    }
+
+    pub fn send(ctx: Context<Send>, amount: u64) -> Result<()> {
+        let mut programs = HashMap::new();
+
+        programs.insert(
+            "token_2022_program",
+            ctx.accounts.token_2022_program.to_account_info(),
+        );
+
+        let programs_map = ProgramsMap(programs);
+        let payer = SeahorseSigner {
+            account: &ctx.accounts.payer,
+            programs: &programs_map,
+        };
+
+        let mint = WithPrograms {
+            account: &ctx.accounts.mint,
+            programs: &programs_map,
+        };
+
+        let source = WithPrograms {
+            account: &ctx.accounts.source,
+            programs: &programs_map,
+        };
+
+        let dest = WithPrograms {
+            account: &ctx.accounts.dest,
+            programs: &programs_map,
+        };
+
+        send_handler(
+            payer.clone(),
+            mint.clone(),
+            source.clone(),
+            dest.clone(),
+            amount,
+        )?;
+
+        return Ok(());
+    }
+}
+
+// ===== seahorse_util.rs =====
+
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(unused_mut)]
+
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token::{self, Mint, Token, TokenAccount},
+};
+
+
+use crate::dot::program::*;
+use std::{cell::RefCell, rc::Rc};
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    ops::{Deref, Index, IndexMut},
+};
+
+// TODO maybe hide the names better? wouldn't want any namespace collisions
+// Utility structs, functions, and macros to beautify the generated code a little.
+
+pub struct Mutable<T>(Rc<RefCell<T>>);
+
+impl<T> Mutable<T> {
+    pub fn new(obj: T) -> Self {
+        Self(Rc::new(RefCell::new(obj)))
+    }
+}
+
+impl<T> Clone for Mutable<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for Mutable<T> {
+    type Target = Rc<RefCell<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Mutable<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl<T: Default> Default for Mutable<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Arrays in zero-copy accounts are used in place instead of being wrapped in a `Mutable`. This
+/// lets them be borrowed the same way.
+pub trait InPlace {
+    fn borrow(&self) -> &Self {
+        self
+    }
+
+    fn borrow_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<T: Copy, const N: usize> InPlace for [T; N] {}
+
+/// Get the largest of several spaces - `Ord::max` can't be used in a const.
+pub const fn max_space(spaces: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < spaces.len() {
+        if spaces[i] > max {
+            max = spaces[i];
+        }
+
+        i += 1;
+    }
+
+    max
+}
+
+pub trait IndexWrapped {
+    type Output;
+
+    fn index_wrapped(&self, index: i128) -> &Self::Output;
+}
+
+pub trait IndexWrappedMut: IndexWrapped {
+    fn index_wrapped_mut(&mut self, index: i128) -> &mut <Self as IndexWrapped>::Output;
+}
+
+impl<T> IndexWrapped for Vec<T> {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T> IndexWrappedMut for Vec<T> {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += self.len() as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrapped for [T; N] {
+    type Output = T;
+
+    fn index_wrapped(&self, mut index: i128) -> &Self::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index(index)
+    }
+}
+
+impl<T, const N: usize> IndexWrappedMut for [T; N] {
+    fn index_wrapped_mut(&mut self, mut index: i128) -> &mut <Self as IndexWrapped>::Output {
+        if index < 0 {
+            index += N as i128;
+        }
+
+        let index: usize = index.try_into().unwrap();
+
+        self.index_mut(index)
+    }
+}
+
+/// Get the indices selected by a Python slice over a sequence of length `len`. Like in Python,
+/// negative indices count from the end and out-of-range bounds are clamped to the sequence.
+fn slice_indices(
+    len: usize,
+    start: Option<i128>,
+    stop: Option<i128>,
+    step: Option<i128>,
+) -> impl Iterator<Item = usize> {
+    let len = len as i128;
+    let step = step.unwrap_or(1);
+
+    if step == 0 {
+        panic!("slice step cannot be zero");
+    }
+
+    let (lower, upper) = if step > 0 { (0, len) } else { (-1, len - 1) };
+    let clamp = |index: i128| {
+        if index < 0 {
+            (index + len).max(lower)
+        } else {
+            index.min(upper)
+        }
+    };
+
+    let start = start.map_or(if step > 0 { lower } else { upper }, clamp);
+    let stop = stop.map_or(if step > 0 { upper } else { lower }, clamp);
+
+    let mut index = start;
+    std::iter::from_fn(move || {
+        if (step > 0 && index < stop) || (step < 0 && index > stop) {
+            let next = index;
+            index += step;
+
+            Some(next as usize)
+        } else {
+            None
+        }
+    })
+}
+
+pub trait SliceWrapped {
+    type Output;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output;
+}
+
+impl<T: Clone> SliceWrapped for [T] {
+    type Output = Vec<T>;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        slice_indices(self.len(), start, stop, step)
+            .map(|index| self[index].clone())
+            .collect()
+    }
+}
+
+impl SliceWrapped for str {
+    type Output = String;
+
+    fn slice_wrapped(
+        &self,
+        start: Option<i128>,
+        stop: Option<i128>,
+        step: Option<i128>,
+    ) -> Self::Output {
+        let chars = self.chars().collect::<Vec<_>>();
+
+        slice_indices(chars.len(), start, stop, step)
+            .map(|index| chars[index])
+            .collect()
+    }
+}
+
+pub trait RemoveKey<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V>;
+}
+
+/// Removing a missing key fails the instruction, like Python's `KeyError`.
+impl<K: Ord + Debug, V> RemoveKey<K, V> for BTreeMap<K, V> {
+    fn remove_key(&mut self, key: &K) -> Result<V> {
+        self.remove(key).ok_or_else(|| {
+            msg!("KeyError: {:?}", key);
+
+            ProgramError::InvalidArgument.into()
+        })
+    }
+}
+
+/// Insert a separator between every `size` digits of the integer part of a formatted number, like
+/// Python's `,` and `_` format options.
+pub fn group_digits(formatted: String, separator: char, size: usize) -> String {
+    let mut start = formatted.starts_with(['+', '-']) as usize;
+    if formatted[start..].starts_with("0x")
+        || formatted[start..].starts_with("0b")
+        || formatted[start..].starts_with("0o")
+    {
+        start += 2;
+    }
+
+    let end = start
+        + formatted[start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(formatted.len() - start);
+
+    let mut grouped = formatted[..start].to_string();
+    for (i, digit) in formatted[start..end].chars().enumerate() {
+        if i > 0 && (end - start - i) % size == 0 {
+            grouped.push(separator);
+        }
+
+        grouped.push(digit);
+    }
+    grouped.push_str(&formatted[end..]);
+
+    grouped
+}
+
+/// A signed integer that's formatted in hex, binary or octal as a sign followed by its magnitude,
+/// like Python does, instead of as its two's complement.
+pub struct SignedInt(pub i128);
+
+impl SignedInt {
+    fn pad(&self, f: &mut std::fmt::Formatter, prefix: &str, digits: String) -> std::fmt::Result {
+        f.pad_integral(self.0 >= 0, prefix, &digits)
+    }
+}
+
+impl std::fmt::LowerHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:x}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::UpperHex for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0x", format!("{:X}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Binary for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0b", format!("{:b}", self.0.unsigned_abs()))
+    }
+}
+
+impl std::fmt::Octal for SignedInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.pad(f, "0o", format!("{:o}", self.0.unsigned_abs()))
+    }
+}
+
+#[derive(Clone)]
+pub struct Empty<T: Clone> {
+    pub account: T,
+    pub bump: Option<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramsMap<'info>(pub HashMap<&'static str, AccountInfo<'info>>);
+
+impl<'info> ProgramsMap<'info> {
+    pub fn get(&self, name: &'static str) -> AccountInfo<'info> {
+        self.0.get(name).unwrap().clone()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WithPrograms<'info, 'entrypoint, A> {
+    pub account: &'entrypoint A,
+    pub programs: &'entrypoint ProgramsMap<'info>,
+}
+
+impl<'info, 'entrypoint, A> Deref for WithPrograms<'info, 'entrypoint, A> {
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.account
+    }
+}
+
+pub type SeahorseAccount<'info, 'entrypoint, A> =
+    WithPrograms<'info, 'entrypoint, Box<Account<'info, A>>>;
+
+pub type SeahorseSigner<'info, 'entrypoint> = WithPrograms<'info, 'entrypoint, Signer<'info>>;
+
+#[derive(Clone, Debug)]
+pub struct CpiAccount<'info> {
+    /// CHECK: CpiAccounts temporarily store AccountInfos.
+    pub account_info: AccountInfo<'info>,
+    pub is_writable: bool,
+    pub is_signer: bool,
+    pub seeds: Option<Vec<Vec<u8>>>,
+}
+
+/// The PDA that signs the CPIs an instruction makes to itself to emit events.
+#[derive(Clone, Debug)]
+pub struct EventAuthority<'info> {
+    pub account: AccountInfo<'info>,
+    pub bump: u8,
+}
+
+#[cfg(feature = "event-cpi")]
+impl<'info> EventAuthority<'info> {
+    /// Emit an event by putting it in the data of a CPI to this program, the same way that
+    /// Anchor's `emit_cpi!` does.
+    pub fn emit<E: anchor_lang::Event>(&self, event: E) -> Result<()> {
+        let data = anchor_lang::event::EVENT_IX_TAG_LE
+            .iter()
+            .copied()
+            .chain(event.data())
+            .collect::<Vec<u8>>();
+        let ix = solana_program::instruction::Instruction::new_with_bytes(
+            crate::ID,
+            &data,
+            vec![solana_program::instruction::AccountMeta::new_readonly(
+                *self.account.key,
+                true,
+            )],
+        );
+
+        solana_program::program::invoke_signed(
+            &ix,
+            &[self.account.clone()],
+            &[&[anchor_lang::event::EVENT_AUTHORITY_SEED, &[self.bump]]],
+        )?;
+
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! seahorse_const {($ name: ident, $ value: expr) => {
+        macro_rules! $name {() => {
+                $value
+            };
+        }
+
+        pub(crate) use $name;
+    };
+}
+
+pub trait Loadable {
+    type Loaded;
+
+    fn load(stored: Self) -> Self::Loaded;
+
+    fn store(loaded: Self::Loaded) -> Self;
+}
+
+macro_rules! Loaded {($ name: ty) => {
+        <$name as Loadable>::Loaded
+    };
+}
+
+pub(crate) use Loaded;
+
+#[macro_export]
+macro_rules! assign {($ lval: expr, $ rval: expr) => {{
+        let temp = $rval;
+
+        $lval = temp;
+    }};
+}
+
+#[macro_export]
+macro_rules! index_assign {($ lval: expr, $ idx: expr, $ rval: expr) => {
+        let temp_rval = $rval;
+        let temp_idx = $idx;
+
+        $lval[temp_idx] = temp_rval;
+    };
+}
+
+pub(crate) use assign;
+
+pub(crate) use index_assign;
+
+pub(crate) use seahorse_const;
+
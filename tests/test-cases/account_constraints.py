@@ -0,0 +1,69 @@
+# This test case checks that account params can declare Anchor constraints with their defaults (which
+# Python requires to come after the params without defaults)
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class VaultError(ErrorCode):
+    WrongOwner = 'This vault belongs to someone else'
+    Empty = 'This vault is empty'
+    NotAdmin = 'Only the admin can do this'
+
+
+class Config(Account):
+    admin: Pubkey
+    min_balance: u64
+
+
+class Vault(Account):
+    owner: Pubkey
+    balance: u64
+    limits: List[u64, 4]
+    tiers: Array[u64, 3]
+
+
+@instruction
+def withdraw(
+    owner: Signer,
+    config: Config,
+    amount: u64,
+    vault: Vault = (
+        has_one('owner', VaultError.WrongOwner),
+        constraint(vault.balance > config.min_balance, VaultError.Empty),
+    )
+):
+    vault.balance -= amount
+
+
+@instruction
+def configure(
+    min_balance: u64,
+    admin: Signer = address(config.admin, VaultError.NotAdmin),
+    config: Config = has_one('admin'),
+    vault: Vault = constraint(vault.balance >= min_balance)
+):
+    config.min_balance = min_balance
+
+
+@instruction
+def open_vault(
+    payer: Signer,
+    config: Config = address(payer.key()),
+    vault: Empty[Vault] = constraint(config.min_balance == 0)
+):
+    vault.init(payer=payer, seeds=['vault', payer])
+
+
+@instruction
+def withdraw_limited(
+    owner: Signer,
+    amount: u64,
+    vault: Vault = (
+        has_one('owner'),
+        constraint(vault.limits[0] >= amount),
+        constraint(vault.tiers[-1] > vault.limits[-1]),
+    )
+):
+    vault.balance -= amount
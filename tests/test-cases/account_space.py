@@ -0,0 +1,39 @@
+# This test case checks that account space is calculated from max-length annotations, and that
+# size() can get the space of an account type
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Rank(Enum):
+    Member = 1
+    Admin = 2
+
+
+class Badge:
+    label: Str[16]
+    level: u8
+
+
+class Profile(Account):
+    owner: Pubkey
+    name: Str[32]
+    scores: List[u64, 10]
+    badges: List[Badge, 4]
+    rank: Rank
+    referrer: Optional[Pubkey]
+    history: Array[u16, 8]
+
+
+@instruction
+def init_profile(owner: Signer, profile: Empty[Profile], name: str):
+    profile = profile.init(payer=owner, seeds=['profile', owner])
+    profile.owner = owner.key()
+    profile.name = name
+
+
+@instruction
+def extend_profile(owner: Signer, profile: Profile, extra: u64):
+    profile.realloc(size(Profile) + extra, owner)
+    print(f'Profile is now {size(Profile) + extra} bytes')
@@ -0,0 +1,33 @@
+# This test case checks that program accounts and token accounts can be closed
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Vault(Account):
+    owner: Pubkey
+    balance: u64
+
+
+@instruction
+def close_vault(owner: Signer, vault: Vault = has_one('owner')):
+    print(f'Closing vault with {vault.balance} tokens')
+    vault.close(to=owner)
+
+
+@instruction
+def close_token_account(owner: Signer, holder: TokenAccount, vault: Vault):
+    if holder.amount() == 0:
+        holder.close(to=owner, authority=owner)
+
+    vault.close(owner)
+
+
+@instruction
+def sweep_token_account(owner: Signer, holder: TokenAccount, target: TokenAccount):
+    # Closing in one branch doesn't stop the account from being used in the other
+    if holder.amount() == 0:
+        holder.close(to=owner, authority=owner)
+    else:
+        holder.transfer(authority=owner, to=target, amount=holder.amount())
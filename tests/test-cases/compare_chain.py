@@ -0,0 +1,34 @@
+# This test case checks that comparison chains evaluate each operand once
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+MAX = 1000
+
+
+class Counter(Account):
+    count: u64
+
+
+def bump(counter: Counter) -> u64:
+    counter.count += 1
+    return counter.count
+
+
+@instruction
+def check_range(signer: Signer, counter: Counter, amount: u64, low: u64, high: u64):
+    # Simple operands are repeated
+    assert 0 < amount <= MAX, 'amount out of range'
+    assert low <= counter.count < high < MAX, 'count out of range'
+
+    # Operands with side effects are only evaluated once
+    if low < bump(counter) <= high:
+        print('in range')
+
+    # Including the first operand, when a later one needs to be bound
+    x = bump(counter) < bump(counter) < high
+    print(x)
+
+    y = low == amount != high
+    print(y)
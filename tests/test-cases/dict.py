@@ -0,0 +1,50 @@
+from seahorse.prelude import *
+
+declare_id('4SEMJzX6o2YQNws7yrsfUdjJCR4B5Z3GyR2Pj7UgzDy2')
+
+# This test case checks that dicts can be used in instruction bodies and stored in accounts
+
+class Ledger(Account):
+    balances: Dict[Pubkey, u64, 16]
+    names: Dict[u8, Str[16], 8]
+
+
+@instruction
+def init(payer: Signer, ledger: Empty[Ledger]):
+    ledger.init(payer, seeds=['ledger'])
+
+
+@instruction
+def use_dicts(payer: Signer, ledger: Ledger, amount: u64):
+    # literals, indexing and assignment
+    scores = {1: 10, 2: 20}
+    scores[3] = 30
+    scores[1] += 5
+    x = scores[2]
+
+    # membership
+    if 3 in scores and 4 not in scores:
+        print(x)
+
+    # get, keys, values, items
+    y = scores.get(4, 0)
+    for k in scores.keys():
+        print(k)
+    total = sum(scores.values())
+    for (k, v) in scores.items():
+        print(k, v)
+
+    # iteration and len
+    for k in scores:
+        print(k)
+    print(len(scores), total, y)
+
+    # del
+    del scores[1]
+
+    # stored dicts
+    key = payer.key()
+    ledger.balances[key] = ledger.balances.get(key, 0) + amount
+    ledger.names[0] = 'zero'
+    if 'zero' in ledger.names.values():
+        del ledger.names[0]
@@ -0,0 +1,39 @@
+from seahorse.prelude import *
+
+declare_id('4SEMJzX6o2YQNws7yrsfUdjJCR4B5Z3GyR2Pj7UgzDy2')
+
+# This test case checks that enum variants can carry data, can be stored in accounts, and can be
+# checked and destructured
+
+class Order(Enum):
+    Empty = 0
+    Pending: Tuple[u64, Pubkey]
+    Cancelled: str
+
+    @dataclass
+    class Filled:
+        amount: u64
+        price: u64
+
+
+class Book(Account):
+    last: Order
+
+
+@instruction
+def place(signer: Signer, book: Book, amount: u64, price: u64):
+    order = Order.Pending(amount, signer.key())
+    if isinstance(book.last, Order.Empty):
+        book.last = order
+    elif isinstance(book.last, Order.Pending):
+        size, owner = book.last.Pending
+        book.last = Order.Filled(amount=size, price=price)
+    else:
+        book.last = Order.Cancelled('no room')
+
+    if isinstance(book.last, Order.Filled):
+        filled_amount, filled_price = book.last.Filled
+        print(filled_amount * filled_price)
+
+    if book.last == Order.Empty:
+        print(book.last)
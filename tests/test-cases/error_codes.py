@@ -0,0 +1,29 @@
+from seahorse.prelude import *
+
+declare_id('4SEMJzX6o2YQNws7yrsfUdjJCR4B5Z3GyR2Pj7UgzDy2')
+
+# This test case checks that custom error codes can be declared, asserted and raised, including
+# from helper functions
+
+class VaultError(ErrorCode):
+    InsufficientFunds = 'Not enough funds in the vault'
+    Unauthorized = 'Only the owner can withdraw'
+
+
+class Vault(Account):
+    owner: Pubkey
+    balance: u64
+
+
+def check_owner(owner: Signer, vault: Vault):
+    if owner.key() != vault.owner:
+        raise VaultError.Unauthorized
+
+
+@instruction
+def withdraw(owner: Signer, vault: Vault, amount: u64):
+    check_owner(owner, vault)
+    assert vault.balance >= amount, VaultError.InsufficientFunds
+    assert amount > 0, 'Amount must be positive'
+
+    vault.balance -= amount
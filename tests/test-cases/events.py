@@ -0,0 +1,79 @@
+# This test case checks events with nested fields, emitted from the logs and with CPIs, and
+# events stored in accounts
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Side(Enum):
+    BUY = 1
+    SELL = 2
+
+
+class Fill:
+    price: u64
+    size: u64
+
+    def __init__(self, price: u64, size: u64):
+        self.price = price
+        self.size = size
+
+
+class OrderFilled(Event):
+    trader: Pubkey
+    side: Side
+    first: Fill
+    fills: List[Fill]
+    prices: List[u64]
+    memo: Optional[str]
+
+    def __init__(
+        self,
+        trader: Pubkey,
+        side: Side,
+        first: Fill,
+        fills: List[Fill],
+        prices: List[u64],
+        memo: Optional[str]
+    ):
+        self.trader = trader
+        self.side = side
+        self.first = first
+        self.fills = fills
+        self.prices = prices
+        self.memo = memo
+
+
+class Trade(Event):
+    trader: Pubkey
+    price: u64
+
+    def __init__(self, trader: Pubkey, price: u64):
+        self.trader = trader
+        self.price = price
+
+
+class Market(Account):
+    last_trade: Trade
+
+
+@instruction
+def fill_order(trader: Signer, price: u64, size: u64):
+    first = Fill(price, size)
+    event = OrderFilled(trader.key(), Side.BUY, first, [first, Fill(price + 1, size)], [price, price + 1], None)
+    event.emit_cpi()
+
+
+@instruction
+def log_order(trader: Signer, price: u64):
+    fill = Fill(price, 1)
+    event = OrderFilled(trader.key(), Side.SELL, fill, [fill], [price], 'logged')
+    event.emit()
+
+
+@instruction
+def record_trade(trader: Signer, market: Market, price: u64):
+    trade = Trade(trader.key(), price)
+    market.last_trade = trade
+    trade.emit()
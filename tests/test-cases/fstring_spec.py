@@ -0,0 +1,36 @@
+# This test case checks that f-strings can use format specs and conversions
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Market(Account):
+    name: str
+    price: f64
+    volume: u64
+    flags: u8
+
+
+@instruction
+def report(signer: Signer, market: Market, offset: i64):
+    # Precision, with ints cast to floats
+    print(f'{market.name}: {market.price:.2f} ({market.volume:f})')
+
+    # Width, alignment and fill
+    print(f'[{market.name:>10}] [{market.name:*<8}] [{market.volume:^12}]')
+
+    # Signs and zero-padding
+    print(f'{offset:+} {offset:08} {market.price:+09.3f}')
+
+    # Hex, binary and octal, with and without prefixes
+    print(f'{market.flags:x} {market.flags:#x} {market.flags:X} {market.flags:08b} {market.flags:#o}')
+
+    # Negative numbers get a sign instead of being shown as their two's complement
+    print(f'{offset:x} {offset:#010b} {offset:+o} {offset:_X}')
+
+    # Thousands separators, padded after grouping
+    print(f'{market.volume:,} {market.price:,.2f} {market.volume:_x} {market.volume:>20,d}')
+
+    # Conversions, and escaped braces
+    print(f'{{{market.name!r}}} {market.name!s:.3} {market.volume + 1:d}')
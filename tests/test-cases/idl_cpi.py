@@ -0,0 +1,102 @@
+# This test case checks that instructions from Anchor IDLs (in both the current and the legacy
+# format) can be imported and called as CPIs, including ones with vec, option and array args and
+# args of the IDL's own structs and enums. An instruction with an unsupported arg (set_range, which
+# takes a tuple struct) doesn't stop the rest from being imported, and PDAs sign automatically
+
+from seahorse.prelude import *
+from idl.my_dex import swap, set_fee, add_routes, set_config, Config, FeeTier, PoolType
+from idl.legacy_vault import deposit_funds, set_limits, Limits
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+@instruction
+def trade(user: Signer, pool: UncheckedAccount, dex: Program, token_program: Program, amount: u64):
+    swap(
+        program=dex,
+        user=user,
+        pool=pool,
+        token_program=token_program,
+        amount_in=amount,
+        min_amount_out=amount * 99 // 100,
+        a_to_b=True
+    )
+
+
+@instruction
+def configure(admin: Signer, pool: UncheckedAccount, dex: Program, recipient: Pubkey):
+    set_fee(program=dex, admin=admin, pool=pool, fee=-5, recipient=recipient, memo='lower fees')
+
+
+@instruction
+def route(admin: Signer, pool: UncheckedAccount, dex: Program, hop: Pubkey):
+    hops = [hop, admin.key()]
+    add_routes(
+        program=dex,
+        admin=admin,
+        pool=pool,
+        hops=hops,
+        weights=[array(1, 2), array(3, 4)],
+        fallback=None,
+        salt=array(0, 1, 2, 3),
+        memo=[1, 2, 3]
+    )
+
+
+@instruction
+def reconfigure(admin: Signer, pool: UncheckedAccount, dex: Program, fee: u16, oracle: Pubkey):
+    config = Config(fee, PoolType.Stable, oracle)
+    if fee == 0:
+        config.oracle = None
+
+    set_config(
+        program=dex,
+        admin=admin,
+        pool=pool,
+        config=config,
+        tiers=[FeeTier.Free, FeeTier.Flat(u64(fee)), FeeTier.Custom(authority=admin.key(), bps=fee)]
+    )
+
+
+@instruction
+def limit(owner: Signer, vault_state: UncheckedAccount, vault_program: Program, max_deposit: u64):
+    set_limits(
+        program=vault_program,
+        owner=owner,
+        vault_state=vault_state,
+        limits=Limits(max_deposit, [max_deposit // 2])
+    )
+
+
+@instruction
+def deposit(
+    owner: Signer,
+    vault_state: UncheckedAccount,
+    vault_program: Program,
+    vault_authority: UncheckedAccount = seeds(['authority', owner])
+):
+    deposit_funds(
+        program=vault_program,
+        owner=owner,
+        vault_state=vault_state,
+        vault_authority=vault_authority,
+        amount=1000,
+        signer=vault_authority.signer_seeds()
+    )
+
+
+@instruction
+def deposit_as_pda(
+    owner: Signer,
+    vault_state: UncheckedAccount,
+    vault_program: Program,
+    vault_authority: UncheckedAccount = seeds(['authority', owner])
+):
+    # The vault authority is a PDA, so it signs with its seeds without being told to
+    deposit_funds(
+        program=vault_program,
+        owner=owner,
+        vault_state=vault_state,
+        vault_authority=vault_authority,
+        amount=500
+    )
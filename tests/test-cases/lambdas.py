@@ -0,0 +1,65 @@
+# This test case checks that lambdas can be passed to builtins, that sorted/min/max take keys, and
+# that errors raised from inside of them are propagated
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Bid:
+    bidder: Pubkey
+    amount: u64
+
+    def __init__(self, bidder: Pubkey, amount: u64):
+        self.bidder = bidder
+        self.amount = amount
+
+
+class Auction(Account):
+    bids: Array[Bid, 4]
+    leader: Pubkey
+
+
+class BidError(ErrorCode):
+    TooLow = 'This bid is too low'
+
+
+def weight(bid: Bid) -> u64:
+    return bid.amount * 2
+
+
+def checked_weight(bid: Bid, minimum: u64) -> u64:
+    if bid.amount < minimum:
+        raise BidError.TooLow
+
+    return weight(bid)
+
+
+@instruction
+def settle(signer: Signer, auction: Auction, minimum: u64, descending: bool):
+    # Lambdas in map and filter, capturing local variables
+    amounts = list(map(lambda bid: bid.amount, auction.bids))
+    valid = list(filter(lambda bid: bid.amount >= minimum, auction.bids))
+    weights = list(map(lambda bid: weight(bid), valid))
+
+    # Picking the top bidder
+    auction.leader = max(auction.bids, key=lambda bid: bid.amount).bidder
+
+    # min/max with several args or an iterable
+    lowest = min(amounts)
+    smallest = min(3, 1, 2, key=lambda x: 0 - x)
+
+    # sorted with a key and/or reverse
+    ranked = sorted(auction.bids, key=lambda bid: bid.amount, reverse=True)
+    ordered = sorted(amounts, reverse=descending)
+    by_parity = sorted(amounts, key=lambda amount: amount % 2)
+
+    print(weights, lowest, smallest, ranked[0].amount, ordered, by_parity)
+
+    # Functions that can raise an error, in every kind of closure
+    checked = list(map(lambda bid: checked_weight(bid, minimum), auction.bids))
+    heavy = list(filter(lambda bid: weight(bid) > minimum, auction.bids))
+    by_weight = sorted(auction.bids, key=weight)
+    heaviest = max(auction.bids, key=lambda bid: checked_weight(bid, minimum))
+
+    print(checked, len(heavy), by_weight[0].amount, heaviest.amount)
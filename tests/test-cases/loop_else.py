@@ -0,0 +1,43 @@
+# This test case checks that loops can have else blocks, which run when the loop isn't broken out of
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Registry(Account):
+    members: Array[Pubkey, 8]
+    count: u8
+
+
+@instruction
+def register(signer: Signer, registry: Registry):
+    # Find-or-fail with for/else
+    for member in registry.members:
+        if member == signer.key():
+            print('already registered')
+            break
+    else:
+        registry.count += 1
+
+    # while/else, with a continue and a nested loop whose break doesn't skip the else block
+    i = 0
+    while i < 8:
+        i += 1
+        if i % 2 == 0:
+            continue
+        for j in range(3):
+            if j == 1:
+                break
+        if i == 9:
+            break
+    else:
+        print('searched everything')
+
+    # A break in the else block breaks out of the outer loop
+    for n in range(4):
+        for m in range(n):
+            if m == 2:
+                break
+        else:
+            break
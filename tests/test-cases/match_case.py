@@ -0,0 +1,89 @@
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+# This test case checks that match statements work with enums, literals, tuples and captures
+
+class Shape(Enum):
+    Empty = 0
+    Circle: u64
+    Rect: Tuple[u64, u64]
+
+    @dataclass
+    class Triangle:
+        base: u64
+        height: u64
+
+
+class Color(Enum):
+    RED = 1
+    GREEN = 2
+    BLUE = 3
+
+
+class Canvas(Account):
+    shape: Shape
+    color: Color
+    area: u64
+
+
+@instruction
+def draw(canvas: Canvas, code: u8, flag: bool, name: str):
+    match canvas.shape:
+        case Shape.Empty:
+            canvas.area = 0
+        case Shape.Circle(r):
+            canvas.area = 3 * r * r
+        case Shape.Rect(w, h) if w == h:
+            canvas.area = w * w
+        case Shape.Rect(w, h):
+            canvas.area = w * h
+        case Shape.Triangle(base=b, height=h):
+            canvas.area = b * h // 2
+
+    match canvas.color:
+        case Color.RED | Color.GREEN:
+            print('warm')
+        case Color.BLUE:
+            print('cool')
+
+    match code, flag:
+        case (0, True):
+            print('zero')
+        case (1 | 2, _):
+            print('small')
+        case (n, False) if n > 100:
+            print(n)
+        case _:
+            pass
+
+    match name:
+        case 'circle':
+            canvas.shape = Shape.Circle(1)
+        case other:
+            print(other)
+
+
+@instruction
+def describe(canvas: Canvas, code: u8, flag: bool):
+    # Match-like text inside strings is left alone
+    note = '''
+match code:
+    case 0 if flag:
+'''
+    print(note)
+
+    # Headers may continue over several lines
+    match (
+        code,
+        flag,
+    ):
+        case (
+            0,
+            True,
+        ):
+            print('zero')
+        case n, False if n > 100:
+            print(n)
+        case _:
+            pass
@@ -0,0 +1,79 @@
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+# This test case checks that optional values can be used in accounts, params and locals
+
+class Point:
+    x: i64
+    y: i64
+
+    def __init__(self, x: i64, y: i64):
+        self.x = x
+        self.y = y
+
+
+class Vault(Account):
+    owner: Optional[Pubkey]
+    limit: Optional[u64]
+    history: Optional[List[u64]]
+    origin: Optional[Point]
+
+
+def halve(n: Optional[u64]) -> Optional[u64]:
+    if n is None:
+        return None
+    else:
+        return n // 2
+
+
+@instruction
+def configure(signer: Signer, vault: Vault, limit: Optional[u64]):
+    # `None` and plain values can be used as optionals
+    vault.limit = limit
+    if vault.owner is None:
+        vault.owner = signer.key()
+
+    owner = vault.owner
+    if owner is not None:
+        print(owner)
+
+    # Narrowing in the else block
+    half = halve(limit)
+    if half is None:
+        print('no limit')
+    else:
+        print(half + 1)
+
+    # Assigning to an optional in a deeper scope
+    count: Optional[u64] = None
+    if limit == 10:
+        count = 5
+    if count is not None:
+        print(count + 1)
+
+    vault.history = None
+    vault.origin = Point(1, 2)
+    origin = vault.origin
+    if origin is not None:
+        origin.x = 3
+
+    # Optional fields of any type can be compared with `None`
+    if vault.origin is None:
+        print('no origin')
+    has_origin = vault.origin is not None
+    print(has_origin)
+
+    # Assignments to a narrowed variable are seen after the if statement
+    total: Optional[u64] = limit
+    if total is not None:
+        total = total + 1
+        total += 2
+    print(total)
+
+    spare = halve(limit)
+    if spare is None:
+        print('no spare')
+    else:
+        spare = 0
+    print(spare)
@@ -0,0 +1,42 @@
+# This test case checks optional accounts, which are narrowed before being used
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Referral(Account):
+    referrer: Pubkey
+    count: u64
+
+
+@instruction
+def swap(
+    user: Signer,
+    source: TokenAccount,
+    destination: TokenAccount,
+    fee_account: Optional[TokenAccount],
+    referrer: Optional[Signer],
+    referral: Optional[Referral],
+    amount: u64
+):
+    fee = amount // 100
+    if fee_account is not None:
+        source.transfer(authority=user, to=fee_account, amount=fee)
+    else:
+        fee = 0
+
+    source.transfer(authority=user, to=destination, amount=amount - fee)
+
+    if referral is not None:
+        referral.count += 1
+        if referrer is not None:
+            referral.referrer = referrer.key()
+
+
+@instruction
+def log_extra(user: Signer, extra: Optional[UncheckedAccount]):
+    if extra is None:
+        print('no extra account')
+    else:
+        print(extra.key())
@@ -0,0 +1,34 @@
+# This test case checks that PDA params can be checked against their seeds, with or without a stored
+# bump
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Vault(Account):
+    owner: Pubkey
+    bump: u8
+    balance: u64
+
+
+@instruction
+def init_vault(owner: Signer, vault: Empty[Vault]):
+    bump = vault.bump()
+    vault = vault.init(payer=owner, seeds=['vault', owner])
+    vault.owner = owner.key()
+    vault.bump = bump
+
+
+@instruction
+def deposit(owner: Signer, amount: u64, vault: Vault = seeds(['vault', owner], bump=vault.bump)):
+    vault.balance += amount
+
+
+@instruction
+def withdraw(
+    owner: Signer,
+    amount: u64,
+    vault: Vault = (has_one('owner'), seeds(['vault', owner]))
+):
+    vault.balance -= amount
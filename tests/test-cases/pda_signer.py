@@ -0,0 +1,58 @@
+# This test case checks that PDAs sign CPIs with their seeds automatically, and that their seeds can
+# be used explicitly with signer_seeds()
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Treasury(Account):
+    bump: u8
+    mint: Pubkey
+
+
+@instruction
+def init_treasury(payer: Signer, treasury: Empty[Treasury], mint: Empty[TokenMint]):
+    bump = treasury.bump()
+    treasury = treasury.init(payer=payer, seeds=['treasury', payer])
+    mint = mint.init(payer=payer, seeds=['mint'], decimals=6, authority=treasury)
+
+    treasury.bump = bump
+    treasury.mint = mint.key()
+
+
+@instruction
+def reward(
+    payer: Signer,
+    mint: TokenMint,
+    to: TokenAccount,
+    amount: u64,
+    treasury: Treasury = seeds(['treasury', payer], bump=treasury.bump)
+):
+    mint.mint(authority=treasury, to=to, amount=amount)
+
+
+@instruction
+def sweep(
+    vault: TokenAccount,
+    to: TokenAccount,
+    vault_authority: UncheckedAccount = seeds(['vault', vault.mint()])
+):
+    vault.transfer(authority=vault_authority, to=to, amount=vault.amount())
+
+
+@instruction
+def burn_from(payer: Signer, mint: TokenMint, holder: TokenAccount, treasury: Treasury):
+    mint.burn(authority=payer, holder=holder, amount=1)
+
+
+@instruction
+def notify(
+    payer: Signer,
+    program: Program,
+    treasury: Treasury = seeds(['treasury', payer], bump=treasury.bump)
+):
+    program.invoke(
+        accounts=[CpiAccount(treasury, seeds=treasury.signer_seeds())],
+        data=[0]
+    )
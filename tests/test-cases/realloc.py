@@ -0,0 +1,32 @@
+# This test case checks that program accounts can be reallocated, both with a param constraint and
+# with a method call
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Roster(Account):
+    owner: Pubkey
+    members: List[Pubkey]
+
+
+@instruction
+def init_roster(owner: Signer, roster: Empty[Roster]):
+    roster = roster.init(payer=owner, padding=4 + 32)
+    roster.owner = owner.key()
+
+
+@instruction
+def add_member(
+    owner: Signer,
+    member: Pubkey,
+    roster: Roster = (has_one('owner'), realloc(8 + 32 + 4 + 32 * (len(roster.members) + 1), owner))
+):
+    roster.members.append(member)
+
+
+@instruction
+def resize_roster(owner: Signer, roster: Roster, capacity: u64):
+    roster.realloc(8 + 32 + 4 + 32 * capacity, owner, zero=True)
+    print(f'Roster can now hold {capacity} members')
@@ -0,0 +1,23 @@
+# This test case checks instruction params that take the remaining accounts as typed lists
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+class Vault(Account):
+    amount: u64
+
+@instruction
+def payout(payer: Signer, remaining: List[UncheckedAccount]):
+    for account in remaining:
+        payer.transfer_lamports(to=account, amount=10)
+
+@instruction
+def bump(payer: Signer, vaults: List[Vault], amount: u64):
+    for vault in vaults:
+        vault.amount += amount
+
+@instruction
+def sweep(owner: Signer, to: TokenAccount, sources: List[TokenAccount]):
+    for source in sources:
+        source.transfer(authority=owner, to=to, amount=source.amount())
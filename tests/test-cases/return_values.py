@@ -0,0 +1,54 @@
+# This test case checks instructions that return values to the client
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Pool(Account):
+    reserve_a: u64
+    reserve_b: u64
+    owner: Optional[Pubkey]
+
+
+class Reserves:
+    a: u64
+    b: u64
+
+    def __init__(self, a: u64, b: u64):
+        self.a = a
+        self.b = b
+
+
+class SwapQuote:
+    amount_out: u64
+    fee: u64
+
+    def __init__(self, amount_out: u64, fee: u64):
+        self.amount_out = amount_out
+        self.fee = fee
+
+
+def amount_out(pool: Pool, amount_in: u64) -> u64:
+    return pool.reserve_b * amount_in // (pool.reserve_a + amount_in)
+
+
+@instruction
+def quote(pool: Pool, amount_in: u64) -> u64:
+    return amount_out(pool, amount_in)
+
+
+@instruction
+def quote_with_fee(pool: Pool, amount_in: u64) -> SwapQuote:
+    fee = amount_in // 100
+    return SwapQuote(amount_out(pool, amount_in - fee), fee)
+
+
+@instruction
+def reserves(pool: Pool) -> Reserves:
+    return Reserves(pool.reserve_a, pool.reserve_b)
+
+
+@instruction
+def owner(pool: Pool) -> Optional[Pubkey]:
+    return pool.owner
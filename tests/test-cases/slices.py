@@ -0,0 +1,26 @@
+from seahorse.prelude import *
+
+declare_id('4SEMJzX6o2YQNws7yrsfUdjJCR4B5Z3GyR2Pj7UgzDy2')
+
+# This test case checks that lists, arrays and strings can be sliced, including with negative
+# indices and steps
+
+class Buffer(Account):
+    data: Array[u8, 8]
+
+
+@instruction
+def slices(payer: Signer, buffer: Buffer, n: u64):
+    xs = [1, 2, 3, 4, 5]
+    head = xs[:2]
+    tail = xs[-2:]
+    rev = xs[::-1]
+    mid = xs[1:n]
+    evens = xs[::2]
+
+    s = 'seahorse'
+    prefix = s[:3]
+    suffix = s[-5:]
+
+    data = buffer.data[2:6]
+    print(head, tail, rev, mid, evens, prefix, suffix, data, len(buffer.data[:n]))
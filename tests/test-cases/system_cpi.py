@@ -0,0 +1,37 @@
+# This test case checks the system program CPIs for creating, allocating and assigning accounts
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+@instruction
+def create(payer: Signer, account: Signer, space: u64, owner: Pubkey):
+    payer.create_account(account=account, lamports=1000000, space=space, owner=owner)
+
+
+@instruction
+def create_pda(
+    payer: Signer,
+    owner: Pubkey,
+    account: UncheckedAccount = seeds(['data', payer])
+):
+    payer.create_account(account=account, lamports=1000000, space=64, owner=owner)
+
+
+@instruction
+def create_with_seed(payer: Signer, account: UncheckedAccount, base: Signer, owner: Pubkey):
+    payer.create_account_with_seed(
+        account=account,
+        base=base,
+        seed='data',
+        lamports=1000000,
+        space=64,
+        owner=owner
+    )
+
+
+@instruction
+def allocate_and_assign(payer: Signer, account: Signer, owner: Pubkey):
+    payer.allocate(account=account, space=128)
+    payer.assign(account=account, owner=owner)
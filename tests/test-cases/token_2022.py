@@ -0,0 +1,54 @@
+# This test case checks that Token-2022 mints and accounts can be initialized and used, and that
+# their transfers are checked
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+@instruction
+def init_mint(payer: Signer, mint: Empty[Token2022Mint]):
+    mint.init(payer=payer, seeds=['mint'], decimals=6, authority=payer)
+
+
+@instruction
+def init_accounts(
+    payer: Signer,
+    mint: Token2022Mint,
+    vault: Empty[Token2022Account],
+    wallet: Empty[Token2022Account]
+):
+    vault.init(payer=payer, seeds=['vault', mint], mint=mint, authority=payer)
+    wallet.init(payer=payer, mint=mint, authority=payer, associated=True)
+
+
+@instruction
+def mint_and_burn(payer: Signer, mint: Token2022Mint, to: Token2022Account, amount: u64):
+    mint.mint(authority=payer, to=to, amount=amount)
+    mint.burn(authority=payer, holder=to, amount=amount // 2)
+
+    print(mint.supply(), mint.decimals(), to.amount())
+
+
+@instruction
+def send(
+    payer: Signer,
+    mint: Token2022Mint,
+    source: Token2022Account,
+    dest: Token2022Account,
+    amount: u64
+):
+    fee = mint.transfer_fee(amount)
+    assert fee <= mint.maximum_transfer_fee(), 'Fee too high'
+    print(mint.transfer_fee_basis_points())
+
+    metadata = mint.metadata_pointer()
+    if metadata is not None:
+        print(f'Metadata: {metadata}')
+
+    source.transfer(authority=payer, to=dest, amount=amount + fee, mint=mint)
+
+
+@instruction
+def close(payer: Signer, account: Token2022Account):
+    account.close(to=payer, authority=payer)
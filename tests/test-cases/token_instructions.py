@@ -0,0 +1,50 @@
+# This test case checks the SPL token instructions beyond transfers, minting and burning
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+@instruction
+def delegate(owner: Signer, account: TokenAccount, delegate: Signer, amount: u64):
+    account.approve(authority=owner, delegate=delegate, amount=amount)
+
+
+@instruction
+def undelegate(owner: Signer, account: TokenAccount):
+    account.revoke(authority=owner)
+
+
+@instruction
+def freeze(authority: Signer, mint: TokenMint, account: TokenAccount, thaw: bool):
+    if thaw:
+        mint.thaw(authority=authority, account=account)
+    else:
+        mint.freeze(authority=authority, account=account)
+
+
+@instruction
+def hand_over(owner: Signer, mint: TokenMint, account: TokenAccount, new_owner: Pubkey):
+    account.set_authority(authority=owner, new_authority=new_owner)
+    account.set_authority(authority=owner, new_authority=new_owner, authority_type='close_account')
+    mint.set_authority(authority=owner, new_authority=None)
+    mint.set_authority(authority=owner, new_authority=new_owner, authority_type='freeze_account')
+
+
+@instruction
+def send_checked(owner: Signer, mint: TokenMint, source: TokenAccount, dest: TokenAccount, amount: u64):
+    source.transfer_checked(authority=owner, to=dest, amount=amount, mint=mint)
+
+
+@instruction
+def wrap(payer: Signer, wrapped: TokenAccount, amount: u64):
+    payer.transfer_lamports(to=wrapped, amount=amount)
+    wrapped.sync_native()
+
+
+@instruction
+def delegate_2022(owner: Signer, account: Token2022Account, mint: Token2022Mint, delegate: Signer):
+    account.approve(authority=owner, delegate=delegate, amount=100)
+    account.revoke(authority=owner)
+    mint.freeze(authority=owner, account=account)
+    account.set_authority(authority=owner, new_authority=delegate.key())
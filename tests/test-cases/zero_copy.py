@@ -0,0 +1,45 @@
+# This test case checks that zero-copy accounts are loaded in place
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+@zero_copy
+class OrderBook(Account):
+    bids: Array[u64, 256]
+    asks: Array[u64, 256]
+    levels: Array[Array[u32, 4], 8]
+    count: u32
+    side: u8
+    _padding: Array[u8, 3]
+    authority: Pubkey
+
+
+@instruction
+def init_book(authority: Signer, book: Empty[OrderBook]):
+    book = book.init(payer=authority, seeds=['book', authority])
+    book.authority = authority.key()
+
+
+@instruction
+def place_bid(authority: Signer, book: OrderBook, price: u64):
+    assert book.authority == authority.key(), 'Unauthorized'
+
+    i = book.count
+    book.bids[i] = price
+    book.levels[0][1] += 1
+    book.count += 1
+
+    best = 0
+    for bid in book.bids:
+        best = max(best, bid)
+    print(f'Best bid: {best}, {len(book.asks)} asks')
+
+
+@instruction
+def clear_book(
+    authority: Signer,
+    book: OrderBook = (has_one('authority'), constraint(book.count > 0))
+):
+    book.count = 0